@@ -81,4 +81,173 @@ pub enum GovError {
 
     #[msg("Hook execution failed")]
     HookExecutionFailed,
+
+    // Sharded tally errors
+    #[msg("Invalid shard count (max 16)")]
+    InvalidShardCount,
+
+    #[msg("Tally shard does not match this election or voter")]
+    InvalidTallyShard,
+
+    #[msg("Election tallies votes in shards, use cast_sharded_vote")]
+    ElectionIsSharded,
+
+    #[msg("Election does not use tally shards")]
+    ElectionNotSharded,
+
+    #[msg("Tally shard has already been merged")]
+    ShardAlreadyMerged,
+
+    #[msg("Not all tally shards have been merged")]
+    ShardsNotMerged,
+
+    // Election lifecycle errors
+    #[msg("Election has not ended yet")]
+    ElectionNotEnded,
+
+    #[msg("Election reuses another election's voter registry, register there")]
+    SharedVoterRegistry,
+
+    #[msg("Clones of a compression election must reuse its voter registry")]
+    CompressedRegistryNotCloned,
+
+    // Ballot group errors
+    #[msg("Election belongs to a ballot group, vote through the group")]
+    ElectionInBallotGroup,
+
+    #[msg("Invalid ballot group (2-8 elections sharing one voter registry)")]
+    InvalidBallotGroup,
+
+    // Challenge errors
+    #[msg("Results are not final (challenge window open or challenges unresolved)")]
+    OutcomeNotFinal,
+
+    #[msg("Challenge window is not open")]
+    ChallengeWindowClosed,
+
+    #[msg("Only the arbiter, or the authority without one, can resolve challenges")]
+    UnauthorizedResolver,
+
+    #[msg("A challenge window requires a challenge bond")]
+    ChallengeBondRequired,
+
+    #[msg("Election has been cancelled")]
+    ElectionCancelled,
+
+    // Voting method errors
+    #[msg("Ballot does not match the election's voting method")]
+    BallotMethodMismatch,
+
+    #[msg("Too many candidates approved")]
+    TooManyApprovals,
+
+    #[msg("Invalid voting method configuration")]
+    InvalidVotingMethod,
+
+    #[msg("Voting method is not supported by this instruction")]
+    UnsupportedVotingMethod,
+
+    #[msg("Candidates can't change once shards, candidate amounts, ranked ballots or a pairwise matrix exist")]
+    CandidatesInUse,
+
+    #[msg("A proposal election keeps its proposal options")]
+    ProposalOptionsFixed,
+
+    // Tabulation errors
+    #[msg("Ranked ballots account is required for this voting method")]
+    RankedBallotsRequired,
+
+    #[msg("Tabulation has not completed")]
+    TabulationIncomplete,
+
+    #[msg("Invalid number of seats for this voting method")]
+    InvalidSeatCount,
+
+    #[msg("Ballot costs more voice credits than the voter has")]
+    InsufficientCredits,
+
+    #[msg("Score is outside the configured range")]
+    ScoreOutOfRange,
+
+    #[msg("Pairwise matrix account is required for this voting method")]
+    PairwiseMatrixRequired,
+
+    #[msg("Ballot allocates more votes than the voter's allowance")]
+    AllowanceExceeded,
+
+    #[msg("Ballot picks more candidates than there are seats")]
+    TooManyChoices,
+
+    // Proposal errors
+    #[msg("Threshold must be at most 10000 basis points")]
+    InvalidThreshold,
+
+    // Conviction errors
+    #[msg("Candidate amounts must match the candidates")]
+    InvalidCandidateAmounts,
+
+    #[msg("Invalid conviction configuration")]
+    InvalidConvictionConfig,
+
+    #[msg("Conviction state account is required for this voting method")]
+    ConvictionStateRequired,
+
+    // Revoting errors
+    #[msg("Election allows revoting; votes must be cast individually")]
+    RevotingEnabled,
+
+    #[msg("Voter ballot account is required when revoting is allowed")]
+    VoterBallotRequired,
+
+    #[msg("Election does not allow revoting")]
+    RevotingDisabled,
+
+    // Weight errors
+    #[msg("Voter weight must be greater than zero")]
+    InvalidVoterWeight,
+
+    // Delegation errors
+    #[msg("Delegation does not apply to this election")]
+    InvalidDelegationScope,
+
+    #[msg("Cannot delegate to yourself")]
+    SelfDelegation,
+
+    #[msg("Delegation does not lead to the voter")]
+    InvalidDelegationChain,
+
+    #[msg("Delegation chain is too deep")]
+    DelegationTooDeep,
+
+    #[msg("Delegator has already voted or been counted")]
+    DelegatorAlreadyVoted,
+
+    // Rationale errors
+    #[msg("Rationale must be non-empty and within its length limit")]
+    InvalidRationale,
+
+    // Token weighting errors
+    #[msg("Election is not token-weighted")]
+    NotTokenWeighted,
+
+    #[msg("Election is token-weighted; votes must be cast individually with the voter's token weight")]
+    TokenWeightedElection,
+
+    #[msg("Token snapshot is not valid for this operation")]
+    InvalidTokenSnapshot,
+
+    #[msg("Token account must be owned by the voter and hold the election's mint")]
+    InvalidTokenAccount,
+
+    #[msg("Token voter weight account is required for token-weighted elections")]
+    TokenVoterWeightRequired,
+
+    #[msg("Token amount must be greater than zero")]
+    InvalidTokenAmount,
+
+    #[msg("Escrowed tokens are locked while the voter's vote counts")]
+    EscrowLocked,
+
+    #[msg("Token weighting can't change once the escrow vault holds deposits")]
+    TokenWeightingLocked,
 }
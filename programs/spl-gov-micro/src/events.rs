@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+use crate::state::VoteRationale;
+
+/// A voter published why they voted
+/// Indexers store the rationale in the vote archive next to the VoteRecord.
+#[event]
+pub struct VoteRationaleSubmitted {
+    /// The election the vote was cast in
+    pub election: Pubkey,
+
+    /// The voter who cast the vote
+    pub voter: Pubkey,
+
+    /// Why the voter voted this way
+    pub rationale: VoteRationale,
+
+    /// Unix timestamp when the vote was cast
+    pub timestamp: i64,
+}
@@ -0,0 +1,264 @@
+//! Ballot Groups
+//!
+//! A shareholder meeting votes on several resolutions at once, each its own
+//! Election. A BallotGroup links those elections so that:
+//!
+//! - Eligibility is checked once against the shared voter registry
+//! - One group-level nullifier covers every election in the group
+//! - Ballots for all elections are recorded atomically in one instruction
+//!
+//! Member elections reject individual votes once they join a group.
+//!
+//! All members must share one voter registry: in practice clones made with
+//! reuse_voter_registry of an election that has ended. Voters register while
+//! that election runs; registration closes when it ends.
+
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::instructions::cast_vote::{verify_voter_eligibility, VoterProof};
+use crate::utils::realloc::reserve_space;
+use crate::utils::tally::{validate_ballot, apply_ballot, check_credit_budget};
+
+#[derive(Accounts)]
+#[instruction(group_id: u64)]
+pub struct CreateBallotGroup<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = BallotGroup::space(BallotGroup::INITIAL_NULLIFIERS),
+        seeds = [b"ballot_group", authority.key().as_ref(), &group_id.to_le_bytes()],
+        bump
+    )]
+    pub ballot_group: Account<'info, BallotGroup>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    // NOTE: Remaining accounts are the member elections (mutable), in ballot order
+}
+
+/// Link several elections of the same authority into a ballot group
+///
+/// Can only be called before the member elections start. Every member must
+/// have the same voter_registry (clones that reuse the registry of one ended
+/// election), since one registration and one proof cover the whole group.
+pub fn create_ballot_group<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateBallotGroup<'info>>,
+    _group_id: u64,
+) -> Result<()> {
+    let ballot_group = &mut ctx.accounts.ballot_group;
+    let authority_key = ctx.accounts.authority.key();
+    let group_key = ballot_group.key();
+    let clock = Clock::get()?;
+
+    require!(
+        ctx.remaining_accounts.len() >= 2 && ctx.remaining_accounts.len() <= BallotGroup::MAX_ELECTIONS,
+        GovError::InvalidBallotGroup
+    );
+
+    let mut elections = Vec::with_capacity(ctx.remaining_accounts.len());
+    let mut voter_registry = Pubkey::default();
+
+    for (idx, election_info) in ctx.remaining_accounts.iter().enumerate() {
+        require!(election_info.is_writable, GovError::InvalidBallotGroup);
+
+        let mut election = Account::<Election>::try_from(election_info)?;
+
+        require!(
+            election.authority == authority_key,
+            GovError::Unauthorized
+        );
+
+        require!(
+            election.effective_status(&clock) == ElectionStatus::Pending,
+            GovError::ElectionAlreadyStarted
+        );
+
+        require!(
+            election.ballot_group.is_none() && !election.is_sharded(),
+            GovError::InvalidBallotGroup
+        );
+
+        require!(
+            !election.allow_revoting,
+            GovError::RevotingEnabled
+        );
+
+        require!(
+            !election.is_token_weighted(),
+            GovError::TokenWeightedElection
+        );
+
+        require!(
+            election.voting_method.tallies_on_election(),
+            GovError::UnsupportedVotingMethod
+        );
+
+        require!(
+            !elections.contains(&election.key()),
+            GovError::InvalidBallotGroup
+        );
+
+        // Every member must accept the same voters
+        if idx == 0 {
+            voter_registry = election.voter_registry;
+        }
+
+        require!(
+            election.voter_registry == voter_registry,
+            GovError::InvalidBallotGroup
+        );
+
+        election.ballot_group = Some(group_key);
+        election.exit(&crate::ID)?;
+
+        elections.push(election.key());
+    }
+
+    ballot_group.authority = authority_key;
+    ballot_group.elections = elections;
+    ballot_group.voter_registry = voter_registry;
+    ballot_group.used_nullifiers = Vec::new();
+    ballot_group.bump = ctx.bumps.ballot_group;
+
+    msg!("Ballot group created with {} elections", ballot_group.elections.len());
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CastGroupBallot<'info> {
+    #[account(mut)]
+    pub ballot_group: Account<'info, BallotGroup>,
+
+    /// Voter registration account (only required for legacy mode)
+    #[account(
+        seeds = [
+            b"voter_registration",
+            ballot_group.voter_registry.as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub voter_registration: Option<Account<'info, VoterRegistration>>,
+
+    /// Pays for growing the group when its nullifiers run out of room
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    /// CHECK: Attestation account (optional, only for compression mode proof verification)
+    pub attestation: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+
+    // NOTE: Remaining accounts are the member elections (mutable), in group order
+}
+
+/// Cast ballots for every election of a ballot group atomically
+///
+/// `ballots[i]` is the ballot for `ballot_group.elections[i]`. A full group
+/// grows by BallotGroup::NULLIFIER_GROWTH nullifiers, paid by the voter.
+pub fn cast_group_ballot<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CastGroupBallot<'info>>,
+    ballots: Vec<Ballot>,
+    merkle_proof: Vec<[u8; 32]>,
+    leaf_index: Option<u32>,
+    registered_at: Option<i64>,
+    voter_weight: Option<u64>,
+) -> Result<()> {
+    let ballot_group = &mut ctx.accounts.ballot_group;
+    let voter_key = ctx.accounts.voter.key();
+    let clock = Clock::get()?;
+
+    require!(
+        ballots.len() == ballot_group.elections.len() &&
+        ctx.remaining_accounts.len() == ballot_group.elections.len(),
+        GovError::InvalidBallotGroup
+    );
+
+    let mut elections = Vec::with_capacity(ctx.remaining_accounts.len());
+    for (election_info, expected) in ctx.remaining_accounts.iter().zip(ballot_group.elections.iter()) {
+        require!(election_info.is_writable, GovError::InvalidBallotGroup);
+
+        let election = Account::<Election>::try_from(election_info)?;
+
+        require!(
+            election.key() == *expected,
+            GovError::InvalidBallotGroup
+        );
+
+        elections.push(election);
+    }
+
+    // Members must still share one voter merkle root for the single proof
+    let first = &elections[0];
+    require!(
+        elections.iter().all(|election| election.voter_merkle_root == first.voter_merkle_root),
+        GovError::InvalidBallotGroup
+    );
+
+    // One eligibility check for the whole group
+    let weight = verify_voter_eligibility(
+        first,
+        voter_key,
+        ctx.accounts.voter_registration.as_ref(),
+        ctx.accounts.attestation.as_ref().map(|attestation| attestation.key()),
+        VoterProof {
+            merkle_proof: &merkle_proof,
+            leaf_index,
+            registered_at,
+            weight: voter_weight,
+        },
+    )?;
+
+    // One nullifier for the whole group
+    let nullifier = VoteNullifier::new(&voter_key, &ballot_group.key(), 0);
+
+    require!(
+        !ballot_group.used_nullifiers.contains(&nullifier.nullifier_hash),
+        GovError::AlreadyVoted
+    );
+
+    let nullifier_count = ballot_group.used_nullifiers.len();
+    reserve_space(
+        &ballot_group.to_account_info(),
+        &ctx.accounts.voter.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        BallotGroup::space(nullifier_count + 1),
+        BallotGroup::space(nullifier_count + BallotGroup::NULLIFIER_GROWTH),
+    )?;
+
+    for (election, ballot) in elections.iter_mut().zip(ballots.iter()) {
+        // Verify election is accepting votes
+        election.require_active(&clock)?;
+
+        // Group ballots only update vote_counts
+        require!(
+            election.voting_method.tallies_on_election(),
+            GovError::UnsupportedVotingMethod
+        );
+
+        // Verify ballot is valid for this election
+        validate_ballot(&election.voting_method, election.candidates.len(), election.seats, ballot)?;
+        check_credit_budget(&election.voting_method, election.seats, ballot, weight)?;
+
+        // Persist the derived status
+        election.status = election.effective_status(&clock);
+
+        // Record the vote
+        let voting_method = election.voting_method;
+        apply_ballot(&voting_method, &mut election.vote_counts, ballot, weight)?;
+        election.count_ballot(ballot, weight)?;
+
+        election.exit(&crate::ID)?;
+    }
+
+    ballot_group.used_nullifiers.push(nullifier.nullifier_hash);
+
+    msg!("Group ballot cast in {} elections by voter {}", elections.len(), voter_key);
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct SetCandidateAmounts<'info> {
+    #[account(mut)]
+    pub election: Account<'info, Election>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Set the amount each candidate requests and the budget they share
+///
+/// Can only be set before election starts.
+pub fn handler(
+    ctx: Context<SetCandidateAmounts>,
+    amounts: Vec<u64>,
+    budget_cap: u64,
+) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    let clock = Clock::get()?;
+
+    require!(
+        election.authority == ctx.accounts.authority.key(),
+        GovError::Unauthorized
+    );
+
+    require!(
+        election.effective_status(&clock) == ElectionStatus::Pending,
+        GovError::ElectionAlreadyStarted
+    );
+
+    require!(
+        amounts.len() == election.candidates.len(),
+        GovError::InvalidCandidateAmounts
+    );
+
+    election.candidate_amounts = amounts;
+    election.budget_cap = budget_cap;
+
+    msg!("Candidate amounts set: {:?}, budget cap: {}", election.candidate_amounts, budget_cap);
+
+    Ok(())
+}
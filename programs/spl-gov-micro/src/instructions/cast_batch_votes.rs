@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::errors::*;
+use crate::events::VoteRationaleSubmitted;
+use crate::utils::tally::{validate_ballot, apply_ballot, check_credit_budget};
 use crate::VoteInput;
 
 #[derive(Accounts)]
@@ -68,54 +70,87 @@ pub fn handler<'info>(
         );
 
         require!(
-            voter_registration.election == vote_input.election,
+            voter_registration.election == election.voter_registry,
             GovError::NotRegistered
         );
 
-        // Verify election is active
+        // Verify election is accepting votes
+        election.require_active(&clock)?;
+
+        // Sharded elections record votes in their tally shards
         require!(
-            clock.unix_timestamp >= election.start_time,
-            GovError::ElectionNotStarted
+            !election.is_sharded(),
+            GovError::ElectionIsSharded
         );
 
+        // Grouped elections only take votes through their ballot group
         require!(
-            clock.unix_timestamp <= election.end_time,
-            GovError::ElectionEnded
+            election.ballot_group.is_none(),
+            GovError::ElectionInBallotGroup
         );
 
-        // Verify choice is valid
+        // Revoting needs each voter's ballot record
         require!(
-            (vote_input.choice as usize) < election.candidates.len(),
-            GovError::InvalidChoice
+            !election.allow_revoting,
+            GovError::RevotingEnabled
         );
 
-        // Check nullifier not used
+        // Token weights are only counted by cast_vote
         require!(
-            !nullifier_set.used_nullifiers.contains(&vote_input.nullifier),
-            GovError::AlreadyVoted
+            !election.is_token_weighted(),
+            GovError::TokenWeightedElection
+        );
+
+        // Batches only update vote_counts
+        require!(
+            election.voting_method.tallies_on_election(),
+            GovError::UnsupportedVotingMethod
         );
 
-        // Update election status if needed
-        if election.status == ElectionStatus::Pending && clock.unix_timestamp >= election.start_time {
-            election.status = ElectionStatus::Active;
+        // Verify ballot is valid for this election
+        validate_ballot(&election.voting_method, election.candidates.len(), election.seats, &vote_input.ballot)?;
+
+        if let Some(rationale) = &vote_input.rationale {
+            rationale.validate()?;
         }
+        check_credit_budget(&election.voting_method, election.seats, &vote_input.ballot, voter_registration.weight)?;
 
-        // Record the vote
-        election.vote_counts[vote_input.choice as usize] = election.vote_counts[vote_input.choice as usize]
-            .checked_add(1)
-            .ok_or(GovError::ArithmeticOverflow)?;
+        // Same nullifier as cast_vote, so votes counted through a delegate are seen
+        require!(
+            nullifier_set.election == election.key(),
+            GovError::InvalidBatchVote
+        );
+
+        let nullifier = VoteNullifier::new(&ctx.accounts.voter.key(), &election.key(), 0);
+        require!(
+            !nullifier_set.used_nullifiers.contains(&nullifier.nullifier_hash),
+            GovError::AlreadyVoted
+        );
 
-        election.total_votes = election.total_votes
-            .checked_add(1)
-            .ok_or(GovError::ArithmeticOverflow)?;
+        // Persist the derived status
+        election.status = election.effective_status(&clock);
+
+        // Record the vote
+        let voting_method = election.voting_method;
+        apply_ballot(&voting_method, &mut election.vote_counts, &vote_input.ballot, voter_registration.weight)?;
+        election.count_ballot(&vote_input.ballot, voter_registration.weight)?;
 
         // Mark nullifier as used
-        nullifier_set.used_nullifiers.push(vote_input.nullifier);
+        nullifier_set.used_nullifiers.push(nullifier.nullifier_hash);
 
         // Save changes
         election.exit(&crate::ID)?;
         nullifier_set.exit(&crate::ID)?;
 
+        if let Some(rationale) = &vote_input.rationale {
+            emit!(VoteRationaleSubmitted {
+                election: vote_input.election,
+                voter: ctx.accounts.voter.key(),
+                rationale: rationale.clone(),
+                timestamp: clock.unix_timestamp,
+            });
+        }
+
         msg!("Batch vote {}/{} processed for election {}", idx + 1, votes.len(), vote_input.election);
     }
 
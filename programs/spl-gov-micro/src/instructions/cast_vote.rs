@@ -1,7 +1,13 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::errors::*;
+use crate::events::VoteRationaleSubmitted;
 use crate::utils::compression::{CompressedVoterData, verify_compressed_voter_proof};
+use crate::utils::delegation::delegation_depths;
+use crate::instructions::ranked_ballots::reserve_ranking;
+use crate::utils::tally::{
+    validate_ballot, apply_ballot, remove_ballot, apply_score_ballots, check_credit_budget,
+};
 
 #[derive(Accounts)]
 pub struct CastVote<'info> {
@@ -10,10 +16,11 @@ pub struct CastVote<'info> {
 
     /// Voter registration account (only required for legacy mode)
     /// In compression mode, voter eligibility is verified via merkle proof
+    /// Registrations live under the election's voter registry
     #[account(
         seeds = [
             b"voter_registration",
-            election.key().as_ref(),
+            election.voter_registry.as_ref(),
             voter.key().as_ref()
         ],
         bump
@@ -29,6 +36,41 @@ pub struct CastVote<'info> {
     )]
     pub nullifier_set: Account<'info, NullifierSet>,
 
+    /// Aggregated ranked ballots (only required for ranked voting methods)
+    #[account(
+        mut,
+        seeds = [b"ranked_ballots", election.key().as_ref()],
+        bump = ranked_ballots.bump
+    )]
+    pub ranked_ballots: Option<Account<'info, RankedBallots>>,
+
+    /// Pairwise preference matrix (only required for Condorcet)
+    #[account(
+        mut,
+        seeds = [b"pairwise_matrix", election.key().as_ref()],
+        bump = pairwise_matrix.bump
+    )]
+    pub pairwise_matrix: Option<Account<'info, PairwiseMatrix>>,
+
+    /// Voter's current ballot (only required when the election allows revoting)
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = VoterBallot::SIZE,
+        seeds = [b"voter_ballot", election.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub voter_ballot: Option<Account<'info, VoterBallot>>,
+
+    /// Voter's token weight (only required for token-weighted elections)
+    /// Proven from the Merkle snapshot or deposited in escrow before voting
+    #[account(
+        mut,
+        seeds = [b"token_weight", election.key().as_ref(), voter.key().as_ref()],
+        bump = token_voter_weight.bump
+    )]
+    pub token_voter_weight: Option<Account<'info, TokenVoterWeight>>,
+
     #[account(mut)]
     pub voter: Signer<'info>,
 
@@ -38,68 +80,322 @@ pub struct CastVote<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(
-    ctx: Context<CastVote>,
-    choice: u8,
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CastVote<'info>>,
+    ballot: Ballot,
     merkle_proof: Vec<[u8; 32]>,
     leaf_index: Option<u32>,
     registered_at: Option<i64>,
+    voter_weight: Option<u64>,
+    rationale: Option<VoteRationale>,
 ) -> Result<()> {
     let election = &mut ctx.accounts.election;
     let nullifier_set = &mut ctx.accounts.nullifier_set;
     let clock = Clock::get()?;
 
-    // Verify election is active
+    // Verify election is accepting votes
+    election.require_active(&clock)?;
+
+    // Verify ballot is valid for this election
+    validate_ballot(&election.voting_method, election.candidates.len(), election.seats, &ballot)?;
+
+    if let Some(rationale) = &rationale {
+        rationale.validate()?;
+    }
+
+    // Sharded elections record votes in their tally shards
     require!(
-        clock.unix_timestamp >= election.start_time,
-        GovError::ElectionNotStarted
+        !election.is_sharded(),
+        GovError::ElectionIsSharded
     );
 
+    // Grouped elections only take votes through their ballot group
     require!(
-        clock.unix_timestamp <= election.end_time,
-        GovError::ElectionEnded
+        election.ballot_group.is_none(),
+        GovError::ElectionInBallotGroup
     );
 
-    // Check if election has been manually ended or cancelled
-    require!(
-        election.status != ElectionStatus::Ended,
-        GovError::ElectionEnded
+    let voter_key = ctx.accounts.voter.key();
+    let election_key = election.key();
+
+    // Verify voter eligibility based on compression mode
+    let weight = verify_voter_eligibility(
+        election,
+        voter_key,
+        ctx.accounts.voter_registration.as_ref(),
+        ctx.accounts.attestation.as_ref().map(|attestation| attestation.key()),
+        VoterProof {
+            merkle_proof: &merkle_proof,
+            leaf_index,
+            registered_at,
+            weight: voter_weight,
+        },
+    )?;
+
+    // Token-weighted elections count the voter's token balance instead
+    let weight = if let Some(token_weighting) = election.token_weighting {
+        require!(
+            ctx.remaining_accounts.is_empty(),
+            GovError::TokenWeightedElection
+        );
+
+        token_weight(&token_weighting, ctx.accounts.token_voter_weight.as_mut())?
+    } else {
+        weight
+    };
+
+    // Delegations chained to the voter (liquid democracy) add their weight
+    let delegations = ctx.remaining_accounts
+        .iter()
+        .map(Account::<Delegation>::try_from)
+        .collect::<Result<Vec<_>>>()?;
+    let delegated_weight = delegated_weight(election, &voter_key, &delegations)?;
+
+    // Create nullifier for this vote (same for both modes)
+    let nullifier = VoteNullifier::new(
+        &voter_key,
+        &election_key,
+        0 // nonce - for MVP we use 0, in production this could be dynamic
     );
 
+    // Initialize nullifier set if needed
+    if nullifier_set.election == Pubkey::default() {
+        nullifier_set.election = election_key;
+        nullifier_set.used_nullifiers = Vec::new();
+        nullifier_set.bump = ctx.bumps.nullifier_set;
+    }
+
+    // Check if voter has already voted (a recast when revoting is allowed)
+    let is_recast = nullifier_set.used_nullifiers.contains(&nullifier.nullifier_hash);
     require!(
-        election.status == ElectionStatus::Active ||
-        (election.status == ElectionStatus::Pending && clock.unix_timestamp >= election.start_time),
-        GovError::ElectionNotActive
+        !is_recast || election.allow_revoting,
+        GovError::AlreadyVoted
     );
 
-    // Verify choice is valid
+    // Counting a delegation uses up the delegator's own vote
+    let delegator_nullifiers: Vec<[u8; 32]> = delegations
+        .iter()
+        .map(|delegation| VoteNullifier::new(&delegation.delegator, &election_key, 0).nullifier_hash)
+        .collect();
     require!(
-        (choice as usize) < election.candidates.len(),
-        GovError::InvalidChoice
+        delegator_nullifiers.iter().all(|hash| !nullifier_set.used_nullifiers.contains(hash)),
+        GovError::DelegatorAlreadyVoted
     );
 
-    let voter_key = ctx.accounts.voter.key();
+    // Persist the derived status
+    election.status = election.effective_status(&clock);
+
+    let voting_method = election.voting_method;
+
+    // Revoting keeps the voter's current ballot; a recast takes it back out first
+    let mut ballot_weight = weight
+        .checked_add(delegated_weight)
+        .ok_or(GovError::ArithmeticOverflow)?;
+
+    if election.allow_revoting {
+        let voter_ballot = ctx.accounts.voter_ballot
+            .as_mut()
+            .ok_or(GovError::VoterBallotRequired)?;
+
+        if is_recast {
+            require!(
+                voter_ballot.voter == voter_key,
+                GovError::AlreadyVoted
+            );
+
+            remove_ballot(&voting_method, &mut election.vote_counts, &voter_ballot.ballot, voter_ballot.weight)?;
+            election.uncount_ballot(&voter_ballot.ballot, voter_ballot.weight)?;
+
+            // Delegations counted by the earlier ballot stay with the voter,
+            // the voter's own weight is the current one
+            let kept_delegated_weight = voter_ballot.delegated_weight
+                .checked_add(delegated_weight)
+                .ok_or(GovError::ArithmeticOverflow)?;
+            ballot_weight = weight
+                .checked_add(kept_delegated_weight)
+                .ok_or(GovError::ArithmeticOverflow)?;
+            voter_ballot.delegated_weight = kept_delegated_weight;
+        } else {
+            voter_ballot.election = election_key;
+            voter_ballot.voter = voter_key;
+            voter_ballot.delegated_weight = delegated_weight;
+            voter_ballot.bump = ctx.bumps.voter_ballot.ok_or(GovError::VoterBallotRequired)?;
+        }
+
+        voter_ballot.ballot = ballot.clone();
+        voter_ballot.weight = ballot_weight;
+    }
+
+    // Quadratic and cumulative ballots must fit the voter's budget
+    check_credit_budget(&voting_method, election.seats, &ballot, ballot_weight)?;
+
+    // Record the vote
+    apply_ballot(&voting_method, &mut election.vote_counts, &ballot, ballot_weight)?;
+
+    // Score elections also count the ballots per candidate for the mean
+    if let Ballot::Score { scores } = &ballot {
+        apply_score_ballots(&mut election.score_ballots, scores, ballot_weight)?;
+    }
+
+    // Keep the full ranking for tabulation
+    if let Ballot::Ranked { ranking } = &ballot {
+        if election.voting_method.uses_pairwise_matrix() {
+            let pairwise_matrix = ctx.accounts.pairwise_matrix
+                .as_mut()
+                .ok_or(GovError::PairwiseMatrixRequired)?;
+            pairwise_matrix.record(ranking, ballot_weight)?;
+        } else if election.voting_method.uses_ranked_ballots() {
+            let ranked_ballots = ctx.accounts.ranked_ballots
+                .as_mut()
+                .ok_or(GovError::RankedBallotsRequired)?;
+            reserve_ranking(
+                ranked_ballots,
+                ranking,
+                &ctx.accounts.voter.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+            )?;
+            ranked_ballots.record(ranking, ballot_weight)?;
+        }
+    }
+
+    // Abstain and blank ballots have their own counters
+    election.count_ballot(&ballot, ballot_weight)?;
+
+    // Publish the voter's reasons for governance UIs and the vote archive
+    if let Some(rationale) = rationale {
+        emit!(VoteRationaleSubmitted {
+            election: election_key,
+            voter: voter_key,
+            rationale,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    // Delegators are counted now and can no longer vote themselves
+    nullifier_set.used_nullifiers.extend(delegator_nullifiers);
+
+    if !delegations.is_empty() {
+        msg!("Voting for {} delegators, delegated weight {}", delegations.len(), delegated_weight);
+    }
+
+    // Only the last ballot counts, so a recast is not another vote
+    if is_recast {
+        msg!("Vote recast by voter {}: {:?}", voter_key, ballot);
+        return Ok(());
+    }
+
+    // Mark nullifier as used
+    nullifier_set.used_nullifiers.push(nullifier.nullifier_hash);
+
+    msg!("Vote cast by voter {}: {:?}", voter_key, ballot);
+    msg!("Total votes: {} (abstain: {}, blank: {})", election.total_votes, election.abstain_votes, election.blank_votes);
+    msg!("Compression mode: {}", election.use_compression);
+
+    Ok(())
+}
+
+/// Total weight of the delegations chained to a voter
+///
+/// Every delegation must apply to the election and lead to the voter
+/// within Delegation::MAX_DEPTH links (see utils::delegation).
+fn delegated_weight(
+    election: &Account<Election>,
+    voter_key: &Pubkey,
+    delegations: &[Account<Delegation>],
+) -> Result<u64> {
     let election_key = election.key();
 
-    // Verify voter eligibility based on compression mode
+    for delegation in delegations {
+        require!(
+            delegation.covers(&election_key, election),
+            GovError::InvalidDelegationScope
+        );
+    }
+
+    let links: Vec<(Pubkey, Pubkey)> = delegations
+        .iter()
+        .map(|delegation| (delegation.delegator, delegation.delegate))
+        .collect();
+    delegation_depths(voter_key, &links, Delegation::MAX_DEPTH)?;
+
+    delegations
+        .iter()
+        .try_fold(0u64, |sum, delegation| sum.checked_add(delegation.weight))
+        .ok_or_else(|| error!(GovError::ArithmeticOverflow))
+}
+
+/// Token weight a voter votes with in a token-weighted election
+///
+/// The balance is proven with prove_token_balance (Merkle snapshot) or
+/// deposited with deposit_tokens (escrow) before voting. Escrowed tokens
+/// cannot be withdrawn while the vote counts. The weight must come from the
+/// election's current snapshot.
+fn token_weight(
+    token_weighting: &TokenWeighting,
+    token_voter_weight: Option<&mut Account<TokenVoterWeight>>,
+) -> Result<u64> {
+    let token_voter_weight = token_voter_weight.ok_or(GovError::TokenVoterWeightRequired)?;
+
+    require!(
+        token_voter_weight.is_escrowed() == (token_weighting.snapshot == TokenSnapshot::Escrow),
+        GovError::InvalidTokenSnapshot
+    );
+
+    require!(
+        token_voter_weight.weight > 0,
+        GovError::InvalidVoterWeight
+    );
+
+    token_voter_weight.vote_counted = true;
+
+    Ok(token_voter_weight.weight)
+}
+
+/// Instruction arguments proving a compressed voter registration
+pub struct VoterProof<'a> {
+    /// Merkle proof of the registration leaf
+    pub merkle_proof: &'a [[u8; 32]],
+    /// Index of the registration leaf
+    pub leaf_index: Option<u32>,
+    /// When the voter registered (part of the leaf)
+    pub registered_at: Option<i64>,
+    /// Voter's weight (part of the leaf, default weight if None)
+    pub weight: Option<u64>,
+}
+
+/// Verify that a voter is eligible to vote in an election
+///
+/// Compression mode checks the merkle proof of the voter's registration leaf,
+/// which commits to the voter's weight; legacy mode checks the voter
+/// registration account.
+/// Returns the voter's voting weight.
+pub fn verify_voter_eligibility(
+    election: &Account<Election>,
+    voter_key: Pubkey,
+    voter_registration: Option<&Account<VoterRegistration>>,
+    attestation_key: Option<Pubkey>,
+    proof: VoterProof,
+) -> Result<u64> {
+    let VoterProof { merkle_proof, leaf_index, registered_at, weight: voter_weight } = proof;
+
     if election.use_compression {
         // ===== COMPRESSION MODE: Verify via merkle proof =====
         msg!("Verifying voter via merkle proof (compression mode)");
 
         // Validate required parameters for compression mode
         require!(
-            leaf_index.is_some() && registered_at.is_some() && ctx.accounts.attestation.is_some(),
+            leaf_index.is_some() && registered_at.is_some() && attestation_key.is_some(),
             GovError::InvalidMerkleProof
         );
 
-        let attestation_key = ctx.accounts.attestation.as_ref().unwrap().key();
-
         // Reconstruct the voter data to generate leaf hash
+        let weight = voter_weight.unwrap_or(DEFAULT_VOTER_WEIGHT);
         let compressed_data = CompressedVoterData::new(
             voter_key,
-            election_key,
-            attestation_key,
+            election.voter_registry,
+            attestation_key.unwrap(),
             registered_at.unwrap(),
+            weight,
         );
 
         let leaf_hash = compressed_data.to_leaf_hash()?;
@@ -108,7 +404,7 @@ pub fn handler(
         let is_valid = verify_compressed_voter_proof(
             &election.voter_merkle_root,
             &leaf_hash,
-            &merkle_proof,
+            merkle_proof,
             leaf_index.unwrap(),
         )?;
 
@@ -116,16 +412,13 @@ pub fn handler(
 
         msg!("Merkle proof verified for voter: {}", voter_key);
 
+        Ok(weight)
+
     } else {
         // ===== LEGACY MODE: Verify via voter registration account =====
         msg!("Verifying voter via registration account (legacy mode)");
 
-        require!(
-            ctx.accounts.voter_registration.is_some(),
-            GovError::NotRegistered
-        );
-
-        let voter_registration = ctx.accounts.voter_registration.as_ref().unwrap();
+        let voter_registration = voter_registration.ok_or(GovError::NotRegistered)?;
 
         // Verify voter registration matches
         require!(
@@ -134,48 +427,7 @@ pub fn handler(
         );
 
         msg!("Voter registration verified: {}", voter_key);
-    }
-
-    // Create nullifier for this vote (same for both modes)
-    let nullifier = VoteNullifier::new(
-        &voter_key,
-        &election_key,
-        0 // nonce - for MVP we use 0, in production this could be dynamic
-    );
-
-    // Initialize nullifier set if needed
-    if nullifier_set.election == Pubkey::default() {
-        nullifier_set.election = election_key;
-        nullifier_set.used_nullifiers = Vec::new();
-        nullifier_set.bump = ctx.bumps.nullifier_set;
-    }
 
-    // Check if voter has already voted
-    require!(
-        !nullifier_set.used_nullifiers.contains(&nullifier.nullifier_hash),
-        GovError::AlreadyVoted
-    );
-
-    // Update election status if needed
-    if election.status == ElectionStatus::Pending && clock.unix_timestamp >= election.start_time {
-        election.status = ElectionStatus::Active;
+        Ok(voter_registration.weight)
     }
-
-    // Record the vote
-    election.vote_counts[choice as usize] = election.vote_counts[choice as usize]
-        .checked_add(1)
-        .ok_or(GovError::ArithmeticOverflow)?;
-
-    election.total_votes = election.total_votes
-        .checked_add(1)
-        .ok_or(GovError::ArithmeticOverflow)?;
-
-    // Mark nullifier as used
-    nullifier_set.used_nullifiers.push(nullifier.nullifier_hash);
-
-    msg!("Vote cast for candidate {} by voter {}", choice, voter_key);
-    msg!("Total votes: {}", election.total_votes);
-    msg!("Compression mode: {}", election.use_compression);
-
-    Ok(())
 }
@@ -0,0 +1,208 @@
+//! Challenge Window
+//!
+//! Results are not actionable the moment voting ends. Elections can configure
+//! a challenge period after end_time during which anyone can post a bonded
+//! challenge citing a specific problem (invalid registration proof, duplicate
+//! nullifier, tally mismatch).
+//!
+//! Hooks and tabulation stay blocked until the window has passed and every
+//! challenge has been resolved by the arbiter, or by the authority when no
+//! arbiter is set:
+//! - Upheld: bond returned to the challenger, election cancelled
+//! - Rejected: bond forfeited to the incinerator
+//!
+//! Burning forfeited bonds means neither resolver gains from a rejection. The
+//! challenge account's rent always goes back to the challenger.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::incinerator;
+use anchor_lang::system_program;
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct ConfigureChallengeWindow<'info> {
+    #[account(mut)]
+    pub election: Account<'info, Election>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Configure the challenge window, bond and arbiter
+///
+/// Can only be set before election starts. A window needs a bond, so holding
+/// the results up costs something. Without an arbiter the authority resolves.
+pub fn configure_challenge_window(
+    ctx: Context<ConfigureChallengeWindow>,
+    challenge_period: i64,
+    challenge_bond: u64,
+    arbiter: Option<Pubkey>,
+) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    let clock = Clock::get()?;
+
+    require!(
+        election.authority == ctx.accounts.authority.key(),
+        GovError::Unauthorized
+    );
+
+    require!(
+        election.effective_status(&clock) == ElectionStatus::Pending,
+        GovError::ElectionAlreadyStarted
+    );
+
+    require!(
+        challenge_period >= 0,
+        GovError::InvalidTimeRange
+    );
+
+    // Free challenges could keep the outcome open indefinitely
+    require!(
+        challenge_period == 0 || challenge_bond > 0,
+        GovError::ChallengeBondRequired
+    );
+
+    election.challenge_period = challenge_period;
+    election.challenge_bond = challenge_bond;
+    election.arbiter = arbiter;
+
+    msg!("Challenge window: {} ({:?}), bond: {} lamports", challenge_period, election.time_base, challenge_bond);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PostChallenge<'info> {
+    #[account(mut)]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        init,
+        payer = challenger,
+        space = Challenge::SIZE,
+        seeds = [b"challenge", election.key().as_ref(), challenger.key().as_ref()],
+        bump
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Post a bonded challenge during the challenge window (permissionless)
+pub fn post_challenge(
+    ctx: Context<PostChallenge>,
+    kind: ChallengeKind,
+    evidence_hash: [u8; 32],
+) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    let clock = Clock::get()?;
+
+    require!(
+        election.in_challenge_window(&clock),
+        GovError::ChallengeWindowClosed
+    );
+
+    // Lock the bond in the challenge account
+    if election.challenge_bond > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.challenger.to_account_info(),
+                    to: ctx.accounts.challenge.to_account_info(),
+                },
+            ),
+            election.challenge_bond,
+        )?;
+    }
+
+    let challenge = &mut ctx.accounts.challenge;
+    challenge.election = election.key();
+    challenge.challenger = ctx.accounts.challenger.key();
+    challenge.kind = kind;
+    challenge.evidence_hash = evidence_hash;
+    challenge.bond = election.challenge_bond;
+    challenge.created_at = clock.unix_timestamp;
+    challenge.bump = ctx.bumps.challenge;
+
+    election.open_challenges = election.open_challenges
+        .checked_add(1)
+        .ok_or(GovError::ArithmeticOverflow)?;
+
+    msg!("Challenge posted: {:?} by {}", kind, challenge.challenger);
+    msg!("Open challenges: {}", election.open_challenges);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ResolveChallenge<'info> {
+    #[account(mut)]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        has_one = election,
+        has_one = challenger,
+        seeds = [b"challenge", election.key().as_ref(), challenger.key().as_ref()],
+        bump = challenge.bump
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    /// Arbiter of the election, or the authority when there is none
+    pub resolver: Signer<'info>,
+
+    /// CHECK: Receives the rent, and the bond if the challenge is upheld (checked by has_one)
+    #[account(mut)]
+    pub challenger: AccountInfo<'info>,
+
+    /// CHECK: Burns the bond if the challenge is rejected
+    #[account(mut, address = incinerator::ID)]
+    pub incinerator: AccountInfo<'info>,
+}
+
+/// Resolve an open challenge (only arbiter, or authority without one)
+///
+/// An upheld challenge cancels the election, so its hooks never run. A
+/// rejected bond is burned before the account is closed to the challenger.
+pub fn resolve_challenge(
+    ctx: Context<ResolveChallenge>,
+    upheld: bool,
+) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    let challenge = &ctx.accounts.challenge;
+    let resolver_key = ctx.accounts.resolver.key();
+
+    require!(
+        election.arbiter.unwrap_or(election.authority) == resolver_key,
+        GovError::UnauthorizedResolver
+    );
+
+    if !upheld && challenge.bond > 0 {
+        let challenge_info = challenge.to_account_info();
+        challenge_info.sub_lamports(challenge.bond)?;
+        ctx.accounts.incinerator.add_lamports(challenge.bond)?;
+    }
+
+    challenge.close(ctx.accounts.challenger.to_account_info())?;
+
+    election.open_challenges = election.open_challenges
+        .checked_sub(1)
+        .ok_or(GovError::ArithmeticOverflow)?;
+
+    if upheld {
+        election.status = ElectionStatus::Cancelled;
+        msg!("Challenge upheld - election cancelled");
+    } else {
+        msg!("Challenge rejected - bond burned");
+    }
+
+    msg!("Resolved by {}", resolver_key);
+    msg!("Open challenges: {}", election.open_challenges);
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+#[instruction(clone_id: u64)]
+pub struct CloneElection<'info> {
+    /// Election whose configuration is copied
+    #[account(has_one = authority)]
+    pub source_election: Account<'info, Election>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Election::MAX_SIZE,
+        seeds = [b"election", authority.key().as_ref(), &clone_id.to_le_bytes()],
+        bump
+    )]
+    pub election: Account<'info, Election>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create a new election with the configuration of an existing one
+///
+/// With reuse_voter_registry the clone counts the source's registered voters.
+/// The source must have ended (or been cancelled) first, since registration
+/// closes then and the copied root and totals can't go stale. Compression
+/// elections can only be cloned that way, since clone_election doesn't set up
+/// a new merkle tree.
+pub fn handler(
+    ctx: Context<CloneElection>,
+    _clone_id: u64,
+    start_time: i64,
+    end_time: i64,
+    reuse_voter_registry: bool,
+) -> Result<()> {
+    let source = &ctx.accounts.source_election;
+    let election = &mut ctx.accounts.election;
+    let clock = Clock::get()?;
+
+    require!(
+        end_time > start_time,
+        GovError::InvalidTimeRange
+    );
+
+    require!(
+        start_time >= source.time_base.now(&clock),
+        GovError::StartTimeInPast
+    );
+
+    require!(
+        reuse_voter_registry || !source.use_compression,
+        GovError::CompressedRegistryNotCloned
+    );
+
+    // The source's registry only stops changing once it is over
+    require!(
+        !reuse_voter_registry
+            || matches!(source.effective_status(&clock), ElectionStatus::Ended | ElectionStatus::Cancelled),
+        GovError::ElectionNotEnded
+    );
+
+    // Copy configuration and candidates
+    election.copy_configuration_from(source);
+
+    // Reset tallies
+    election.vote_counts = vec![0; election.candidates.len()];
+    election.score_ballots = vec![0; election.candidates.len()];
+    election.total_votes = 0;
+    election.abstain_votes = 0;
+    election.blank_votes = 0;
+    election.total_weight = 0;
+    election.shards_merged = 0;
+    election.ballot_group = None;
+    election.open_challenges = 0;
+
+    election.start_time = start_time;
+    election.end_time = end_time;
+
+    if reuse_voter_registry {
+        // Voters registered for the source can vote here without re-registering
+        election.voter_registry = source.voter_registry;
+        election.voter_merkle_root = source.voter_merkle_root;
+        election.merkle_tree = source.merkle_tree;
+        election.total_registered = source.total_registered;
+        election.total_registered_weight = source.total_registered_weight;
+
+        msg!("Reusing voter registry of {}", source.voter_registry);
+    } else {
+        election.voter_registry = election.key();
+        election.voter_merkle_root = [0; 32]; // Will be updated when voters register
+        election.merkle_tree = Pubkey::default();
+        election.total_registered = 0;
+        election.total_registered_weight = 0;
+    }
+
+    election.status = ElectionStatus::Pending;
+    election.status = election.effective_status(&clock);
+
+    election.bump = ctx.bumps.election;
+
+    msg!("Election cloned from {}", source.key());
+    msg!("Start: {}, End: {} ({:?})", start_time, end_time, election.time_base);
+
+    Ok(())
+}
@@ -6,9 +6,7 @@ use crate::errors::*;
 pub struct CloseElection<'info> {
     #[account(
         mut,
-        has_one = authority,
-        seeds = [b"election", authority.key().as_ref()],
-        bump = election.bump
+        has_one = authority
     )]
     pub election: Account<'info, Election>,
     
@@ -19,15 +17,16 @@ pub fn handler(
     ctx: Context<CloseElection>,
 ) -> Result<()> {
     let election = &mut ctx.accounts.election;
+    let clock = Clock::get()?;
 
-    // Verify election can be closed
+    // Verify election can be closed (a past end_time still allows closing)
     require!(
         election.status != ElectionStatus::Ended,
         GovError::ElectionEnded
     );
 
     require!(
-        election.status != ElectionStatus::Cancelled,
+        election.effective_status(&clock) != ElectionStatus::Cancelled,
         GovError::ElectionNotActive
     );
 
@@ -35,7 +34,8 @@ pub fn handler(
     election.status = ElectionStatus::Ended;
 
     msg!("Election closed by authority");
-    msg!("Total votes: {}", election.total_votes);
+    msg!("Total votes: {} (weight {})", election.total_votes, election.total_weight);
+    msg!("Abstain: {}, Blank: {}", election.abstain_votes, election.blank_votes);
     msg!("Results:");
     for (idx, count) in election.vote_counts.iter().enumerate() {
         msg!("  {}: {} votes", election.candidates[idx], count);
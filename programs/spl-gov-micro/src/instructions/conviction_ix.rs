@@ -0,0 +1,309 @@
+//! Conviction Voting
+//!
+//! For continuous funding, candidates are proposals each requesting an
+//! amount (Election::candidate_amounts). Instead of casting a ballot, a
+//! voter stakes their registration weight on one proposal and can move it
+//! at any time while the election is active. Each proposal's conviction
+//! grows toward its staked weight / (1 - decay) period by period, and the
+//! proposal passes once conviction crosses a threshold that rises with the
+//! share of the remaining funds requested (see utils::conviction). Passed
+//! proposals commit their amount, so later ones compete for what is left.
+//!
+//! - init_conviction: authority configures decay, threshold and funds
+//! - stake_conviction: voter stakes (or unstakes) their weight
+//! - update_conviction: anyone moves conviction forward to the current period
+
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::instructions::cast_vote::{verify_voter_eligibility, VoterProof};
+use crate::utils::conviction::{advance_conviction, conviction_threshold, MAX_DECAY_BPS};
+
+#[derive(Accounts)]
+pub struct InitConviction<'info> {
+    pub election: Account<'info, Election>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = ConvictionState::SIZE,
+        seeds = [b"conviction", election.key().as_ref()],
+        bump
+    )]
+    pub conviction_state: Account<'info, ConvictionState>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Configure conviction voting for an election
+///
+/// Can only be called before election starts, once candidate amounts are set.
+/// Decay is capped at MAX_DECAY_BPS; conviction saturates beyond u64::MAX.
+pub fn init_conviction(
+    ctx: Context<InitConviction>,
+    decay_bps: u16,
+    max_ratio_bps: u16,
+    weight_bps: u16,
+    period: i64,
+    total_funds: u64,
+) -> Result<()> {
+    let election = &ctx.accounts.election;
+    let conviction_state = &mut ctx.accounts.conviction_state;
+    let clock = Clock::get()?;
+
+    require!(
+        election.authority == ctx.accounts.authority.key(),
+        GovError::Unauthorized
+    );
+
+    require!(
+        election.effective_status(&clock) == ElectionStatus::Pending,
+        GovError::ElectionAlreadyStarted
+    );
+
+    require!(
+        election.voting_method == VotingMethod::Conviction,
+        GovError::UnsupportedVotingMethod
+    );
+
+    require!(
+        election.candidate_amounts.len() == election.candidates.len(),
+        GovError::InvalidCandidateAmounts
+    );
+
+    require!(
+        decay_bps > 0 && decay_bps <= MAX_DECAY_BPS &&
+        max_ratio_bps > 0 && max_ratio_bps <= 10_000 &&
+        weight_bps > 0 &&
+        period > 0 &&
+        total_funds > 0,
+        GovError::InvalidConvictionConfig
+    );
+
+    let candidate_count = election.candidates.len();
+
+    conviction_state.election = election.key();
+    conviction_state.decay_bps = decay_bps;
+    conviction_state.max_ratio_bps = max_ratio_bps;
+    conviction_state.weight_bps = weight_bps;
+    conviction_state.period = period;
+    conviction_state.total_funds = total_funds;
+    conviction_state.committed_funds = 0;
+    conviction_state.staked = vec![0; candidate_count];
+    conviction_state.conviction = vec![0; candidate_count];
+    conviction_state.last_update = election.start_time;
+    conviction_state.passed = Vec::new();
+    conviction_state.bump = ctx.bumps.conviction_state;
+
+    msg!("Conviction configured: decay {} bps, max ratio {} bps, period {} ({:?})",
+        decay_bps, max_ratio_bps, period, election.time_base);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct StakeConviction<'info> {
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"conviction", election.key().as_ref()],
+        bump = conviction_state.bump
+    )]
+    pub conviction_state: Account<'info, ConvictionState>,
+
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = ConvictionStake::SIZE,
+        seeds = [b"conviction_stake", election.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub stake: Account<'info, ConvictionStake>,
+
+    /// Voter registration account (only required for legacy mode)
+    #[account(
+        seeds = [
+            b"voter_registration",
+            election.voter_registry.as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub voter_registration: Option<Account<'info, VoterRegistration>>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    /// CHECK: Attestation account (optional, only for compression mode proof verification)
+    pub attestation: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Stake the voter's weight on a proposal, or unstake with None
+///
+/// Moving a stake takes the weight off the previous proposal; conviction
+/// already built up there decays from then on.
+pub fn stake_conviction(
+    ctx: Context<StakeConviction>,
+    candidate: Option<u8>,
+    merkle_proof: Vec<[u8; 32]>,
+    leaf_index: Option<u32>,
+    registered_at: Option<i64>,
+    voter_weight: Option<u64>,
+) -> Result<()> {
+    let election = &ctx.accounts.election;
+    let conviction_state = &mut ctx.accounts.conviction_state;
+    let stake = &mut ctx.accounts.stake;
+    let voter_key = ctx.accounts.voter.key();
+    let clock = Clock::get()?;
+
+    // Stakes only move while the election is running
+    election.require_active(&clock)?;
+
+    // Token weights are only counted by cast_vote
+    require!(
+        !election.is_token_weighted(),
+        GovError::TokenWeightedElection
+    );
+
+    if let Some(candidate) = candidate {
+        require!(
+            (candidate as usize) < election.candidates.len(),
+            GovError::InvalidChoice
+        );
+    }
+
+    let weight = verify_voter_eligibility(
+        election,
+        voter_key,
+        ctx.accounts.voter_registration.as_ref(),
+        ctx.accounts.attestation.as_ref().map(|attestation| attestation.key()),
+        VoterProof {
+            merkle_proof: &merkle_proof,
+            leaf_index,
+            registered_at,
+            weight: voter_weight,
+        },
+    )?;
+
+    // Settle conviction under the old stakes first
+    advance_conviction_state(conviction_state, election, &clock)?;
+
+    // Initialize stake on first use
+    if stake.election == Pubkey::default() {
+        stake.election = election.key();
+        stake.voter = voter_key;
+        stake.candidate = None;
+        stake.amount = 0;
+        stake.bump = ctx.bumps.stake;
+    }
+
+    if let Some(previous) = stake.candidate {
+        let staked = &mut conviction_state.staked[previous as usize];
+        *staked = staked
+            .checked_sub(stake.amount)
+            .ok_or(GovError::ArithmeticOverflow)?;
+    }
+
+    if let Some(candidate) = candidate {
+        let staked = &mut conviction_state.staked[candidate as usize];
+        *staked = staked
+            .checked_add(weight)
+            .ok_or(GovError::ArithmeticOverflow)?;
+    }
+
+    stake.candidate = candidate;
+    stake.amount = if candidate.is_some() { weight } else { 0 };
+
+    msg!("Voter {} staked {} on {:?}", voter_key, stake.amount, candidate);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateConviction<'info> {
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"conviction", election.key().as_ref()],
+        bump = conviction_state.bump
+    )]
+    pub conviction_state: Account<'info, ConvictionState>,
+}
+
+/// Move conviction forward to the current period (permissionless)
+pub fn update_conviction(
+    ctx: Context<UpdateConviction>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    advance_conviction_state(&mut ctx.accounts.conviction_state, &ctx.accounts.election, &clock)
+}
+
+/// Advance conviction by every full period elapsed since the last update
+///
+/// Conviction only accrues inside the voting window. Proposals whose
+/// conviction reaches their threshold against the remaining funds are
+/// recorded as passed and commit their amount; proposals requesting more
+/// than remains are skipped.
+pub fn advance_conviction_state(
+    conviction_state: &mut ConvictionState,
+    election: &Election,
+    clock: &Clock,
+) -> Result<()> {
+    let now = election.now(clock).min(election.end_time);
+    if now <= conviction_state.last_update {
+        return Ok(());
+    }
+
+    let steps = (now - conviction_state.last_update) / conviction_state.period;
+    if steps == 0 {
+        return Ok(());
+    }
+
+    for idx in 0..conviction_state.conviction.len() {
+        conviction_state.conviction[idx] = advance_conviction(
+            conviction_state.conviction[idx],
+            conviction_state.staked[idx],
+            conviction_state.decay_bps,
+            steps as u64,
+        );
+
+        if conviction_state.passed.contains(&(idx as u8)) || conviction_state.conviction[idx] == 0 {
+            continue;
+        }
+
+        let requested = election.candidate_amounts[idx];
+        let remaining = conviction_state.total_funds.saturating_sub(conviction_state.committed_funds);
+        if requested > remaining {
+            continue;
+        }
+
+        let threshold = conviction_threshold(
+            requested,
+            remaining,
+            election.eligible_weight(),
+            conviction_state.max_ratio_bps,
+            conviction_state.weight_bps,
+            conviction_state.decay_bps,
+        );
+
+        if threshold.is_some_and(|threshold| conviction_state.conviction[idx] >= threshold) {
+            conviction_state.passed.push(idx as u8);
+            conviction_state.committed_funds += requested;
+            msg!("Proposal {} passed with conviction {}", idx, conviction_state.conviction[idx]);
+        }
+    }
+
+    conviction_state.last_update += steps * conviction_state.period;
+
+    msg!("Conviction updated by {} periods", steps);
+
+    Ok(())
+}
@@ -7,7 +7,7 @@ use crate::utils::compression::get_merkle_tree_size;
 use spl_account_compression::program::SplAccountCompression;
 
 #[derive(Accounts)]
-#[instruction(candidates: Vec<String>, start_time: i64, end_time: i64, use_compression: bool, max_voters: u32)]
+#[instruction(candidates: Vec<String>, start_time: i64, end_time: i64, use_compression: bool, max_voters: u32, time_base: TimeBase)]
 pub struct CreateElection<'info> {
     #[account(
         init,
@@ -39,6 +39,7 @@ pub fn handler(
     end_time: i64,
     use_compression: bool,
     max_voters: u32,
+    time_base: TimeBase,
 ) -> Result<()> {
     let election = &mut ctx.accounts.election;
     let clock = Clock::get()?;
@@ -62,7 +63,7 @@ pub fn handler(
     );
 
     require!(
-        start_time >= clock.unix_timestamp,
+        start_time >= time_base.now(&clock),
         GovError::StartTimeInPast
     );
 
@@ -71,18 +72,21 @@ pub fn handler(
     election.candidates = candidates.clone();
     election.vote_counts = vec![0; candidates.len()];
     election.total_votes = 0;
+    election.abstain_votes = 0;
+    election.blank_votes = 0;
+    election.total_weight = 0;
     election.total_registered = 0;
+    election.total_registered_weight = 0;
     election.voter_merkle_root = [0; 32]; // Will be updated when voters register
+    election.voter_registry = election.key();
     election.start_time = start_time;
     election.end_time = end_time;
+    election.time_base = time_base;
     election.use_compression = use_compression;
 
     // Set status based on start time
-    election.status = if start_time <= clock.unix_timestamp {
-        ElectionStatus::Active
-    } else {
-        ElectionStatus::Pending
-    };
+    election.status = ElectionStatus::Pending;
+    election.status = election.effective_status(&clock);
 
     // Handle compression setup
     if use_compression {
@@ -136,10 +140,32 @@ pub fn handler(
     election.on_success_hook = None;
     election.on_failure_hook = None;
 
+    // Sharding is opt-in via configure_tally_shards
+    election.shard_count = 0;
+    election.shards_merged = 0;
+
+    // Ballot groups are opt-in via create_ballot_group
+    election.ballot_group = None;
+
+    // No challenge window unless configured
+    election.challenge_period = 0;
+    election.challenge_bond = 0;
+    election.arbiter = None;
+    election.open_challenges = 0;
+
+    // Plurality unless changed via set_voting_method
+    election.voting_method = VotingMethod::Plurality;
+    election.seats = 1;
+    election.score_ballots = vec![0; candidates.len()];
+    election.candidate_amounts = Vec::new();
+    election.budget_cap = 0;
+    election.allow_revoting = false;
+    election.token_weighting = None;
+
     election.bump = ctx.bumps.election;
 
     msg!("Election created with {} candidates", candidates.len());
-    msg!("Start: {}, End: {}", start_time, end_time);
+    msg!("Start: {}, End: {} ({:?})", start_time, end_time, time_base);
     msg!("Compression: {}", use_compression);
 
     Ok(())
@@ -0,0 +1,166 @@
+//! Liquid Democracy
+//!
+//! A registered voter can hand their vote to another registered voter for
+//! one election, or for every election of an authority sharing the same
+//! voter registry. Delegations chain: a delegate who delegates onward passes
+//! on everything delegated to them, up to Delegation::MAX_DEPTH links.
+//!
+//! - delegate_vote: delegator proves registration and names a delegate
+//! - revoke_delegation: delegator closes the delegation
+//! - cast_vote: delegate passes the chained delegations as remaining
+//!   accounts and votes with the accumulated weight
+//!
+//! Counting a delegation uses up the delegator's nullifier, so a delegator
+//! who has already voted is not counted, and one who has been counted can
+//! no longer vote.
+//!
+//! Sharded, grouped and token-weighted elections count no delegations:
+//! delegating into one is rejected, and cast_vote, the only instruction that
+//! counts delegations, refuses them.
+
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::instructions::cast_vote::{verify_voter_eligibility, VoterProof};
+
+#[derive(Accounts)]
+#[instruction(scope: DelegationScope)]
+pub struct DelegateVote<'info> {
+    /// Election the delegator proves registration in (inside the scope)
+    pub election: Account<'info, Election>,
+
+    #[account(
+        init_if_needed,
+        payer = delegator,
+        space = Delegation::SIZE,
+        seeds = [b"delegation", scope.key().as_ref(), delegator.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    /// Voter registration account (only required for legacy mode)
+    #[account(
+        seeds = [
+            b"voter_registration",
+            election.voter_registry.as_ref(),
+            delegator.key().as_ref()
+        ],
+        bump
+    )]
+    pub voter_registration: Option<Account<'info, VoterRegistration>>,
+
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+
+    /// CHECK: Attestation account (optional, only for compression mode proof verification)
+    pub attestation: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Delegate the voter's vote, or move an existing delegation to a new delegate
+pub fn delegate_vote(
+    ctx: Context<DelegateVote>,
+    scope: DelegationScope,
+    delegate: Pubkey,
+    merkle_proof: Vec<[u8; 32]>,
+    leaf_index: Option<u32>,
+    registered_at: Option<i64>,
+    voter_weight: Option<u64>,
+) -> Result<()> {
+    let election = &ctx.accounts.election;
+    let delegation = &mut ctx.accounts.delegation;
+    let delegator_key = ctx.accounts.delegator.key();
+    let clock = Clock::get()?;
+
+    require!(
+        match scope {
+            DelegationScope::Election(election_key) => election_key == election.key(),
+            DelegationScope::Authority(authority) => authority == election.authority,
+        },
+        GovError::InvalidDelegationScope
+    );
+
+    require!(
+        delegate != delegator_key,
+        GovError::SelfDelegation
+    );
+
+    // Delegating into a finished election has no effect
+    if let DelegationScope::Election(_) = scope {
+        require!(
+            matches!(election.effective_status(&clock), ElectionStatus::Pending | ElectionStatus::Active),
+            GovError::ElectionEnded
+        );
+
+        // Token-weighted elections count no delegations
+        require!(
+            !election.is_token_weighted(),
+            GovError::TokenWeightedElection
+        );
+
+        // Neither do sharded or grouped elections, which don't take cast_vote
+        require!(
+            !election.is_sharded(),
+            GovError::ElectionIsSharded
+        );
+
+        require!(
+            election.ballot_group.is_none(),
+            GovError::ElectionInBallotGroup
+        );
+    }
+
+    let weight = verify_voter_eligibility(
+        election,
+        delegator_key,
+        ctx.accounts.voter_registration.as_ref(),
+        ctx.accounts.attestation.as_ref().map(|attestation| attestation.key()),
+        VoterProof {
+            merkle_proof: &merkle_proof,
+            leaf_index,
+            registered_at,
+            weight: voter_weight,
+        },
+    )?;
+
+    delegation.delegator = delegator_key;
+    delegation.delegate = delegate;
+    delegation.scope = scope;
+    delegation.voter_registry = election.voter_registry;
+    delegation.weight = weight;
+    delegation.created_at = clock.unix_timestamp;
+    delegation.bump = ctx.bumps.delegation;
+
+    msg!("Vote delegated by {} to {} ({:?})", delegator_key, delegate, scope);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevokeDelegation<'info> {
+    #[account(
+        mut,
+        has_one = delegator,
+        close = delegator,
+        seeds = [b"delegation", delegation.scope.key().as_ref(), delegator.key().as_ref()],
+        bump = delegation.bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+}
+
+/// Revoke a delegation
+///
+/// Votes the delegate has already cast with it stay counted.
+pub fn revoke_delegation(
+    ctx: Context<RevokeDelegation>,
+) -> Result<()> {
+    let delegation = &ctx.accounts.delegation;
+
+    msg!("Delegation from {} to {} revoked", delegation.delegator, delegation.delegate);
+
+    Ok(())
+}
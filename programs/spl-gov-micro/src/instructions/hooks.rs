@@ -12,6 +12,9 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::errors::*;
+use crate::utils::stv::STV_SCALE;
+use crate::utils::conviction::CONVICTION_SCALE;
+use crate::utils::tally::{majority_threshold, tally_proposal, top_candidates};
 
 #[derive(Accounts)]
 pub struct SetSuccessHook<'info> {
@@ -36,6 +39,7 @@ pub fn set_success_hook(
     ctx: Context<SetSuccessHook>,
 ) -> Result<()> {
     let election = &mut ctx.accounts.election;
+    let clock = Clock::get()?;
 
     require!(
         election.authority == ctx.accounts.authority.key(),
@@ -43,7 +47,7 @@ pub fn set_success_hook(
     );
 
     require!(
-        election.status == ElectionStatus::Pending,
+        election.effective_status(&clock) == ElectionStatus::Pending,
         GovError::ElectionAlreadyStarted
     );
 
@@ -77,6 +81,7 @@ pub fn set_failure_hook(
     ctx: Context<SetFailureHook>,
 ) -> Result<()> {
     let election = &mut ctx.accounts.election;
+    let clock = Clock::get()?;
 
     require!(
         election.authority == ctx.accounts.authority.key(),
@@ -84,7 +89,7 @@ pub fn set_failure_hook(
     );
 
     require!(
-        election.status == ElectionStatus::Pending,
+        election.effective_status(&clock) == ElectionStatus::Pending,
         GovError::ElectionAlreadyStarted
     );
 
@@ -114,6 +119,7 @@ pub fn clear_hooks(
     ctx: Context<ClearHooks>,
 ) -> Result<()> {
     let election = &mut ctx.accounts.election;
+    let clock = Clock::get()?;
 
     require!(
         election.authority == ctx.accounts.authority.key(),
@@ -121,7 +127,7 @@ pub fn clear_hooks(
     );
 
     require!(
-        election.status == ElectionStatus::Pending,
+        election.effective_status(&clock) == ElectionStatus::Pending,
         GovError::ElectionAlreadyStarted
     );
 
@@ -137,9 +143,18 @@ pub fn clear_hooks(
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct HookContext {
     pub election: Pubkey,
-    pub winning_choice: u8,
-    pub winning_votes: u64,
+    /// Winning candidates, best first (one per seat; empty without a winner)
+    pub winners: Vec<u8>,
+    /// Votes of each winner (parallel to winners)
+    pub winning_votes: Vec<u64>,
+    /// Ballots cast for candidates
     pub total_votes: u64,
+    /// Sum of the weights of the ballots in total_votes
+    pub total_weight: u64,
+    /// Weight of the abstain ballots (not part of total_votes)
+    pub abstain_votes: u64,
+    /// Weight of the blank ballots (not part of total_votes)
+    pub blank_votes: u64,
     pub passed_threshold: bool,
     pub timestamp: i64,
 }
@@ -153,37 +168,45 @@ pub struct HookContext {
 pub fn execute_hooks(
     election: &Election,
     election_pubkey: Pubkey,
+    result: Option<&ElectionResult>,
     hook_program: &AccountInfo,
     remaining_accounts: &[AccountInfo],
 ) -> Result<()> {
-    // Determine if election passed (simple majority for MVP)
-    let total_votes = election.total_votes;
-    let max_votes = election.vote_counts.iter().max().copied().unwrap_or(0);
+    let clock = Clock::get()?;
 
-    let passed_threshold = if total_votes > 0 {
-        // Simple majority: more than 50% of votes
-        max_votes > (total_votes / 2)
-    } else {
-        false
-    };
+    // Hooks only run on a finished election
+    require!(
+        election.effective_status(&clock) == ElectionStatus::Ended,
+        GovError::ElectionNotEnded
+    );
 
-    // Find winning choice
-    let winning_choice = election
-        .vote_counts
-        .iter()
-        .enumerate()
-        .max_by_key(|(_, &votes)| votes)
-        .map(|(idx, _)| idx as u8)
-        .unwrap_or(0);
+    // Results stay blocked during the challenge window and while disputed
+    require!(
+        election.outcome_final(&clock),
+        GovError::OutcomeNotFinal
+    );
+
+    // Sharded elections must fold every shard in before the outcome is known
+    require!(
+        election.all_shards_merged(),
+        GovError::ShardsNotMerged
+    );
+
+    let outcome = election_outcome(election, election_pubkey, result)?;
+    let total_votes = election.total_votes;
+    let passed_threshold = outcome.passed;
 
     // Prepare hook context
     let hook_ctx = HookContext {
         election: election_pubkey,
-        winning_choice,
-        winning_votes: max_votes,
+        winners: outcome.winners,
+        winning_votes: outcome.winning_votes,
         total_votes,
+        total_weight: election.total_weight,
+        abstain_votes: election.abstain_votes,
+        blank_votes: election.blank_votes,
         passed_threshold,
-        timestamp: Clock::get()?.unix_timestamp,
+        timestamp: clock.unix_timestamp,
     };
 
     // Serialize context for CPI
@@ -191,8 +214,8 @@ pub fn execute_hooks(
     data.extend_from_slice(&hook_ctx.try_to_vec()?);
 
     msg!("Executing hook: {}", hook_program.key());
-    msg!("Passed: {}, Winner: {}, Votes: {}/{}",
-        passed_threshold, winning_choice, max_votes, total_votes);
+    msg!("Passed: {}, Winners: {:?}, Votes: {:?}/{}",
+        passed_threshold, hook_ctx.winners, hook_ctx.winning_votes, total_votes);
 
     // CPI to hook program
     // Note: Hook program must have an instruction that accepts HookContext
@@ -217,21 +240,117 @@ pub fn execute_hooks(
     Ok(())
 }
 
-/// Helper to determine which hook to execute
-pub fn get_active_hook(election: &Election) -> Option<Pubkey> {
-    // Check if election passed threshold
-    let total_votes = election.total_votes;
-    if total_votes == 0 {
-        return election.on_failure_hook;
+/// Winners of an election as seen by the hooks
+struct ElectionOutcome {
+    winners: Vec<u8>,
+    winning_votes: Vec<u64>,
+    passed: bool,
+}
+
+/// Determine the election outcome
+///
+/// Proposals use quorum, pass ratio and veto. Other single-seat methods
+/// tallied on the election use the vote counts (simple majority for MVP).
+/// Tabulated methods and multi-seat elections read the
+/// winners from their complete ElectionResult.
+fn election_outcome(
+    election: &Election,
+    election_pubkey: Pubkey,
+    result: Option<&ElectionResult>,
+) -> Result<ElectionOutcome> {
+    if election.requires_tabulation() {
+        let result = result
+            .filter(|result| result.election == election_pubkey && result.complete)
+            .ok_or(GovError::TabulationIncomplete)?;
+
+        return Ok(ElectionOutcome {
+            winners: result.winners.clone(),
+            winning_votes: result.winners
+                .iter()
+                .map(|winner| winning_votes(election, result, *winner))
+                .collect(),
+            passed: !result.winners.is_empty(),
+        });
+    }
+
+    // Proposals pass on quorum, pass ratio and veto rather than a majority
+    if let VotingMethod::Proposal { quorum_bps, pass_threshold_bps, veto_threshold_bps } = election.voting_method {
+        let outcome = tally_proposal(
+            quorum_bps,
+            pass_threshold_bps,
+            veto_threshold_bps,
+            &election.vote_counts,
+            election.abstentions(),
+            election.eligible_weight(),
+        );
+
+        let winners: Vec<u8> = outcome.decisive_option()
+            .map(|option| option.index() as u8)
+            .into_iter()
+            .collect();
+
+        return Ok(ElectionOutcome {
+            winning_votes: winners.iter().map(|winner| election.vote_counts[*winner as usize]).collect(),
+            winners,
+            passed: outcome.passed,
+        });
+    }
+
+    Ok(vote_count_outcome(&election.voting_method, election.seats, &election.vote_counts, election.total_weight))
+}
+
+/// Single-seat outcome straight from the vote counts
+/// Picks the winner exactly like tabulation (ties go to the earlier candidate).
+fn vote_count_outcome(
+    voting_method: &VotingMethod,
+    seats: u8,
+    vote_counts: &[u64],
+    total_weight: u64,
+) -> ElectionOutcome {
+    let winners = top_candidates(vote_counts, 1).winners;
+    let winning_votes: Vec<u64> = winners.iter().map(|winner| vote_counts[*winner as usize]).collect();
+
+    // Simple majority, in the units the method counts votes in
+    let threshold = majority_threshold(voting_method, seats, vote_counts, total_weight);
+    let passed = total_weight > 0 && winning_votes.first().is_some_and(|votes| *votes > threshold);
+
+    ElectionOutcome {
+        winners,
+        winning_votes,
+        passed,
     }
+}
+
+/// Votes the winner held in the round they were elected
+/// STV tallies and convictions are converted back from fixed-point units.
+fn winning_votes(election: &Election, result: &ElectionResult, winner: u8) -> u64 {
+    let votes = result.rounds
+        .iter()
+        .find(|round| round.elected.contains(&winner))
+        .map(|round| round.tallies[winner as usize])
+        .unwrap_or(0);
+
+    let votes = match election.voting_method {
+        VotingMethod::SingleTransferableVote => votes / STV_SCALE,
+        VotingMethod::Conviction => votes / CONVICTION_SCALE as u128,
+        _ => votes,
+    };
+
+    votes as u64
+}
 
-    let max_votes = election.vote_counts.iter().max().copied().unwrap_or(0);
-    let passed = max_votes > (total_votes / 2);
+/// Helper to determine which hook to execute
+pub fn get_active_hook(
+    election: &Election,
+    election_pubkey: Pubkey,
+    result: Option<&ElectionResult>,
+) -> Result<Option<Pubkey>> {
+    let outcome = election_outcome(election, election_pubkey, result)?;
 
-    if passed {
-        election.on_success_hook
+    if outcome.passed {
+        Ok(election.on_success_hook)
     } else {
-        election.on_failure_hook
+        Ok(election.on_failure_hook)
     }
 }
 
@@ -250,8 +369,8 @@ pub fn get_active_hook(election: &Election) -> Option<Pubkey> {
 //     // Hook context contains election results
 //     require!(hook_ctx.passed_threshold, MyError::ProposalFailed);
 //
-//     // Execute your logic based on results
-//     match hook_ctx.winning_choice {
+//     // Execute your logic based on results (winners[0] is the top candidate)
+//     match hook_ctx.winners.first().copied().unwrap_or(u8::MAX) {
 //         0 => execute_option_a(ctx)?,
 //         1 => execute_option_b(ctx)?,
 //         _ => return Err(MyError::InvalidChoice.into()),
@@ -260,3 +379,29 @@ pub fn get_active_hook(election: &Election) -> Option<Pubkey> {
 //     Ok(())
 // }
 // ```
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vote_count_outcome_breaks_ties_like_tabulation() {
+        // Candidates 1 and 2 tie for the lead
+        let vote_counts = [2, 5, 5];
+        let outcome = vote_count_outcome(&VotingMethod::Plurality, 1, &vote_counts, 12);
+
+        assert_eq!(outcome.winners, vec![1]);
+        assert_eq!(outcome.winners, top_candidates(&vote_counts, 1).winners);
+        assert_eq!(outcome.winning_votes, vec![5]);
+        assert!(!outcome.passed);
+    }
+
+    #[test]
+    fn test_vote_count_outcome_without_votes() {
+        let outcome = vote_count_outcome(&VotingMethod::Plurality, 1, &[0, 0], 0);
+
+        assert!(outcome.winners.is_empty());
+        assert!(outcome.winning_votes.is_empty());
+        assert!(!outcome.passed);
+    }
+}
@@ -1,8 +1,22 @@
 pub mod create_election;
+pub mod clone_election;
 pub mod register_voter;
 pub mod cast_vote;
 pub mod cast_batch_votes;
 pub mod close_election;
+pub mod tick;
+pub mod voting_method;
+pub mod ranked_ballots;
+pub mod pairwise_matrix;
+pub mod tabulate;
+pub mod candidate_amounts;
+pub mod conviction_ix;
+pub mod revoting;
+pub mod delegation_ix;
+pub mod token_voting_ix;
+pub mod sharding;
+pub mod ballot_group_ix;
+pub mod challenges;
 
 // Beta features
 pub mod privacy_interface;
@@ -10,10 +24,24 @@ pub mod hooks;
 
 // Re-export all generated types from Anchor macros
 pub use create_election::*;
+pub use clone_election::*;
 pub use register_voter::*;
 pub use cast_vote::*;
 pub use cast_batch_votes::*;
 pub use close_election::*;
+pub use tick::*;
+pub use voting_method::*;
+pub use ranked_ballots::*;
+pub use pairwise_matrix::*;
+pub use tabulate::*;
+pub use candidate_amounts::*;
+pub use conviction_ix::*;
+pub use revoting::*;
+pub use delegation_ix::*;
+pub use token_voting_ix::*;
+pub use sharding::*;
+pub use ballot_group_ix::*;
+pub use challenges::*;
 
 // Beta feature re-exports
 pub use privacy_interface::*;
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct InitPairwiseMatrix<'info> {
+    pub election: Account<'info, Election>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = PairwiseMatrix::SIZE,
+        seeds = [b"pairwise_matrix", election.key().as_ref()],
+        bump
+    )]
+    pub pairwise_matrix: Account<'info, PairwiseMatrix>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the pairwise preference matrix of a Condorcet election (permissionless)
+pub fn handler(
+    ctx: Context<InitPairwiseMatrix>,
+) -> Result<()> {
+    let election = &ctx.accounts.election;
+    let pairwise_matrix = &mut ctx.accounts.pairwise_matrix;
+
+    require!(
+        election.voting_method.uses_pairwise_matrix(),
+        GovError::UnsupportedVotingMethod
+    );
+
+    let candidate_count = election.candidates.len();
+
+    pairwise_matrix.election = election.key();
+    pairwise_matrix.candidate_count = candidate_count as u8;
+    pairwise_matrix.preferences = vec![0; candidate_count * candidate_count];
+    pairwise_matrix.bump = ctx.bumps.pairwise_matrix;
+
+    msg!("Pairwise matrix initialized for election {} ({}x{})", election.key(), candidate_count, candidate_count);
+
+    Ok(())
+}
@@ -32,6 +32,7 @@ pub fn enable_private_voting(
     ctx: Context<EnablePrivateVoting>,
 ) -> Result<()> {
     let election = &mut ctx.accounts.election;
+    let clock = Clock::get()?;
 
     // Only authority can enable privacy
     require!(
@@ -41,7 +42,7 @@ pub fn enable_private_voting(
 
     // Can only enable before election starts
     require!(
-        election.status == ElectionStatus::Pending,
+        election.effective_status(&clock) == ElectionStatus::Pending,
         GovError::ElectionAlreadyStarted
     );
 
@@ -79,12 +80,14 @@ pub struct ReceivePrivateTally<'info> {
 /// to update the election with the final decrypted results.
 ///
 /// This is the ONLY way to update results for a private election.
+/// Only accepted once voting has closed.
 pub fn receive_private_tally(
     ctx: Context<ReceivePrivateTally>,
     tally: Vec<u64>,
     _proof: Vec<u8>, // TallyProof (not validated in MVP)
 ) -> Result<()> {
     let election = &mut ctx.accounts.election;
+    let clock = Clock::get()?;
 
     // Verify election has privacy enabled
     require!(
@@ -98,6 +101,12 @@ pub fn receive_private_tally(
         GovError::UnauthorizedPrivacyLayer
     );
 
+    // The tally is final, so votes must no longer be coming in
+    require!(
+        election.effective_status(&clock) == ElectionStatus::Ended,
+        GovError::ElectionNotEnded
+    );
+
     // Verify tally length matches candidates
     require!(
         tally.len() == election.candidates.len(),
@@ -110,6 +119,7 @@ pub fn receive_private_tally(
     // Update vote counts
     election.vote_counts = tally.clone();
     election.total_votes = tally.iter().sum();
+    election.total_weight = election.total_votes;
 
     msg!("Private tally received and updated");
     msg!("Total votes: {}", election.total_votes);
@@ -133,6 +143,7 @@ pub fn disable_private_voting(
     ctx: Context<DisablePrivateVoting>,
 ) -> Result<()> {
     let election = &mut ctx.accounts.election;
+    let clock = Clock::get()?;
 
     require!(
         election.authority == ctx.accounts.authority.key(),
@@ -140,7 +151,7 @@ pub fn disable_private_voting(
     );
 
     require!(
-        election.status == ElectionStatus::Pending,
+        election.effective_status(&clock) == ElectionStatus::Pending,
         GovError::ElectionAlreadyStarted
     );
 
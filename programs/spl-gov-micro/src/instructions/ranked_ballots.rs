@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+use crate::state::*;
+use crate::errors::*;
+use crate::utils::realloc::grow_account;
+
+#[derive(Accounts)]
+pub struct InitRankedBallots<'info> {
+    pub election: Account<'info, Election>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = RankedBallots::SIZE,
+        seeds = [b"ranked_ballots", election.key().as_ref()],
+        bump
+    )]
+    pub ranked_ballots: Account<'info, RankedBallots>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the ranked ballot store of a ranked election (permissionless)
+pub fn handler(
+    ctx: Context<InitRankedBallots>,
+) -> Result<()> {
+    let election = &ctx.accounts.election;
+    let ranked_ballots = &mut ctx.accounts.ranked_ballots;
+
+    require!(
+        election.voting_method.uses_ranked_ballots(),
+        GovError::UnsupportedVotingMethod
+    );
+
+    ranked_ballots.election = election.key();
+    ranked_ballots.rankings = Vec::new();
+    ranked_ballots.ballot_weights = Vec::new();
+    ranked_ballots.bump = ctx.bumps.ranked_ballots;
+
+    msg!("Ranked ballots initialized for election {}", election.key());
+
+    Ok(())
+}
+
+/// Make room for `ranking` in the ranked ballot store
+///
+/// A ranking nobody has cast yet may not fit; the account then grows by
+/// RankedBallots::GROWTH_RANKINGS entries, paid by `payer`.
+pub fn reserve_ranking<'info>(
+    ranked_ballots: &Account<'info, RankedBallots>,
+    ranking: &[u8],
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    if ranked_ballots.contains(ranking) {
+        return Ok(());
+    }
+
+    let count = ranked_ballots.rankings.len();
+    let account = ranked_ballots.to_account_info();
+
+    if account.data_len() < RankedBallots::space(count + 1) {
+        grow_account(
+            &account,
+            payer,
+            system_program,
+            RankedBallots::space(count + RankedBallots::GROWTH_RANKINGS),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Make room for the STV value of every ranking group
+///
+/// An instruction can grow an account by at most MAX_PERMITTED_DATA_INCREASE
+/// bytes, so a large ballot store takes several calls. Returns whether the
+/// values fit yet.
+pub fn reserve_ballot_weights<'info>(
+    ranked_ballots: &Account<'info, RankedBallots>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<bool> {
+    let account = ranked_ballots.to_account_info();
+    let required = RankedBallots::space_with_weights(ranked_ballots.rankings.len());
+
+    if account.data_len() < required {
+        let grown = required.min(account.data_len() + MAX_PERMITTED_DATA_INCREASE);
+        grow_account(&account, payer, system_program, grown)?;
+    }
+
+    Ok(account.data_len() >= required)
+}
@@ -37,11 +37,15 @@ pub struct RegisterVoter<'info> {
     /// CHECK: Compression program is validated when used
     pub compression_program: Option<AccountInfo<'info>>,
 
+    /// Election authority (only required to assign a non-default weight)
+    pub authority: Option<Signer<'info>>,
+
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(
     ctx: Context<RegisterVoter>,
+    weight: u64,
 ) -> Result<()> {
     let election = &mut ctx.accounts.election;
     let clock = Clock::get()?;
@@ -57,6 +61,34 @@ pub fn handler(
     let attestation_key = ctx.accounts.attestation.key();
     let election_key = election.key();
 
+    // Clones that reuse another election's voters register there
+    require!(
+        election.voter_registry == election_key,
+        crate::errors::GovError::SharedVoterRegistry
+    );
+
+    // Registration closes with the election, clones may have copied its totals
+    require!(
+        matches!(
+            election.effective_status(&clock),
+            ElectionStatus::Pending | ElectionStatus::Active
+        ),
+        crate::errors::GovError::ElectionEnded
+    );
+
+    require!(
+        weight > 0,
+        crate::errors::GovError::InvalidVoterWeight
+    );
+
+    // Only the authority can give a voter more (or less) than the default weight
+    if weight != DEFAULT_VOTER_WEIGHT {
+        require!(
+            ctx.accounts.authority.as_ref().is_some_and(|authority| authority.key() == election.authority),
+            crate::errors::GovError::Unauthorized
+        );
+    }
+
     if election.use_compression {
         // ===== COMPRESSION MODE =====
         msg!("Registering voter in compression mode");
@@ -70,6 +102,7 @@ pub fn handler(
             election_key,
             attestation_key,
             clock.unix_timestamp,
+            weight,
         );
 
         // Generate leaf hash
@@ -100,10 +133,13 @@ pub fn handler(
         election.total_registered = election.total_registered
             .checked_add(1)
             .ok_or(crate::errors::GovError::ArithmeticOverflow)?;
+        election.total_registered_weight = election.total_registered_weight
+            .checked_add(weight)
+            .ok_or(crate::errors::GovError::ArithmeticOverflow)?;
 
         msg!("Voter registered (compressed): {}", voter_key);
         msg!("Leaf hash: {:?}", leaf_hash);
-        msg!("Total registered: {}", election.total_registered);
+        msg!("Total registered: {} (weight {})", election.total_registered, election.total_registered_weight);
 
     } else {
         // ===== LEGACY MODE (Regular Accounts) =====
@@ -122,12 +158,14 @@ pub fn handler(
         voter_registration.attestation = attestation_key;
         voter_registration.election = election_key;
         voter_registration.registered_at = clock.unix_timestamp;
+        voter_registration.weight = weight;
 
         // Create leaf hash for merkle tree (legacy approach)
         let leaf = create_voter_leaf(
             &voter_registration.wallet,
             &voter_registration.election,
             &voter_registration.attestation,
+            voter_registration.weight,
         );
 
         // Track the last leaf hash as the root (MVP approach)
@@ -135,10 +173,13 @@ pub fn handler(
         election.total_registered = election.total_registered
             .checked_add(1)
             .ok_or(crate::errors::GovError::ArithmeticOverflow)?;
+        election.total_registered_weight = election.total_registered_weight
+            .checked_add(weight)
+            .ok_or(crate::errors::GovError::ArithmeticOverflow)?;
 
         msg!("Voter registered (legacy): {}", voter_registration.wallet);
         msg!("Attestation: {}", voter_registration.attestation);
-        msg!("Total registered: {}", election.total_registered);
+        msg!("Total registered: {} (weight {})", election.total_registered, election.total_registered_weight);
     }
 
     Ok(())
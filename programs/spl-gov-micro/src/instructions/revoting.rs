@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct SetRevoting<'info> {
+    #[account(mut)]
+    pub election: Account<'info, Election>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Allow or forbid voters to recast their vote while the election is active
+///
+/// Can only be set before election starts. Recasts undo the voter's
+/// previous ballot, so only methods that tally on the election account are
+/// supported, and never together with tally shards or ballot groups.
+pub fn handler(
+    ctx: Context<SetRevoting>,
+    allow_revoting: bool,
+) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    let clock = Clock::get()?;
+
+    require!(
+        election.authority == ctx.accounts.authority.key(),
+        GovError::Unauthorized
+    );
+
+    require!(
+        election.effective_status(&clock) == ElectionStatus::Pending,
+        GovError::ElectionAlreadyStarted
+    );
+
+    if allow_revoting {
+        require!(
+            election.voting_method.tallies_on_election(),
+            GovError::UnsupportedVotingMethod
+        );
+
+        require!(
+            !election.is_sharded(),
+            GovError::ElectionIsSharded
+        );
+
+        require!(
+            election.ballot_group.is_none(),
+            GovError::ElectionInBallotGroup
+        );
+    }
+
+    election.allow_revoting = allow_revoting;
+
+    msg!("Revoting allowed: {}", allow_revoting);
+
+    Ok(())
+}
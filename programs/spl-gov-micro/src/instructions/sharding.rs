@@ -0,0 +1,338 @@
+//! Sharded Tallies
+//!
+//! Every regular vote write-locks the Election account and its NullifierSet,
+//! which serializes all votes of an election. Sharded elections spread votes
+//! across N TallyShard accounts instead:
+//!
+//! - The shard is picked from the voter's nullifier prefix, so a voter always
+//!   lands in the same shard and double votes are caught there
+//! - cast_sharded_vote only reads the Election account
+//! - After voting closes, merge_tally_shard folds each shard into vote_counts
+
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::instructions::cast_vote::{verify_voter_eligibility, VoterProof};
+use crate::utils::realloc::reserve_space;
+use crate::utils::tally::{validate_ballot, apply_ballot, check_credit_budget};
+
+#[derive(Accounts)]
+pub struct ConfigureTallyShards<'info> {
+    #[account(mut)]
+    pub election: Account<'info, Election>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Set the number of tally shards for an election (0 disables sharding)
+///
+/// Can only be set before election starts.
+pub fn configure_tally_shards(
+    ctx: Context<ConfigureTallyShards>,
+    shard_count: u8,
+) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    let clock = Clock::get()?;
+
+    require!(
+        election.authority == ctx.accounts.authority.key(),
+        GovError::Unauthorized
+    );
+
+    require!(
+        election.effective_status(&clock) == ElectionStatus::Pending,
+        GovError::ElectionAlreadyStarted
+    );
+
+    require!(
+        shard_count <= TallyShard::MAX_SHARDS,
+        GovError::InvalidShardCount
+    );
+
+    require!(
+        election.ballot_group.is_none(),
+        GovError::ElectionInBallotGroup
+    );
+
+    require!(
+        shard_count == 0 || election.voting_method.tallies_on_election(),
+        GovError::UnsupportedVotingMethod
+    );
+
+    require!(
+        shard_count == 0 || !election.allow_revoting,
+        GovError::RevotingEnabled
+    );
+
+    require!(
+        shard_count == 0 || !election.is_token_weighted(),
+        GovError::TokenWeightedElection
+    );
+
+    election.shard_count = shard_count;
+    election.shards_merged = 0;
+
+    msg!("Tally shards configured: {}", shard_count);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(shard_index: u8)]
+pub struct InitTallyShard<'info> {
+    pub election: Account<'info, Election>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = TallyShard::space(TallyShard::INITIAL_NULLIFIERS),
+        seeds = [b"tally_shard", election.key().as_ref(), &[shard_index]],
+        bump
+    )]
+    pub tally_shard: Account<'info, TallyShard>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create one tally shard of a sharded election (permissionless)
+pub fn init_tally_shard(
+    ctx: Context<InitTallyShard>,
+    shard_index: u8,
+) -> Result<()> {
+    let election = &ctx.accounts.election;
+    let tally_shard = &mut ctx.accounts.tally_shard;
+
+    require!(
+        election.is_sharded(),
+        GovError::ElectionNotSharded
+    );
+
+    require!(
+        shard_index < election.shard_count,
+        GovError::InvalidTallyShard
+    );
+
+    tally_shard.election = election.key();
+    tally_shard.shard_index = shard_index;
+    tally_shard.vote_counts = vec![0; election.candidates.len()];
+    tally_shard.total_votes = 0;
+    tally_shard.abstain_votes = 0;
+    tally_shard.blank_votes = 0;
+    tally_shard.total_weight = 0;
+    tally_shard.used_nullifiers = Vec::new();
+    tally_shard.merged = false;
+    tally_shard.bump = ctx.bumps.tally_shard;
+
+    msg!("Tally shard {} initialized", shard_index);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CastShardedVote<'info> {
+    /// Read-only: sharded votes never write-lock the election
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"tally_shard", election.key().as_ref(), &[tally_shard.shard_index]],
+        bump = tally_shard.bump
+    )]
+    pub tally_shard: Account<'info, TallyShard>,
+
+    /// Voter registration account (only required for legacy mode)
+    #[account(
+        seeds = [
+            b"voter_registration",
+            election.voter_registry.as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub voter_registration: Option<Account<'info, VoterRegistration>>,
+
+    /// Pays for growing the shard when its nullifiers run out of room
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    /// CHECK: Attestation account (optional, only for compression mode proof verification)
+    pub attestation: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Cast a vote into the tally shard selected by the voter's nullifier
+///
+/// A full shard grows by TallyShard::NULLIFIER_GROWTH nullifiers, paid by the voter.
+pub fn cast_sharded_vote(
+    ctx: Context<CastShardedVote>,
+    ballot: Ballot,
+    merkle_proof: Vec<[u8; 32]>,
+    leaf_index: Option<u32>,
+    registered_at: Option<i64>,
+    voter_weight: Option<u64>,
+) -> Result<()> {
+    let election = &ctx.accounts.election;
+    let tally_shard = &mut ctx.accounts.tally_shard;
+    let clock = Clock::get()?;
+
+    require!(
+        election.is_sharded(),
+        GovError::ElectionNotSharded
+    );
+
+    // Verify election is accepting votes
+    election.require_active(&clock)?;
+
+    // Shards only hold vote_counts
+    require!(
+        election.voting_method.tallies_on_election(),
+        GovError::UnsupportedVotingMethod
+    );
+
+    // Verify ballot is valid for this election
+    validate_ballot(&election.voting_method, election.candidates.len(), election.seats, &ballot)?;
+
+    // The shard must count the same candidates as the election
+    require!(
+        tally_shard.vote_counts.len() == election.candidates.len(),
+        GovError::InvalidTallyShard
+    );
+
+    let voter_key = ctx.accounts.voter.key();
+    let election_key = election.key();
+
+    let weight = verify_voter_eligibility(
+        election,
+        voter_key,
+        ctx.accounts.voter_registration.as_ref(),
+        ctx.accounts.attestation.as_ref().map(|attestation| attestation.key()),
+        VoterProof {
+            merkle_proof: &merkle_proof,
+            leaf_index,
+            registered_at,
+            weight: voter_weight,
+        },
+    )?;
+
+    check_credit_budget(&election.voting_method, election.seats, &ballot, weight)?;
+
+    let nullifier = VoteNullifier::new(&voter_key, &election_key, 0);
+
+    // The voter's nullifier decides which shard holds their vote
+    require!(
+        TallyShard::index_for(&nullifier.nullifier_hash, election.shard_count) == tally_shard.shard_index,
+        GovError::InvalidTallyShard
+    );
+
+    require!(
+        !tally_shard.used_nullifiers.contains(&nullifier.nullifier_hash),
+        GovError::AlreadyVoted
+    );
+
+    let nullifier_count = tally_shard.used_nullifiers.len();
+    reserve_space(
+        &tally_shard.to_account_info(),
+        &ctx.accounts.voter.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        TallyShard::space(nullifier_count + 1),
+        TallyShard::space(nullifier_count + TallyShard::NULLIFIER_GROWTH),
+    )?;
+
+    // Record the vote in the shard
+    apply_ballot(&election.voting_method, &mut tally_shard.vote_counts, &ballot, weight)?;
+
+    // Abstain and blank ballots have their own counters
+    tally_shard.count_ballot(&ballot, weight)?;
+
+    tally_shard.used_nullifiers.push(nullifier.nullifier_hash);
+
+    msg!("Vote cast in shard {}: {:?}", tally_shard.shard_index, ballot);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MergeTallyShard<'info> {
+    #[account(mut)]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"tally_shard", election.key().as_ref(), &[tally_shard.shard_index]],
+        bump = tally_shard.bump
+    )]
+    pub tally_shard: Account<'info, TallyShard>,
+}
+
+/// Fold a tally shard into the election's vote_counts (permissionless)
+///
+/// Can only be called once voting has closed. Hooks run only after every
+/// shard has been merged.
+pub fn merge_tally_shard(
+    ctx: Context<MergeTallyShard>,
+) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    let tally_shard = &mut ctx.accounts.tally_shard;
+    let clock = Clock::get()?;
+
+    require!(
+        election.is_sharded(),
+        GovError::ElectionNotSharded
+    );
+
+    require!(
+        election.effective_status(&clock) == ElectionStatus::Ended,
+        GovError::ElectionNotEnded
+    );
+
+    require!(
+        !tally_shard.merged,
+        GovError::ShardAlreadyMerged
+    );
+
+    // Only the configured shards count toward shards_merged, and every
+    // candidate of the shard must have a count to fold into
+    require!(
+        tally_shard.shard_index < election.shard_count
+            && tally_shard.vote_counts.len() == election.vote_counts.len(),
+        GovError::InvalidTallyShard
+    );
+
+    for (count, shard_count) in election.vote_counts.iter_mut().zip(tally_shard.vote_counts.iter()) {
+        *count = count
+            .checked_add(*shard_count)
+            .ok_or(GovError::ArithmeticOverflow)?;
+    }
+
+    election.total_votes = election.total_votes
+        .checked_add(tally_shard.total_votes)
+        .ok_or(GovError::ArithmeticOverflow)?;
+
+    election.total_weight = election.total_weight
+        .checked_add(tally_shard.total_weight)
+        .ok_or(GovError::ArithmeticOverflow)?;
+
+    election.abstain_votes = election.abstain_votes
+        .checked_add(tally_shard.abstain_votes)
+        .ok_or(GovError::ArithmeticOverflow)?;
+
+    election.blank_votes = election.blank_votes
+        .checked_add(tally_shard.blank_votes)
+        .ok_or(GovError::ArithmeticOverflow)?;
+
+    election.shards_merged = election.shards_merged
+        .checked_add(1)
+        .ok_or(GovError::ArithmeticOverflow)?;
+
+    tally_shard.merged = true;
+
+    msg!("Tally shard {} merged ({}/{})", tally_shard.shard_index, election.shards_merged, election.shard_count);
+    msg!("Total votes: {}", election.total_votes);
+
+    Ok(())
+}
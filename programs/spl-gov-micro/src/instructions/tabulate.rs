@@ -0,0 +1,434 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::utils::irv::{irv_round, IrvOutcome};
+use crate::utils::stv::{stv_round, droop_quota, initial_weights, StvOutcome};
+use crate::utils::tally::{score_winner, top_candidates, tally_proposal, select_within_budget, ProposalOutcome};
+use crate::utils::schulze::schulze;
+use crate::instructions::conviction_ix::advance_conviction_state;
+use crate::instructions::ranked_ballots::reserve_ballot_weights;
+
+#[derive(Accounts)]
+pub struct TabulateElection<'info> {
+    pub election: Account<'info, Election>,
+
+    /// Aggregated ranked ballots (only required for ranked voting methods)
+    /// Holds the STV ranking group values between calls.
+    #[account(
+        mut,
+        seeds = [b"ranked_ballots", election.key().as_ref()],
+        bump = ranked_ballots.bump
+    )]
+    pub ranked_ballots: Option<Account<'info, RankedBallots>>,
+
+    /// Pairwise preference matrix (only required for Condorcet)
+    #[account(
+        seeds = [b"pairwise_matrix", election.key().as_ref()],
+        bump = pairwise_matrix.bump
+    )]
+    pub pairwise_matrix: Option<Account<'info, PairwiseMatrix>>,
+
+    /// Conviction state (only required for conviction voting)
+    #[account(
+        mut,
+        seeds = [b"conviction", election.key().as_ref()],
+        bump = conviction_state.bump
+    )]
+    pub conviction_state: Option<Account<'info, ConvictionState>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = ElectionResult::space(&election.voting_method),
+        seeds = [b"result", election.key().as_ref()],
+        bump
+    )]
+    pub result: Account<'info, ElectionResult>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Tabulate an ended election into its result record (permissionless)
+///
+/// Only final outcomes are tabulated: the challenge window must have passed
+/// with every challenge resolved, and the election must not be cancelled.
+/// Runs at most `max_rounds` rounds per call so long tabulations can be
+/// spread across several transactions. Calling again resumes where the
+/// previous call stopped.
+pub fn handler(
+    ctx: Context<TabulateElection>,
+    max_rounds: u8,
+) -> Result<()> {
+    let election = &ctx.accounts.election;
+    let result = &mut ctx.accounts.result;
+    let clock = Clock::get()?;
+
+    // An upheld challenge voids the results
+    require!(
+        election.effective_status(&clock) != ElectionStatus::Cancelled,
+        GovError::ElectionCancelled
+    );
+
+    require!(
+        election.effective_status(&clock) == ElectionStatus::Ended,
+        GovError::ElectionNotEnded
+    );
+
+    // Results stay blocked during the challenge window and while disputed
+    require!(
+        election.outcome_final(&clock),
+        GovError::OutcomeNotFinal
+    );
+
+    require!(
+        election.all_shards_merged(),
+        GovError::ShardsNotMerged
+    );
+
+    // Initialize result on first call
+    if result.election == Pubkey::default() {
+        result.election = election.key();
+        result.voting_method = election.voting_method;
+        result.complete = false;
+        result.winners = Vec::new();
+        result.tied_at_cutoff = Vec::new();
+        result.eliminated = Vec::new();
+        result.rounds = Vec::new();
+        result.quota = 0;
+        result.order = Vec::new();
+        result.pairwise = Vec::new();
+        result.strengths = Vec::new();
+        result.budget_spent = 0;
+        result.bump = ctx.bumps.result;
+    }
+
+    if result.complete {
+        msg!("Tabulation already complete");
+        return Ok(());
+    }
+
+    match election.voting_method {
+        VotingMethod::RankedChoice => {
+            let ranked_ballots = ctx.accounts.ranked_ballots
+                .as_ref()
+                .ok_or(GovError::RankedBallotsRequired)?;
+
+            tabulate_instant_runoff(result, election.candidates.len(), &ranked_ballots.rankings, max_rounds)?;
+        }
+        VotingMethod::SingleTransferableVote => {
+            let ranked_ballots = ctx.accounts.ranked_ballots
+                .as_mut()
+                .ok_or(GovError::RankedBallotsRequired)?;
+
+            // Large ballot stores take several calls to fit the ranking group values
+            if result.rounds.is_empty() {
+                let payer = ctx.accounts.payer.to_account_info();
+                let system_program = ctx.accounts.system_program.to_account_info();
+
+                if !reserve_ballot_weights(ranked_ballots, &payer, &system_program)? {
+                    msg!("Ranked ballots grown to {} bytes, call again to tabulate", ranked_ballots.to_account_info().data_len());
+                    return Ok(());
+                }
+            }
+
+            tabulate_single_transferable_vote(
+                result,
+                election.candidates.len(),
+                election.seats,
+                ranked_ballots,
+                max_rounds,
+            )?;
+        }
+        VotingMethod::Condorcet => {
+            let pairwise_matrix = ctx.accounts.pairwise_matrix
+                .as_ref()
+                .ok_or(GovError::PairwiseMatrixRequired)?;
+
+            tabulate_schulze(result, &election.vote_counts, pairwise_matrix);
+        }
+        VotingMethod::Proposal { quorum_bps, pass_threshold_bps, veto_threshold_bps } => {
+            let outcome = tally_proposal(
+                quorum_bps,
+                pass_threshold_bps,
+                veto_threshold_bps,
+                &election.vote_counts,
+                election.abstentions(),
+                election.eligible_weight(),
+            );
+
+            tabulate_proposal(result, outcome, &election.vote_counts);
+        }
+        VotingMethod::Conviction => {
+            let conviction_state = ctx.accounts.conviction_state
+                .as_mut()
+                .ok_or(GovError::ConvictionStateRequired)?;
+
+            // Settle the last periods before end_time
+            advance_conviction_state(conviction_state, election, &clock)?;
+
+            tabulate_conviction(result, conviction_state);
+        }
+        VotingMethod::Budgeting { .. } => {
+            require!(
+                election.candidate_amounts.len() == election.candidates.len(),
+                GovError::InvalidCandidateAmounts
+            );
+
+            tabulate_budget(result, &election.vote_counts, &election.candidate_amounts, election.budget_cap);
+        }
+        VotingMethod::Score { winner_rule, .. } => {
+            tabulate_scores(result, winner_rule, &election.vote_counts, &election.score_ballots);
+        }
+        _ => tabulate_vote_counts(result, &election.vote_counts, election.seats),
+    }
+
+    msg!("Tabulation rounds: {}, complete: {}", result.rounds.len(), result.complete);
+    if result.complete {
+        msg!("Winners: {:?}", result.winners);
+    }
+
+    Ok(())
+}
+
+/// Run IRV rounds until a winner is found or the round budget is spent
+fn tabulate_instant_runoff(
+    result: &mut ElectionResult,
+    candidate_count: usize,
+    rankings: &[RankingCount],
+    max_rounds: u8,
+) -> Result<()> {
+    for _ in 0..max_rounds {
+        let round = irv_round(candidate_count, rankings, &result.eliminated)?;
+
+        let (elected, eliminated) = match round.outcome {
+            IrvOutcome::Eliminated(candidate) => {
+                result.eliminated.push(candidate);
+                (Vec::new(), Some(candidate))
+            }
+            IrvOutcome::Winner(candidate) => {
+                result.winners.push(candidate);
+                result.complete = true;
+                (vec![candidate], None)
+            }
+            IrvOutcome::NoWinner => {
+                result.complete = true;
+                (Vec::new(), None)
+            }
+        };
+
+        result.rounds.push(TabulationRound {
+            tallies: widen(&round.tallies),
+            exhausted: round.exhausted as u128,
+            elected,
+            eliminated,
+        });
+
+        if result.complete {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Run STV rounds until every seat is filled or the round budget is spent
+///
+/// The quota lives in the result and the ranking group values in the ranked
+/// ballots, so the next call resumes with exactly the same state.
+fn tabulate_single_transferable_vote(
+    result: &mut ElectionResult,
+    candidate_count: usize,
+    seats: u8,
+    ranked_ballots: &mut RankedBallots,
+    max_rounds: u8,
+) -> Result<()> {
+    let rankings = &ranked_ballots.rankings;
+
+    if result.rounds.is_empty() {
+        result.quota = droop_quota(rankings, seats)?;
+        ranked_ballots.ballot_weights = initial_weights(rankings)?;
+    }
+
+    for _ in 0..max_rounds {
+        let round = stv_round(
+            candidate_count,
+            seats,
+            result.quota,
+            rankings,
+            &mut ranked_ballots.ballot_weights,
+            &result.winners,
+            &result.eliminated,
+        )?;
+
+        let (elected, eliminated) = match round.outcome {
+            StvOutcome::Elected(candidate) => (vec![candidate], None),
+            StvOutcome::ElectedRemaining(candidates) => (candidates, None),
+            StvOutcome::Eliminated(candidate) => {
+                result.eliminated.push(candidate);
+                (Vec::new(), Some(candidate))
+            }
+            StvOutcome::NoWinner => {
+                result.complete = true;
+                (Vec::new(), None)
+            }
+        };
+
+        result.winners.extend_from_slice(&elected);
+        if result.winners.len() >= seats as usize {
+            result.complete = true;
+        }
+
+        result.rounds.push(TabulationRound {
+            tallies: round.tallies,
+            exhausted: round.exhausted,
+            elected,
+            eliminated,
+        });
+
+        if result.complete {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Single round: the candidates with the most votes fill the seats (ties go to the earlier candidate)
+fn tabulate_vote_counts(
+    result: &mut ElectionResult,
+    vote_counts: &[u64],
+    seats: u8,
+) {
+    let top = top_candidates(vote_counts, seats);
+    result.winners = top.winners;
+    result.tied_at_cutoff = top.tied_at_cutoff;
+
+    if !result.tied_at_cutoff.is_empty() {
+        msg!("Tie at the cutoff between {:?}, seated by candidate order", result.tied_at_cutoff);
+    }
+
+    result.rounds.push(TabulationRound {
+        tallies: widen(vote_counts),
+        exhausted: 0,
+        elected: result.winners.clone(),
+        eliminated: None,
+    });
+    result.complete = true;
+}
+
+/// Single round: score sums as tallies, winner picked by the configured rule
+fn tabulate_scores(
+    result: &mut ElectionResult,
+    winner_rule: ScoreRule,
+    sums: &[u64],
+    ballots: &[u64],
+) {
+    if let Some(winner) = score_winner(winner_rule, sums, ballots) {
+        result.winners.push(winner);
+    }
+
+    result.rounds.push(TabulationRound {
+        tallies: widen(sums),
+        exhausted: 0,
+        elected: result.winners.clone(),
+        eliminated: None,
+    });
+    result.complete = true;
+}
+
+/// Single round: Schulze order over the pairwise matrix
+///
+/// The round tallies are first preferences; the matrix and beat-path
+/// strengths are copied into the result for independent verification.
+fn tabulate_schulze(
+    result: &mut ElectionResult,
+    first_preferences: &[u64],
+    pairwise_matrix: &PairwiseMatrix,
+) {
+    let outcome = schulze(pairwise_matrix.candidate_count as usize, &pairwise_matrix.preferences);
+
+    // Without ballots there is no winner, only the index order
+    if first_preferences.iter().any(|votes| *votes > 0) {
+        result.winners.push(outcome.order[0]);
+    }
+
+    result.order = outcome.order;
+    result.pairwise = pairwise_matrix.preferences.clone();
+    result.strengths = outcome.strengths;
+
+    result.rounds.push(TabulationRound {
+        tallies: widen(first_preferences),
+        exhausted: 0,
+        elected: result.winners.clone(),
+        eliminated: None,
+    });
+    result.complete = true;
+}
+
+/// Round tallies from u64 counts
+fn widen(counts: &[u64]) -> Vec<u128> {
+    counts.iter().map(|count| *count as u128).collect()
+}
+
+/// Single round: the proposal's decisive option (Yes or NoWithVeto) if any
+fn tabulate_proposal(
+    result: &mut ElectionResult,
+    outcome: ProposalOutcome,
+    vote_counts: &[u64],
+) {
+    msg!("Quorum: {}, vetoed: {}, passed: {}", outcome.quorum_reached, outcome.vetoed, outcome.passed);
+
+    if let Some(option) = outcome.decisive_option() {
+        result.winners.push(option.index() as u8);
+    }
+
+    result.rounds.push(TabulationRound {
+        tallies: widen(vote_counts),
+        exhausted: 0,
+        elected: result.winners.clone(),
+        eliminated: None,
+    });
+    result.complete = true;
+}
+
+/// Single round: every proposal that passed during the election, in order
+/// The round tallies are the final convictions (fixed-point units).
+fn tabulate_conviction(
+    result: &mut ElectionResult,
+    conviction_state: &ConvictionState,
+) {
+    result.winners = conviction_state.passed.clone();
+
+    result.rounds.push(TabulationRound {
+        tallies: widen(&conviction_state.conviction),
+        exhausted: 0,
+        elected: result.winners.clone(),
+        eliminated: None,
+    });
+    result.complete = true;
+}
+
+/// Single round: projects funded greedily by votes within the budget cap
+fn tabulate_budget(
+    result: &mut ElectionResult,
+    vote_counts: &[u64],
+    costs: &[u64],
+    budget_cap: u64,
+) {
+    let selection = select_within_budget(vote_counts, costs, budget_cap);
+    result.winners = selection.winners;
+    result.budget_spent = selection.spent;
+
+    msg!("Budget spent: {}/{}", result.budget_spent, budget_cap);
+
+    result.rounds.push(TabulationRound {
+        tallies: widen(vote_counts),
+        exhausted: 0,
+        elected: result.winners.clone(),
+        eliminated: None,
+    });
+    result.complete = true;
+}
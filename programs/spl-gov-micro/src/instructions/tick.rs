@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct Tick<'info> {
+    #[account(mut)]
+    pub election: Account<'info, Election>,
+}
+
+/// Persist the election's derived status (permissionless)
+///
+/// Status transitions are otherwise only written as a side effect of other
+/// instructions. Anyone can tick an election so the stored status can be
+/// trusted by readers.
+pub fn handler(
+    ctx: Context<Tick>,
+) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    let clock = Clock::get()?;
+
+    let previous = election.status;
+    election.status = election.effective_status(&clock);
+
+    if previous != election.status {
+        msg!("Election status: {:?} -> {:?}", previous, election.status);
+    } else {
+        msg!("Election status unchanged: {:?}", election.status);
+    }
+
+    Ok(())
+}
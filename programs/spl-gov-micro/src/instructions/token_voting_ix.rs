@@ -0,0 +1,494 @@
+//! Token-Weighted Voting
+//!
+//! Votes in a token-weighted election count the voter's balance of the
+//! election's mint instead of their registered weight, optionally capped per
+//! voter. Voters still have to be registered to vote.
+//!
+//! - set_token_weighting: authority picks the mint, snapshot and cap
+//! - prove_token_balance: voter proves their balance in a Merkle snapshot
+//! - deposit_tokens: voter moves tokens into the election's escrow vault
+//! - cast_vote: votes with the proven or deposited balance
+//! - retract_vote: voter takes their ballot back out (requires revoting)
+//! - withdraw_tokens: voter takes their deposit back once their vote no
+//!   longer counts, either after end_time or after retracting, or closes a
+//!   weight proven under a Merkle snapshot
+//!
+//! Escrowed tokens can leave before the end only while no vote of the voter
+//! counts. Without revoting a cast vote can't be retracted, so the deposit
+//! stays in the vault until the election ends or is cancelled.
+//!
+//! Balances come from one of two snapshots: a Merkle root of (owner, balance)
+//! pairs, or live balances under a lock. The program has no authority to
+//! lock tokens in the voter's own wallet, where they could be moved to
+//! another wallet and counted again after a vote. So the live balance is
+//! read from the voter's token account as it moves into the escrow vault
+//! (deposit_tokens), and the vault is the lock for as long as the vote
+//! counts. A Merkle snapshot entry belongs to one owner.
+//!
+//! Token weights only apply to cast_vote, so token-weighted elections take
+//! no batches, shards, ballot groups, conviction stakes or delegations.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::*;
+use crate::errors::*;
+use crate::utils::merkle::{create_token_balance_leaf, verify_merkle_proof};
+use crate::utils::tally::remove_ballot;
+
+#[derive(Accounts)]
+pub struct SetTokenWeighting<'info> {
+    #[account(mut)]
+    pub election: Account<'info, Election>,
+
+    /// Mint whose balances are counted
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: Election's escrow vault, only checked for existence
+    #[account(
+        seeds = [b"token_vault", election.key().as_ref()],
+        bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Weight votes by token balances (only authority, before start)
+///
+/// Merkle snapshots carry the total balance they cover; escrow uses the
+/// mint's current supply as the eligible weight for quorum. The program
+/// cannot sum the snapshot's leaves, so the authority's total_balance is
+/// trusted as is.
+///
+/// Once voters have deposited (the vault exists) an escrow configuration is
+/// final, since withdrawals need the vault under the same mint.
+pub fn set_token_weighting(
+    ctx: Context<SetTokenWeighting>,
+    snapshot: TokenSnapshot,
+    max_voter_weight: u64,
+) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    let mint = &ctx.accounts.mint;
+    let clock = Clock::get()?;
+
+    require!(
+        election.authority == ctx.accounts.authority.key(),
+        GovError::Unauthorized
+    );
+
+    require!(
+        election.effective_status(&clock) == ElectionStatus::Pending,
+        GovError::ElectionAlreadyStarted
+    );
+
+    require!(
+        !election.is_sharded(),
+        GovError::ElectionIsSharded
+    );
+
+    require!(
+        election.ballot_group.is_none(),
+        GovError::ElectionInBallotGroup
+    );
+
+    require!(
+        ctx.accounts.vault.data_is_empty(),
+        GovError::TokenWeightingLocked
+    );
+
+    let supply = match snapshot {
+        TokenSnapshot::Merkle { total_balance, .. } => total_balance,
+        TokenSnapshot::Escrow => mint.supply,
+    };
+
+    require!(
+        supply > 0,
+        GovError::InvalidTokenSnapshot
+    );
+
+    election.token_weighting = Some(TokenWeighting {
+        mint: mint.key(),
+        snapshot,
+        max_voter_weight,
+        supply,
+    });
+
+    msg!("Token weighting: mint {}, {:?}", mint.key(), snapshot);
+    msg!("Supply: {}, max voter weight: {}", supply, max_voter_weight);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProveTokenBalance<'info> {
+    pub election: Account<'info, Election>,
+
+    #[account(
+        init,
+        payer = voter,
+        space = TokenVoterWeight::SIZE,
+        seeds = [b"token_weight", election.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub token_voter_weight: Account<'info, TokenVoterWeight>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Prove the voter's balance in the election's Merkle snapshot
+///
+/// The leaf is (voter, balance); the proven balance is locked for the
+/// election in the voter's TokenVoterWeight.
+pub fn prove_token_balance(
+    ctx: Context<ProveTokenBalance>,
+    balance: u64,
+    merkle_proof: Vec<[u8; 32]>,
+    leaf_index: u32,
+) -> Result<()> {
+    let election = &ctx.accounts.election;
+    let clock = Clock::get()?;
+
+    let token_weighting = election.token_weighting.ok_or(GovError::NotTokenWeighted)?;
+
+    let root = match token_weighting.snapshot {
+        TokenSnapshot::Merkle { root, .. } => root,
+        TokenSnapshot::Escrow => return err!(GovError::InvalidTokenSnapshot),
+    };
+
+    require!(
+        matches!(
+            election.effective_status(&clock),
+            ElectionStatus::Pending | ElectionStatus::Active
+        ),
+        GovError::ElectionEnded
+    );
+
+    let voter_key = ctx.accounts.voter.key();
+    let leaf = create_token_balance_leaf(&voter_key, balance);
+    require!(
+        verify_merkle_proof(leaf, root, &merkle_proof, leaf_index)?,
+        GovError::InvalidMerkleProof
+    );
+
+    let weight = token_weighting.weight(balance);
+    require!(
+        weight > 0,
+        GovError::InvalidVoterWeight
+    );
+
+    let token_voter_weight = &mut ctx.accounts.token_voter_weight;
+    token_voter_weight.election = election.key();
+    token_voter_weight.voter = voter_key;
+    token_voter_weight.token_account = Pubkey::default();
+    token_voter_weight.balance = balance;
+    token_voter_weight.weight = weight;
+    token_voter_weight.vote_counted = false;
+    token_voter_weight.bump = ctx.bumps.token_voter_weight;
+
+    msg!("Token balance proven for voter {}: {} (weight {})", voter_key, balance, weight);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DepositTokens<'info> {
+    pub election: Account<'info, Election>,
+
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = TokenVoterWeight::SIZE,
+        seeds = [b"token_weight", election.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub token_voter_weight: Account<'info, TokenVoterWeight>,
+
+    /// Election's escrow vault (its own authority)
+    #[account(
+        init_if_needed,
+        payer = voter,
+        seeds = [b"token_vault", election.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = voter_token_account.owner == voter.key()
+            && voter_token_account.mint == mint.key() @ GovError::InvalidTokenAccount
+    )]
+    pub voter_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposit tokens into the election's escrow vault
+///
+/// Deposits add up and set the weight of the voter's next vote. They are
+/// refused while a vote of the voter counts. A balance proven before the
+/// election switched to escrow is replaced by the first deposit.
+pub fn deposit_tokens(
+    ctx: Context<DepositTokens>,
+    amount: u64,
+) -> Result<()> {
+    let election = &ctx.accounts.election;
+    let clock = Clock::get()?;
+
+    let token_weighting = election.token_weighting.ok_or(GovError::NotTokenWeighted)?;
+
+    require!(
+        token_weighting.snapshot == TokenSnapshot::Escrow,
+        GovError::InvalidTokenSnapshot
+    );
+
+    require!(
+        ctx.accounts.mint.key() == token_weighting.mint,
+        GovError::InvalidTokenAccount
+    );
+
+    require!(
+        matches!(
+            election.effective_status(&clock),
+            ElectionStatus::Pending | ElectionStatus::Active
+        ),
+        GovError::ElectionEnded
+    );
+
+    require!(
+        amount > 0,
+        GovError::InvalidTokenAmount
+    );
+
+    let voter_key = ctx.accounts.voter.key();
+    let token_voter_weight = &mut ctx.accounts.token_voter_weight;
+
+    if token_voter_weight.election == Pubkey::default() {
+        token_voter_weight.election = election.key();
+        token_voter_weight.voter = voter_key;
+        token_voter_weight.balance = 0;
+        token_voter_weight.vote_counted = false;
+        token_voter_weight.bump = ctx.bumps.token_voter_weight;
+    }
+
+    require!(
+        !token_voter_weight.vote_counted,
+        GovError::EscrowLocked
+    );
+
+    // A balance proven under an earlier Merkle snapshot holds no tokens here
+    if !token_voter_weight.is_escrowed() {
+        token_voter_weight.balance = 0;
+    }
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.voter_token_account.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.voter.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    token_voter_weight.token_account = ctx.accounts.voter_token_account.key();
+    token_voter_weight.balance = token_voter_weight.balance
+        .checked_add(amount)
+        .ok_or(GovError::ArithmeticOverflow)?;
+    token_voter_weight.weight = token_weighting.weight(token_voter_weight.balance);
+
+    msg!("Deposited {} tokens for voter {} (escrowed {}, weight {})",
+        amount, voter_key, token_voter_weight.balance, token_voter_weight.weight);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RetractVote<'info> {
+    #[account(mut)]
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        seeds = [b"nullifiers", election.key().as_ref()],
+        bump = nullifier_set.bump
+    )]
+    pub nullifier_set: Account<'info, NullifierSet>,
+
+    /// Voter's current ballot (only exists when revoting is allowed)
+    #[account(
+        mut,
+        has_one = voter,
+        close = voter,
+        seeds = [b"voter_ballot", election.key().as_ref(), voter.key().as_ref()],
+        bump = voter_ballot.bump
+    )]
+    pub voter_ballot: Option<Account<'info, VoterBallot>>,
+
+    #[account(
+        mut,
+        has_one = voter,
+        seeds = [b"token_weight", election.key().as_ref(), voter.key().as_ref()],
+        bump = token_voter_weight.bump
+    )]
+    pub token_voter_weight: Account<'info, TokenVoterWeight>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+}
+
+/// Take the voter's ballot back out of the tally while the election is active
+///
+/// Only token-weighted elections that allow revoting, whose ballots carry no
+/// delegations, allow retracting. The voter's nullifier is released, so they
+/// can vote again.
+pub fn retract_vote(
+    ctx: Context<RetractVote>,
+) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    let clock = Clock::get()?;
+
+    election.require_active(&clock)?;
+
+    require!(
+        election.is_token_weighted(),
+        GovError::NotTokenWeighted
+    );
+
+    // Without revoting there is no ballot record to take back out
+    require!(
+        election.allow_revoting,
+        GovError::RevotingDisabled
+    );
+
+    let voter_ballot = ctx.accounts.voter_ballot
+        .as_ref()
+        .ok_or(GovError::VoterBallotRequired)?;
+
+    // Revoting elections tally on vote_counts alone (see set_revoting)
+    let voting_method = election.voting_method;
+    remove_ballot(&voting_method, &mut election.vote_counts, &voter_ballot.ballot, voter_ballot.weight)?;
+    election.uncount_ballot(&voter_ballot.ballot, voter_ballot.weight)?;
+
+    let voter_key = ctx.accounts.voter.key();
+    let nullifier = VoteNullifier::new(&voter_key, &election.key(), 0);
+    ctx.accounts.nullifier_set.used_nullifiers.retain(|hash| *hash != nullifier.nullifier_hash);
+
+    // Escrowed tokens can be withdrawn again
+    ctx.accounts.token_voter_weight.vote_counted = false;
+
+    msg!("Vote retracted by voter {}", voter_key);
+    msg!("Total votes: {} (abstain: {}, blank: {})", election.total_votes, election.abstain_votes, election.blank_votes);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTokens<'info> {
+    pub election: Account<'info, Election>,
+
+    #[account(
+        mut,
+        has_one = voter,
+        close = voter,
+        seeds = [b"token_weight", election.key().as_ref(), voter.key().as_ref()],
+        bump = token_voter_weight.bump
+    )]
+    pub token_voter_weight: Account<'info, TokenVoterWeight>,
+
+    /// Election's escrow vault (only required for escrowed balances)
+    #[account(
+        mut,
+        seeds = [b"token_vault", election.key().as_ref()],
+        bump
+    )]
+    pub vault: Option<Account<'info, TokenAccount>>,
+
+    /// Voter's token account receiving the balance (only required for escrowed balances)
+    #[account(
+        mut,
+        constraint = voter_token_account.owner == voter.key() @ GovError::InvalidTokenAccount
+    )]
+    pub voter_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Withdraw the voter's escrowed tokens and close their token voter weight
+///
+/// Allowed once the election has ended or been cancelled, or while no vote of
+/// the voter counts (never voted, or retracted). Retracting needs revoting, so
+/// in elections without it a voter's deposit is locked from their vote until
+/// the end. The deposited balance is paid out whatever the election's token
+/// weighting says now; a balance proven under a Merkle snapshot never entered
+/// the vault and is closed without a transfer.
+pub fn withdraw_tokens(
+    ctx: Context<WithdrawTokens>,
+) -> Result<()> {
+    let election = &ctx.accounts.election;
+    let token_voter_weight = &ctx.accounts.token_voter_weight;
+    let clock = Clock::get()?;
+
+    require!(
+        !token_voter_weight.vote_counted
+            || matches!(
+                election.effective_status(&clock),
+                ElectionStatus::Ended | ElectionStatus::Cancelled
+            ),
+        GovError::EscrowLocked
+    );
+
+    if !token_voter_weight.is_escrowed() {
+        msg!("Closed proven token weight of voter {}", token_voter_weight.voter);
+        return Ok(());
+    }
+
+    let vault = ctx.accounts.vault
+        .as_ref()
+        .ok_or(GovError::InvalidTokenAccount)?;
+    let voter_token_account = ctx.accounts.voter_token_account
+        .as_ref()
+        .ok_or(GovError::InvalidTokenAccount)?;
+
+    require!(
+        voter_token_account.mint == vault.mint,
+        GovError::InvalidTokenAccount
+    );
+
+    let election_key = election.key();
+    let vault_bump = ctx.bumps.vault.ok_or(GovError::InvalidTokenAccount)?;
+    let vault_seeds: &[&[u8]] = &[b"token_vault", election_key.as_ref(), &[vault_bump]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to: voter_token_account.to_account_info(),
+                authority: vault.to_account_info(),
+            },
+            &[vault_seeds],
+        ),
+        token_voter_weight.balance,
+    )?;
+
+    msg!("Withdrew {} escrowed tokens for voter {}", token_voter_weight.balance, token_voter_weight.voter);
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::utils::tally::validate_voting_method;
+
+#[derive(Accounts)]
+pub struct SetVotingMethod<'info> {
+    #[account(mut)]
+    pub election: Account<'info, Election>,
+
+    /// CHECK: Ranked ballot store of the election, which must not exist when the candidates change
+    #[account(seeds = [b"ranked_ballots", election.key().as_ref()], bump)]
+    pub ranked_ballots: UncheckedAccount<'info>,
+
+    /// CHECK: Pairwise matrix of the election, which must not exist when the candidates change
+    #[account(seeds = [b"pairwise_matrix", election.key().as_ref()], bump)]
+    pub pairwise_matrix: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Set how ballots are cast and counted, and how many seats are filled
+///
+/// Can only be set before election starts. Switching to a proposal replaces
+/// the candidates with the proposal options, so nothing sized by the old
+/// candidates may exist yet, and a proposal can't switch back.
+pub fn handler(
+    ctx: Context<SetVotingMethod>,
+    voting_method: VotingMethod,
+    seats: u8,
+) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    let clock = Clock::get()?;
+
+    require!(
+        election.authority == ctx.accounts.authority.key(),
+        GovError::Unauthorized
+    );
+
+    require!(
+        election.effective_status(&clock) == ElectionStatus::Pending,
+        GovError::ElectionAlreadyStarted
+    );
+
+    let is_proposal = |method: &VotingMethod| matches!(method, VotingMethod::Proposal { .. });
+
+    // The original candidates are gone once replaced by the proposal options
+    require!(
+        is_proposal(&voting_method) || !is_proposal(&election.voting_method),
+        GovError::ProposalOptionsFixed
+    );
+
+    // Proposals always use the fixed Yes / No / Abstain / NoWithVeto options
+    if is_proposal(&voting_method) && !is_proposal(&election.voting_method) {
+        // Shards, amounts and stores sized by the old candidates would go stale
+        require!(
+            !election.is_sharded()
+                && election.candidate_amounts.is_empty()
+                && ctx.accounts.ranked_ballots.data_is_empty()
+                && ctx.accounts.pairwise_matrix.data_is_empty(),
+            GovError::CandidatesInUse
+        );
+
+        election.candidates = ProposalOption::ALL
+            .iter()
+            .map(|option| option.name().to_string())
+            .collect();
+        election.vote_counts = vec![0; election.candidates.len()];
+        election.score_ballots = vec![0; election.candidates.len()];
+    }
+
+    validate_voting_method(&voting_method, election.candidates.len(), seats)?;
+
+    // Shards, ballot groups and revoting only carry vote_counts
+    require!(
+        voting_method.tallies_on_election()
+            || (!election.is_sharded() && election.ballot_group.is_none() && !election.allow_revoting),
+        GovError::UnsupportedVotingMethod
+    );
+
+    election.voting_method = voting_method;
+    election.seats = seats;
+
+    msg!("Voting method set: {:?} ({} seats)", voting_method, seats);
+
+    Ok(())
+}
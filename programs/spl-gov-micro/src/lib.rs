@@ -5,11 +5,13 @@ pub mod errors;
 pub mod state;
 pub mod instructions;
 pub mod utils;
+pub mod events;
 
 // Re-exports
 pub use errors::*;
 pub use state::*;
 pub use instructions::*;
+pub use events::*;
 
 // Program ID - This will be updated after deployment
 declare_id!("G3oRp71dn6S5TRmhXWXaURzGTtk485zSdZ6Xy46JkRDR");
@@ -26,26 +28,52 @@ pub mod mpl_gov_micro {
         end_time: i64,
         use_compression: bool,
         max_voters: u32,
+        time_base: TimeBase,
     ) -> Result<()> {
-        instructions::create_election::handler(ctx, candidates, start_time, end_time, use_compression, max_voters)
+        instructions::create_election::handler(ctx, candidates, start_time, end_time, use_compression, max_voters, time_base)
+    }
+
+    /// Create a new election with the configuration of an existing one
+    pub fn clone_election(
+        ctx: Context<CloneElection>,
+        clone_id: u64,
+        start_time: i64,
+        end_time: i64,
+        reuse_voter_registry: bool,
+    ) -> Result<()> {
+        instructions::clone_election::handler(ctx, clone_id, start_time, end_time, reuse_voter_registry)
+    }
+
+    /// Set how ballots are cast and counted and the seats to fill (only authority, before start)
+    pub fn set_voting_method(
+        ctx: Context<SetVotingMethod>,
+        voting_method: VotingMethod,
+        seats: u8,
+    ) -> Result<()> {
+        instructions::voting_method::handler(ctx, voting_method, seats)
     }
 
     /// Register a voter for an election (with compression)
+    /// Weights other than the default need the election authority's signature.
     pub fn register_voter(
         ctx: Context<RegisterVoter>,
+        weight: u64,
     ) -> Result<()> {
-        instructions::register_voter::handler(ctx)
+        instructions::register_voter::handler(ctx, weight)
     }
 
-    /// Cast a vote
-    pub fn cast_vote(
-        ctx: Context<CastVote>,
-        choice: u8,
+    /// Cast a vote, optionally with a rationale
+    /// Delegations chained to the voter are passed as remaining accounts.
+    pub fn cast_vote<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CastVote<'info>>,
+        ballot: Ballot,
         merkle_proof: Vec<[u8; 32]>,
         leaf_index: Option<u32>,
         registered_at: Option<i64>,
+        voter_weight: Option<u64>,
+        rationale: Option<VoteRationale>,
     ) -> Result<()> {
-        instructions::cast_vote::handler(ctx, choice, merkle_proof, leaf_index, registered_at)
+        instructions::cast_vote::handler(ctx, ballot, merkle_proof, leaf_index, registered_at, voter_weight, rationale)
     }
 
     /// Cast multiple votes in a batch
@@ -63,6 +91,226 @@ pub mod mpl_gov_micro {
         instructions::close_election::handler(ctx)
     }
 
+    /// Create the ranked ballot store of a ranked election
+    pub fn init_ranked_ballots(
+        ctx: Context<InitRankedBallots>,
+    ) -> Result<()> {
+        instructions::ranked_ballots::handler(ctx)
+    }
+
+    /// Create the pairwise preference matrix of a Condorcet election
+    pub fn init_pairwise_matrix(
+        ctx: Context<InitPairwiseMatrix>,
+    ) -> Result<()> {
+        instructions::pairwise_matrix::handler(ctx)
+    }
+
+    /// Tabulate an ended election into its result record (resumable)
+    pub fn tabulate_election(
+        ctx: Context<TabulateElection>,
+        max_rounds: u8,
+    ) -> Result<()> {
+        instructions::tabulate::handler(ctx, max_rounds)
+    }
+
+    /// Set candidate amounts and the budget cap (only authority, before start)
+    pub fn set_candidate_amounts(
+        ctx: Context<SetCandidateAmounts>,
+        amounts: Vec<u64>,
+        budget_cap: u64,
+    ) -> Result<()> {
+        instructions::candidate_amounts::handler(ctx, amounts, budget_cap)
+    }
+
+    /// Configure conviction voting for an election (only authority, before start)
+    pub fn init_conviction(
+        ctx: Context<InitConviction>,
+        decay_bps: u16,
+        max_ratio_bps: u16,
+        weight_bps: u16,
+        period: i64,
+        total_funds: u64,
+    ) -> Result<()> {
+        instructions::conviction_ix::init_conviction(ctx, decay_bps, max_ratio_bps, weight_bps, period, total_funds)
+    }
+
+    /// Stake the voter's weight on a proposal, or unstake with None
+    pub fn stake_conviction(
+        ctx: Context<StakeConviction>,
+        candidate: Option<u8>,
+        merkle_proof: Vec<[u8; 32]>,
+        leaf_index: Option<u32>,
+        registered_at: Option<i64>,
+        voter_weight: Option<u64>,
+    ) -> Result<()> {
+        instructions::conviction_ix::stake_conviction(ctx, candidate, merkle_proof, leaf_index, registered_at, voter_weight)
+    }
+
+    /// Allow or forbid recasting votes (only authority, before start)
+    pub fn set_revoting(
+        ctx: Context<SetRevoting>,
+        allow_revoting: bool,
+    ) -> Result<()> {
+        instructions::revoting::handler(ctx, allow_revoting)
+    }
+
+    /// Weight votes by SPL token balances (only authority, before start)
+    pub fn set_token_weighting(
+        ctx: Context<SetTokenWeighting>,
+        snapshot: TokenSnapshot,
+        max_voter_weight: u64,
+    ) -> Result<()> {
+        instructions::token_voting_ix::set_token_weighting(ctx, snapshot, max_voter_weight)
+    }
+
+    /// Prove the voter's balance in a token-weighted election's Merkle snapshot
+    pub fn prove_token_balance(
+        ctx: Context<ProveTokenBalance>,
+        balance: u64,
+        merkle_proof: Vec<[u8; 32]>,
+        leaf_index: u32,
+    ) -> Result<()> {
+        instructions::token_voting_ix::prove_token_balance(ctx, balance, merkle_proof, leaf_index)
+    }
+
+    /// Deposit tokens into a token-weighted election's escrow vault
+    pub fn deposit_tokens(
+        ctx: Context<DepositTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::token_voting_ix::deposit_tokens(ctx, amount)
+    }
+
+    /// Retract the voter's vote in a token-weighted election that allows revoting (while active)
+    pub fn retract_vote(
+        ctx: Context<RetractVote>,
+    ) -> Result<()> {
+        instructions::token_voting_ix::retract_vote(ctx)
+    }
+
+    /// Withdraw escrowed tokens once the voter's vote no longer counts
+    pub fn withdraw_tokens(
+        ctx: Context<WithdrawTokens>,
+    ) -> Result<()> {
+        instructions::token_voting_ix::withdraw_tokens(ctx)
+    }
+
+    /// Delegate the voter's vote for an election or an authority's elections
+    pub fn delegate_vote(
+        ctx: Context<DelegateVote>,
+        scope: DelegationScope,
+        delegate: Pubkey,
+        merkle_proof: Vec<[u8; 32]>,
+        leaf_index: Option<u32>,
+        registered_at: Option<i64>,
+        voter_weight: Option<u64>,
+    ) -> Result<()> {
+        instructions::delegation_ix::delegate_vote(ctx, scope, delegate, merkle_proof, leaf_index, registered_at, voter_weight)
+    }
+
+    /// Revoke a delegation (only delegator)
+    pub fn revoke_delegation(
+        ctx: Context<RevokeDelegation>,
+    ) -> Result<()> {
+        instructions::delegation_ix::revoke_delegation(ctx)
+    }
+
+    /// Move conviction forward to the current period (permissionless)
+    pub fn update_conviction(
+        ctx: Context<UpdateConviction>,
+    ) -> Result<()> {
+        instructions::conviction_ix::update_conviction(ctx)
+    }
+
+    /// Persist the election's derived status (permissionless)
+    pub fn tick(
+        ctx: Context<Tick>,
+    ) -> Result<()> {
+        instructions::tick::handler(ctx)
+    }
+
+    /// Set the number of tally shards for an election (only authority)
+    pub fn configure_tally_shards(
+        ctx: Context<ConfigureTallyShards>,
+        shard_count: u8,
+    ) -> Result<()> {
+        instructions::sharding::configure_tally_shards(ctx, shard_count)
+    }
+
+    /// Create a tally shard for a sharded election
+    pub fn init_tally_shard(
+        ctx: Context<InitTallyShard>,
+        shard_index: u8,
+    ) -> Result<()> {
+        instructions::sharding::init_tally_shard(ctx, shard_index)
+    }
+
+    /// Cast a vote into a tally shard (sharded elections)
+    pub fn cast_sharded_vote(
+        ctx: Context<CastShardedVote>,
+        ballot: Ballot,
+        merkle_proof: Vec<[u8; 32]>,
+        leaf_index: Option<u32>,
+        registered_at: Option<i64>,
+        voter_weight: Option<u64>,
+    ) -> Result<()> {
+        instructions::sharding::cast_sharded_vote(ctx, ballot, merkle_proof, leaf_index, registered_at, voter_weight)
+    }
+
+    /// Fold a tally shard into the election tally after voting closes
+    pub fn merge_tally_shard(
+        ctx: Context<MergeTallyShard>,
+    ) -> Result<()> {
+        instructions::sharding::merge_tally_shard(ctx)
+    }
+
+    /// Link several elections sharing one voter registry into an atomic ballot group (only authority)
+    pub fn create_ballot_group<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateBallotGroup<'info>>,
+        group_id: u64,
+    ) -> Result<()> {
+        instructions::ballot_group_ix::create_ballot_group(ctx, group_id)
+    }
+
+    /// Cast ballots for every election of a ballot group atomically
+    pub fn cast_group_ballot<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CastGroupBallot<'info>>,
+        ballots: Vec<Ballot>,
+        merkle_proof: Vec<[u8; 32]>,
+        leaf_index: Option<u32>,
+        registered_at: Option<i64>,
+        voter_weight: Option<u64>,
+    ) -> Result<()> {
+        instructions::ballot_group_ix::cast_group_ballot(ctx, ballots, merkle_proof, leaf_index, registered_at, voter_weight)
+    }
+
+    /// Configure the challenge window, bond and arbiter (only authority)
+    pub fn configure_challenge_window(
+        ctx: Context<ConfigureChallengeWindow>,
+        challenge_period: i64,
+        challenge_bond: u64,
+        arbiter: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::challenges::configure_challenge_window(ctx, challenge_period, challenge_bond, arbiter)
+    }
+
+    /// Post a bonded challenge against an election's results
+    pub fn post_challenge(
+        ctx: Context<PostChallenge>,
+        kind: ChallengeKind,
+        evidence_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::challenges::post_challenge(ctx, kind, evidence_hash)
+    }
+
+    /// Resolve a challenge (only arbiter, or authority without one)
+    pub fn resolve_challenge(
+        ctx: Context<ResolveChallenge>,
+        upheld: bool,
+    ) -> Result<()> {
+        instructions::challenges::resolve_challenge(ctx, upheld)
+    }
+
     // ===== BETA FEATURES (Not for Hackathon Demo) =====
 
     /// Enable private voting for an election (privacy layer integration)
@@ -115,10 +363,10 @@ pub mod mpl_gov_micro {
 pub struct VoteInput {
     /// The election to vote in
     pub election: Pubkey,
-    /// The choice to vote for
-    pub choice: u8,
+    /// The ballot to cast
+    pub ballot: Ballot,
     /// Merkle proof showing voter is registered
     pub merkle_proof: Vec<[u8; 32]>,
-    /// Nullifier to prevent double voting
-    pub nullifier: [u8; 32],
+    /// Optional reason for the vote, emitted as VoteRationaleSubmitted
+    pub rationale: Option<VoteRationale>,
 }
@@ -0,0 +1,486 @@
+use anchor_lang::prelude::*;
+use crate::errors::*;
+use crate::state::Election;
+
+/// How ballots are cast and counted in an election
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VotingMethod {
+    /// One candidate per ballot, most votes wins
+    /// With several seats, up to `Election::seats` candidates per ballot and the top k win
+    #[default]
+    Plurality,
+    /// Any set of candidates per ballot, each approved candidate gets +1
+    /// Most approvals wins
+    Approval {
+        /// Maximum candidates a voter may approve (0 = no cap)
+        max_approvals: u8,
+    },
+    /// Full or partial preference ordering per ballot
+    /// Instant runoff tabulation after the election ends
+    RankedChoice,
+    /// Ranked ballots filling `Election::seats` seats
+    /// Single transferable vote with a Droop quota after the election ends
+    SingleTransferableVote,
+    /// Votes bought with voice credits, n votes on a candidate cost n² credits
+    /// Most votes wins
+    Quadratic {
+        /// Voice credits per voter (per unit of weight if scale_by_weight)
+        credits: u64,
+        /// Whether the budget is multiplied by the voter's registration weight
+        scale_by_weight: bool,
+    },
+    /// Each candidate rated within a range, e.g. 0-10
+    /// Tabulated after the election ends by mean or sum
+    Score {
+        /// Lowest allowed score
+        min_score: u8,
+        /// Highest allowed score
+        max_score: u8,
+        /// How the winner is picked
+        winner_rule: ScoreRule,
+    },
+    /// Ranked ballots counted into a pairwise preference matrix
+    /// Schulze winner and order after the election ends
+    Condorcet,
+    /// Ranked ballots award points by position, most points wins
+    /// Points are added to vote_counts
+    Borda {
+        /// How positions are converted to points
+        scheme: BordaScheme,
+    },
+    /// Seats × weight votes per voter, distributed freely across candidates
+    /// The top `Election::seats` candidates win
+    Cumulative,
+    /// DAO proposal with the fixed options Yes, No, Abstain and NoWithVeto
+    /// Thresholds are in basis points (10000 = 100%)
+    Proposal {
+        /// Share of registered voters that must vote, abstentions included
+        quorum_bps: u16,
+        /// Share of Yes among Yes, No and NoWithVeto needed to pass (strictly above)
+        pass_threshold_bps: u16,
+        /// Share of NoWithVeto among all votes that fails the proposal (strictly above, 0 = no veto)
+        veto_threshold_bps: u16,
+    },
+    /// Continuous funding: voters stake weight on proposals and conviction grows over time
+    /// Configured by init_conviction; proposals pass when conviction crosses their threshold
+    Conviction,
+    /// Participatory budgeting: candidates are projects costing Election::candidate_amounts
+    /// Projects are funded by votes, greedily, until Election::budget_cap is spent
+    Budgeting {
+        /// Points per voter (per unit of weight) to allocate; 0 = approval ballots
+        points_per_voter: u64,
+    },
+}
+
+/// Fixed options of a proposal election, in candidate order
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProposalOption {
+    /// In favour
+    Yes,
+    /// Against
+    No,
+    /// Counts toward quorum only
+    Abstain,
+    /// Against, and fails the proposal outright above the veto threshold
+    NoWithVeto,
+}
+
+impl ProposalOption {
+    /// All options, in candidate order
+    pub const ALL: [ProposalOption; 4] = [
+        ProposalOption::Yes,
+        ProposalOption::No,
+        ProposalOption::Abstain,
+        ProposalOption::NoWithVeto,
+    ];
+
+    /// Index of the option in the candidates array
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// Candidate name of the option
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProposalOption::Yes => "Yes",
+            ProposalOption::No => "No",
+            ProposalOption::Abstain => "Abstain",
+            ProposalOption::NoWithVeto => "NoWithVeto",
+        }
+    }
+}
+
+/// Points awarded per ranking position in a Borda election
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BordaScheme {
+    /// N - 1 points for first place, N - 2 for second, ..., unranked 0
+    Standard,
+    /// 1/1, 1/2, 1/3, ... points, scaled by DOWDALL_SCALE to stay integral
+    Dowdall,
+}
+
+impl BordaScheme {
+    /// Least common multiple of 1..=10, so every Dowdall point value is exact
+    pub const DOWDALL_SCALE: u64 = 2520;
+
+    /// Points for the candidate ranked at `position` (0 = first)
+    pub fn points(&self, position: usize, candidate_count: usize) -> u64 {
+        match self {
+            BordaScheme::Standard => candidate_count.saturating_sub(position + 1) as u64,
+            BordaScheme::Dowdall => Self::DOWDALL_SCALE / (position as u64 + 1),
+        }
+    }
+}
+
+/// How the winner of a score election is picked
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScoreRule {
+    /// Highest mean over the ballots that scored the candidate
+    HighestMean,
+    /// Highest sum of scores
+    HighestSum,
+}
+
+impl VotingMethod {
+    /// Serialized size of the largest variant
+    /// 1 (tag) + 8 (credits) + 1 (scale_by_weight)
+    pub const SIZE: usize = 10;
+
+    /// Whether the whole tally lives in the election's vote_counts
+    /// Only these methods can be used with batches, tally shards and ballot groups.
+    pub fn tallies_on_election(&self) -> bool {
+        matches!(
+            self,
+            VotingMethod::Plurality
+                | VotingMethod::Approval { .. }
+                | VotingMethod::Quadratic { .. }
+                | VotingMethod::Borda { .. }
+                | VotingMethod::Cumulative
+                | VotingMethod::Proposal { .. }
+                | VotingMethod::Budgeting { .. }
+        )
+    }
+
+    /// Whether ballots are aggregated in a RankedBallots account
+    pub fn uses_ranked_ballots(&self) -> bool {
+        matches!(self, VotingMethod::RankedChoice | VotingMethod::SingleTransferableVote)
+    }
+
+    /// Voice credits (quadratic) or votes (cumulative, budgeting points) available to a voter
+    /// with the given registration weight; None for methods without a budget
+    pub fn credit_budget(&self, seats: u8, weight: u64) -> Option<u64> {
+        match self {
+            VotingMethod::Quadratic { credits, scale_by_weight: true } => Some(credits.saturating_mul(weight)),
+            VotingMethod::Quadratic { credits, scale_by_weight: false } => Some(*credits),
+            VotingMethod::Cumulative => Some((seats as u64).saturating_mul(weight)),
+            VotingMethod::Budgeting { points_per_voter } if *points_per_voter > 0 => {
+                Some(points_per_voter.saturating_mul(weight))
+            }
+            _ => None,
+        }
+    }
+
+    /// Most vote_counts a single ballot can give one candidate per unit of weight
+    /// Methods spending credits or an allowance are handled by tally::majority_threshold.
+    pub fn top_points(&self, candidate_count: usize) -> u64 {
+        match self {
+            VotingMethod::Borda { scheme } => scheme.points(0, candidate_count),
+            _ => 1,
+        }
+    }
+
+    /// Whether the method can fill more than one seat
+    pub fn supports_multiple_seats(&self) -> bool {
+        matches!(
+            self,
+            VotingMethod::Plurality | VotingMethod::SingleTransferableVote | VotingMethod::Cumulative
+        )
+    }
+
+    /// Whether ballots are aggregated in a PairwiseMatrix account
+    pub fn uses_pairwise_matrix(&self) -> bool {
+        matches!(self, VotingMethod::Condorcet)
+    }
+
+    /// Whether the winner comes from a tabulated ElectionResult
+    /// See also Election::requires_tabulation for multi-seat elections.
+    pub fn requires_tabulation(&self) -> bool {
+        !self.tallies_on_election() || matches!(self, VotingMethod::Budgeting { .. })
+    }
+}
+
+/// A voter's ballot, shaped by the election's voting method
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Ballot {
+    /// A single candidate (plurality)
+    Single {
+        /// Index into candidates array
+        choice: u8,
+    },
+    /// Up to `Election::seats` distinct candidates (multi-winner plurality)
+    Multiple {
+        /// Indices into candidates array
+        choices: Vec<u8>,
+    },
+    /// Approved candidates (approval)
+    Approval {
+        /// Bitmask of approved candidates (bit i = candidate i)
+        approved: u16,
+    },
+    /// Preference ordering, most preferred first (ranked methods)
+    Ranked {
+        /// Candidate indices; partial rankings are allowed
+        ranking: Vec<u8>,
+    },
+    /// Votes per candidate (quadratic)
+    Quadratic {
+        /// Votes for each candidate (parallel to candidates array, may be shorter)
+        votes: Vec<u32>,
+    },
+    /// A proposal option (proposal)
+    Proposal {
+        /// Chosen option
+        option: ProposalOption,
+    },
+    /// Votes allocated per candidate (cumulative)
+    Cumulative {
+        /// Votes for each candidate (parallel to candidates array, may be shorter)
+        allocations: Vec<u64>,
+    },
+    /// Score per candidate (score)
+    Score {
+        /// Score for each candidate (parallel to candidates array, None = not scored)
+        scores: Vec<Option<u8>>,
+    },
+    /// Counted for turnout without backing any candidate (any method)
+    Abstain,
+    /// Deliberately blank (spoiled) ballot, counted for turnout only (any method)
+    Blank,
+}
+
+impl Ballot {
+    /// Largest serialized ballot
+    /// 1 (variant) + 4 (vec len) + (10 * 8) (cumulative allocations: max 10 u64s)
+    /// = 85 bytes
+    pub const MAX_SIZE: usize = 1 + 4 + (Election::MAX_CANDIDATES * 8);
+}
+
+/// Turnout counters of an election or tally shard
+/// Both count ballots through here, so a ballot lands in the same counters
+/// whichever account tallies it.
+pub struct VoteTotals<'a> {
+    pub total_votes: &'a mut u64,
+    pub total_weight: &'a mut u64,
+    pub abstain_votes: &'a mut u64,
+    pub blank_votes: &'a mut u64,
+}
+
+impl VoteTotals<'_> {
+    /// Count a ballot carrying `weight` (the voter's weight plus delegated weight)
+    /// Abstain and blank ballots add their weight to their own counters, every
+    /// other ballot counts toward total_votes and adds its weight to total_weight.
+    pub fn count(self, ballot: &Ballot, weight: u64) -> Result<()> {
+        match ballot {
+            Ballot::Abstain => add_count(self.abstain_votes, weight),
+            Ballot::Blank => add_count(self.blank_votes, weight),
+            _ => {
+                add_count(self.total_votes, 1)?;
+                add_count(self.total_weight, weight)
+            }
+        }
+    }
+
+    /// Take back a ballot counted with count (revoting)
+    pub fn uncount(self, ballot: &Ballot, weight: u64) -> Result<()> {
+        match ballot {
+            Ballot::Abstain => sub_count(self.abstain_votes, weight),
+            Ballot::Blank => sub_count(self.blank_votes, weight),
+            _ => {
+                sub_count(self.total_votes, 1)?;
+                sub_count(self.total_weight, weight)
+            }
+        }
+    }
+}
+
+fn add_count(counter: &mut u64, amount: u64) -> Result<()> {
+    *counter = counter
+        .checked_add(amount)
+        .ok_or(GovError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+fn sub_count(counter: &mut u64, amount: u64) -> Result<()> {
+    *counter = counter
+        .checked_sub(amount)
+        .ok_or(GovError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+/// Voter Ballot - Regular account holding a voter's current ballot
+/// Only used when the election allows revoting, so a recast can undo it.
+#[account]
+#[derive(Debug)]
+pub struct VoterBallot {
+    /// The election the ballot was cast in
+    pub election: Pubkey,
+
+    /// The voter who cast it
+    pub voter: Pubkey,
+
+    /// The ballot currently counted for the voter
+    pub ballot: Ballot,
+
+    /// Weight the ballot was counted with (own weight plus delegated weight)
+    pub weight: u64,
+
+    /// Part of the weight delegated to the voter (kept across recasts)
+    pub delegated_weight: u64,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl VoterBallot {
+    /// Size of the voter ballot account
+    /// 8 (discriminator) + 32 (election) + 32 (voter) + Ballot::MAX_SIZE (ballot) + 8 (weight) + 8 (delegated_weight) + 1 (bump)
+    /// = 174 bytes
+    pub const SIZE: usize = 8 + 32 + 32 + Ballot::MAX_SIZE + 8 + 8 + 1;
+}
+
+/// Ranked Ballots - Regular account aggregating ranked ballots
+/// Ballots are stored compactly as one count per distinct ranking. The
+/// account grows when a voter casts a ranking it has no room for, and again
+/// for the ranking group values once STV tabulation starts.
+#[account]
+#[derive(Debug)]
+pub struct RankedBallots {
+    /// The election these ballots belong to
+    pub election: Pubkey,
+
+    /// Number of ballots per distinct ranking
+    pub rankings: Vec<RankingCount>,
+
+    /// Current STV value of each ranking group (parallel to rankings)
+    /// Empty until STV tabulation starts.
+    pub ballot_weights: Vec<u128>,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+/// Number of ballots cast with the same ranking
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RankingCount {
+    /// Candidate indices, most preferred first
+    pub ranking: Vec<u8>,
+
+    /// Number of ballots with this ranking
+    pub count: u64,
+}
+
+impl RankingCount {
+    /// Size of one ranking entry
+    /// 4 (vec len) + 10 (ranking: max 10 candidates) + 8 (count)
+    /// = 22 bytes
+    pub const SIZE: usize = 22;
+}
+
+impl RankedBallots {
+    /// Distinct rankings a new account has room for
+    pub const INITIAL_RANKINGS: usize = 32;
+
+    /// Distinct rankings added each time the account grows
+    pub const GROWTH_RANKINGS: usize = 32;
+
+    /// Size of a new ranked ballots account
+    /// 8 (discriminator) + 32 (election) + 4 (vec len) + (32 * 22) (rankings)
+    /// + 4 (ballot_weights) + 1 (bump)
+    /// = 753 bytes
+    pub const SIZE: usize = Self::space(Self::INITIAL_RANKINGS);
+
+    /// Account size with room for `rankings` distinct rankings
+    pub const fn space(rankings: usize) -> usize {
+        8 + 32 + 4 + (rankings * RankingCount::SIZE) + 4 + 1
+    }
+
+    /// Account size with room for `rankings` distinct rankings and their STV values
+    pub const fn space_with_weights(rankings: usize) -> usize {
+        Self::space(rankings) + (rankings * 16)
+    }
+
+    /// Whether ballots with the given ranking have been recorded
+    pub fn contains(&self, ranking: &[u8]) -> bool {
+        self.rankings.iter().any(|entry| entry.ranking == ranking)
+    }
+
+    /// Add `weight` ballots with the given ranking
+    /// The account must have room for a new ranking (see reserve_ranking).
+    pub fn record(&mut self, ranking: &[u8], weight: u64) -> Result<()> {
+        if let Some(entry) = self.rankings.iter_mut().find(|entry| entry.ranking == ranking) {
+            entry.count = entry.count
+                .checked_add(weight)
+                .ok_or(GovError::ArithmeticOverflow)?;
+            return Ok(());
+        }
+
+        self.rankings.push(RankingCount {
+            ranking: ranking.to_vec(),
+            count: weight,
+        });
+
+        Ok(())
+    }
+}
+
+/// Pairwise Matrix - Regular account aggregating ranked ballots pairwise
+/// preferences[i * N + j] is the number of ballots preferring candidate i over j.
+/// A ranked candidate is preferred over every candidate ranked after them and
+/// every unranked candidate; unranked candidates are not compared.
+#[account]
+#[derive(Debug)]
+pub struct PairwiseMatrix {
+    /// The election these ballots belong to
+    pub election: Pubkey,
+
+    /// Number of candidates (N)
+    pub candidate_count: u8,
+
+    /// N×N preference counts, row-major
+    pub preferences: Vec<u64>,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl PairwiseMatrix {
+    /// Size of the pairwise matrix account
+    /// 8 (discriminator) + 32 (election) + 1 (candidate_count) + 4 (vec len) + (10 * 10 * 8) (preferences) + 1 (bump)
+    /// = 846 bytes
+    pub const SIZE: usize = 8 + 32 + 1 + 4 + (Election::MAX_CANDIDATES * Election::MAX_CANDIDATES * 8) + 1;
+
+    /// Add `weight` ballots with the given (validated) ranking
+    pub fn record(&mut self, ranking: &[u8], weight: u64) -> Result<()> {
+        let n = self.candidate_count as usize;
+
+        for (position, winner) in ranking.iter().enumerate() {
+            for loser in 0..n as u8 {
+                // Ranked earlier than the loser, or the loser is unranked
+                let beats = !ranking[..=position].contains(&loser);
+                if beats {
+                    let cell = &mut self.preferences[*winner as usize * n + loser as usize];
+                    *cell = cell
+                        .checked_add(weight)
+                        .ok_or(GovError::ArithmeticOverflow)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ballots preferring candidate a over candidate b
+    pub fn preference(&self, a: u8, b: u8) -> u64 {
+        self.preferences[a as usize * self.candidate_count as usize + b as usize]
+    }
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+/// Ballot Group - Regular account linking several elections into one ballot
+/// A voter submits choices for every election in the group in a single
+/// atomic instruction, with one eligibility check and one group nullifier.
+#[account]
+#[derive(Debug)]
+pub struct BallotGroup {
+    /// Authority that created the group (owns every member election)
+    pub authority: Pubkey,
+
+    /// Member elections, in the order ballots list their choices
+    pub elections: Vec<Pubkey>,
+
+    /// Voter registry shared by all member elections
+    pub voter_registry: Pubkey,
+
+    /// Group-level nullifiers (one per voter for the whole group, grows as voters arrive)
+    pub used_nullifiers: Vec<[u8; 32]>,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl BallotGroup {
+    /// Initial size allocation (without nullifiers)
+    /// 8 (discriminator)
+    /// + 32 (authority)
+    /// + 4 (vec len) + (8 * 32) (elections: max 8)
+    /// + 32 (voter_registry)
+    /// + 4 (vec len) (used_nullifiers)
+    /// + 1 (bump)
+    /// = 8 + 32 + 260 + 32 + 4 + 1 = 337 bytes
+    pub const INIT_SIZE: usize = 337;
+
+    /// Nullifiers a new group has room for
+    pub const INITIAL_NULLIFIERS: usize = 100;
+
+    /// Nullifiers added each time the group grows
+    pub const NULLIFIER_GROWTH: usize = 100;
+
+    /// Account size with room for `nullifiers` nullifiers
+    pub const fn space(nullifiers: usize) -> usize {
+        Self::INIT_SIZE + (32 * nullifiers)
+    }
+
+    /// Maximum number of elections in a group
+    pub const MAX_ELECTIONS: usize = 8;
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+/// Challenge - Regular account for a bonded dispute of an election's results
+/// Posted during the challenge window after end_time. While any challenge is
+/// open, hooks and the final outcome are blocked. Closed to the bond recipient
+/// once resolved.
+#[account]
+#[derive(Debug)]
+pub struct Challenge {
+    /// The election being challenged
+    pub election: Pubkey,
+
+    /// Who posted the challenge (and gets the bond back if upheld)
+    pub challenger: Pubkey,
+
+    /// What the challenge claims is wrong
+    pub kind: ChallengeKind,
+
+    /// Hash of the off-chain evidence backing the claim
+    pub evidence_hash: [u8; 32],
+
+    /// Bond held by this account (lamports)
+    pub bond: u64,
+
+    /// Unix timestamp when the challenge was posted
+    pub created_at: i64,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl Challenge {
+    /// Size of the challenge account
+    /// 8 (discriminator) + 32 (election) + 32 (challenger) + 1 (kind) + 32 (evidence_hash)
+    /// + 8 (bond) + 8 (created_at) + 1 (bump)
+    /// = 122 bytes
+    pub const SIZE: usize = 8 + 32 + 32 + 1 + 32 + 8 + 8 + 1;
+}
+
+/// Problem a challenge cites
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChallengeKind {
+    /// A counted vote was backed by an invalid registration proof
+    InvalidRegistrationProof,
+    /// The same nullifier was counted more than once
+    DuplicateNullifier,
+    /// The tally doesn't match the recorded ballots
+    TallyMismatch,
+}
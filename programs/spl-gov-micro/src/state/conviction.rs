@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+
+/// Conviction State - Regular account holding the conviction of every proposal
+/// Candidates are proposals requesting Election::candidate_amounts. Conviction
+/// is moved forward period by period by update_conviction (permissionless) and
+/// before every stake change.
+#[account]
+#[derive(Debug)]
+pub struct ConvictionState {
+    /// The election this state belongs to
+    pub election: Pubkey,
+
+    /// Conviction kept per period (α, basis points)
+    pub decay_bps: u16,
+
+    /// Maximum share of funds a proposal may request (β, basis points)
+    pub max_ratio_bps: u16,
+
+    /// Weight parameter of the pass threshold (ρ, basis points)
+    pub weight_bps: u16,
+
+    /// Length of one conviction period (in units of the election's time_base)
+    pub period: i64,
+
+    /// Funds proposals request from (R)
+    pub total_funds: u64,
+
+    /// Funds already granted to passed proposals
+    /// Thresholds are computed against the funds that remain.
+    pub committed_funds: u64,
+
+    /// Weight currently staked on each proposal (parallel to candidates array)
+    pub staked: Vec<u64>,
+
+    /// Conviction of each proposal in utils::conviction::CONVICTION_SCALE units
+    pub conviction: Vec<u64>,
+
+    /// Time conviction has been computed up to (start of the current period)
+    pub last_update: i64,
+
+    /// Proposals that crossed their threshold, in the order they passed
+    pub passed: Vec<u8>,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl ConvictionState {
+    /// Size of the conviction state account
+    /// 8 (discriminator) + 32 (election) + 2 (decay_bps) + 2 (max_ratio_bps) + 2 (weight_bps)
+    /// + 8 (period) + 8 (total_funds) + 8 (committed_funds)
+    /// + 4 + (10 * 8) (staked) + 4 + (10 * 8) (conviction)
+    /// + 8 (last_update) + 4 + 10 (passed) + 1 (bump)
+    /// = 261 bytes
+    pub const SIZE: usize = 8 + 32 + 2 + 2 + 2 + 8 + 8 + 8
+        + (4 + Election::MAX_CANDIDATES * 8)
+        + (4 + Election::MAX_CANDIDATES * 8)
+        + 8
+        + (4 + Election::MAX_CANDIDATES)
+        + 1;
+}
+
+/// Conviction Stake - Regular account holding one voter's stake
+#[account]
+#[derive(Debug)]
+pub struct ConvictionStake {
+    /// The election staked in
+    pub election: Pubkey,
+
+    /// The staking voter
+    pub voter: Pubkey,
+
+    /// Proposal the weight is staked on (None = unstaked)
+    pub candidate: Option<u8>,
+
+    /// Weight staked
+    pub amount: u64,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl ConvictionStake {
+    /// Size of a conviction stake
+    /// 8 (discriminator) + 32 (election) + 32 (voter) + 2 (candidate: Option<u8>) + 8 (amount) + 1 (bump)
+    /// = 83 bytes
+    pub const SIZE: usize = 83;
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+
+/// Delegation - Regular account passing a voter's vote on to another voter
+/// A delegate voting with cast_vote counts every delegation chained to them
+/// (passed as remaining accounts) and uses up the delegators' nullifiers, so
+/// whoever votes first, delegate or delegator, decides how the weight is used.
+#[account]
+#[derive(Debug)]
+pub struct Delegation {
+    /// The voter delegating their vote
+    pub delegator: Pubkey,
+
+    /// The voter receiving it
+    pub delegate: Pubkey,
+
+    /// Elections the delegation applies to
+    pub scope: DelegationScope,
+
+    /// Voter registry the delegator proved their registration in
+    pub voter_registry: Pubkey,
+
+    /// Delegator's voting weight, passed on to the delegate
+    pub weight: u64,
+
+    /// Unix timestamp when the delegation was made
+    pub created_at: i64,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl Delegation {
+    /// Size of the delegation account
+    /// 8 (discriminator) + 32 (delegator) + 32 (delegate) + 33 (scope) + 32 (voter_registry) + 8 (weight) + 8 (created_at) + 1 (bump)
+    /// = 154 bytes
+    pub const SIZE: usize = 8 + 32 + 32 + DelegationScope::SIZE + 32 + 8 + 8 + 1;
+
+    /// Longest delegation chain counted for a delegate
+    pub const MAX_DEPTH: u8 = 4;
+
+    /// Whether this delegation applies to the given election
+    pub fn covers(&self, election_key: &Pubkey, election: &Election) -> bool {
+        let in_scope = match self.scope {
+            DelegationScope::Election(election) => election == *election_key,
+            DelegationScope::Authority(authority) => authority == election.authority,
+        };
+
+        in_scope && self.voter_registry == election.voter_registry
+    }
+}
+
+/// Elections a delegation applies to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DelegationScope {
+    /// A single election
+    Election(Pubkey),
+    /// Every election of an authority sharing the delegator's voter registry
+    Authority(Pubkey),
+}
+
+impl DelegationScope {
+    /// 1 (variant) + 32 (election or authority)
+    pub const SIZE: usize = 1 + 32;
+
+    /// The election or authority, used in the delegation's PDA seeds
+    pub fn key(&self) -> Pubkey {
+        match self {
+            DelegationScope::Election(key) | DelegationScope::Authority(key) => *key,
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use crate::errors::*;
+use crate::state::{Ballot, TokenWeighting, VoteTotals, VotingMethod};
 
 /// Election account - Regular Solana account (not compressed)
 /// This is HOT DATA that needs fast, frequent access
@@ -14,16 +16,16 @@ pub struct Election {
     /// Vote counts for each candidate (parallel to candidates array)
     pub vote_counts: Vec<u64>,
 
-    /// Total number of votes cast
+    /// Total number of ballots cast for candidates
     pub total_votes: u64,
 
     /// Merkle root of registered voters (compressed tree)
     pub voter_merkle_root: [u8; 32],
 
-    /// Unix timestamp when voting starts
+    /// When voting starts (in units of time_base)
     pub start_time: i64,
 
-    /// Unix timestamp when voting ends
+    /// When voting ends (in units of time_base)
     pub end_time: i64,
 
     /// Current status of the election
@@ -39,6 +41,10 @@ pub struct Election {
     /// Total number of registered voters
     pub total_registered: u64,
 
+    /// Election whose voter registrations are accepted here
+    /// This election itself, unless the registry was reused by clone_election
+    pub voter_registry: Pubkey,
+
     // ===== BETA FEATURES (Not for Hackathon Demo) =====
 
     /// Whether private voting is enabled (via privacy layer)
@@ -53,6 +59,66 @@ pub struct Election {
     /// Hook to execute when election fails (doesn't pass threshold)
     pub on_failure_hook: Option<Pubkey>,
 
+    /// Number of tally shards votes are spread across (0 = unsharded)
+    pub shard_count: u8,
+
+    /// Number of shards already folded into vote_counts
+    pub shards_merged: u8,
+
+    /// Clock that start_time and end_time are measured in
+    pub time_base: TimeBase,
+
+    /// Ballot group this election belongs to (votes only through the group)
+    pub ballot_group: Option<Pubkey>,
+
+    /// Length of the challenge window after end_time (in units of time_base, 0 = none)
+    pub challenge_period: i64,
+
+    /// Bond a challenger must post (lamports)
+    pub challenge_bond: u64,
+
+    /// Arbiter that resolves challenges (the authority resolves if None)
+    pub arbiter: Option<Pubkey>,
+
+    /// Number of challenges waiting for resolution
+    pub open_challenges: u32,
+
+    /// How ballots are cast and counted
+    pub voting_method: VotingMethod,
+
+    /// Number of seats to fill (winners)
+    pub seats: u8,
+
+    /// Ballots that scored each candidate (score voting, parallel to candidates array)
+    /// vote_counts holds the score sums, so mean = vote_counts[i] / score_ballots[i]
+    pub score_ballots: Vec<u64>,
+
+    /// Amount requested by each candidate (conviction voting and budgeting, parallel to candidates array)
+    /// Empty unless set via set_candidate_amounts
+    pub candidate_amounts: Vec<u64>,
+
+    /// Total budget the winning candidates' amounts may add up to (budgeting)
+    pub budget_cap: u64,
+
+    /// Whether voters may recast their vote while the election is active
+    /// Only the last ballot counts; each voter's current ballot is kept in a VoterBallot
+    pub allow_revoting: bool,
+
+    /// Weight of the abstain ballots cast (not part of total_votes)
+    pub abstain_votes: u64,
+
+    /// Weight of the blank ballots cast (not part of total_votes)
+    pub blank_votes: u64,
+
+    /// Sum of the weights of the ballots counted in total_votes
+    pub total_weight: u64,
+
+    /// Sum of the weights of all registered voters
+    pub total_registered_weight: u64,
+
+    /// Token balances votes are weighted by (None = registered weights)
+    pub token_weighting: Option<TokenWeighting>,
+
     /// Bump seed for PDA
     pub bump: u8,
 }
@@ -71,19 +137,166 @@ impl Election {
     /// + 1 (use_compression)
     /// + 32 (merkle_tree)
     /// + 8 (total_registered)
+    /// + 32 (voter_registry)
     /// + 1 (privacy_enabled)
     /// + 32 (privacy_layer_program)
     /// + 33 (on_success_hook: Option<Pubkey>)
     /// + 33 (on_failure_hook: Option<Pubkey>)
+    /// + 1 (shard_count)
+    /// + 1 (shards_merged)
+    /// + 1 (time_base)
+    /// + 33 (ballot_group: Option<Pubkey>)
+    /// + 8 (challenge_period)
+    /// + 8 (challenge_bond)
+    /// + 33 (arbiter: Option<Pubkey>)
+    /// + 4 (open_challenges)
+    /// + VotingMethod::SIZE (voting_method)
+    /// + 1 (seats)
+    /// + 4 (vec len) + (10 * 8) (score_ballots: max 10 u64s)
+    /// + 4 (vec len) + (10 * 8) (candidate_amounts: max 10 u64s)
+    /// + 8 (budget_cap)
+    /// + 1 (allow_revoting)
+    /// + 8 (abstain_votes)
+    /// + 8 (blank_votes)
+    /// + 8 (total_weight)
+    /// + 8 (total_registered_weight)
+    /// + 1 + TokenWeighting::SIZE (token_weighting: Option<TokenWeighting>)
     /// + 1 (bump)
-    /// = 8 + 32 + 544 + 84 + 8 + 32 + 8 + 8 + 1 + 1 + 32 + 8 + 1 + 32 + 33 + 33 + 1 = 866 bytes
-    pub const MAX_SIZE: usize = 866;
+    /// = 8 + 32 + 544 + 84 + 8 + 32 + 8 + 8 + 1 + 1 + 32 + 8 + 32 + 1 + 32 + 33 + 33 + 1 + 1 + 1 + 33 + 8 + 8 + 33 + 4 + 1 + 84 + 84 + 8 + 1 + 8 + 8 + 8 + 8 + 1 + 1
+    ///   + VotingMethod::SIZE + TokenWeighting::SIZE
+    pub const MAX_SIZE: usize = 1198 + VotingMethod::SIZE + TokenWeighting::SIZE;
 
     /// Maximum number of candidates allowed
     pub const MAX_CANDIDATES: usize = 10;
 
     /// Maximum length of candidate name
     pub const MAX_CANDIDATE_NAME_LEN: usize = 50;
+
+    /// Status derived from the clock and the stored status
+    ///
+    /// The stored status only moves when an instruction persists it, so
+    /// handlers should always check this instead of `status`.
+    /// Ended and Cancelled are final; otherwise the voting window decides.
+    pub fn effective_status(&self, clock: &Clock) -> ElectionStatus {
+        let now = self.now(clock);
+
+        match self.status {
+            ElectionStatus::Ended | ElectionStatus::Cancelled => self.status,
+            _ if now < self.start_time => ElectionStatus::Pending,
+            _ if now > self.end_time => ElectionStatus::Ended,
+            _ => ElectionStatus::Active,
+        }
+    }
+
+    /// Whether challenges can currently be posted
+    /// The window runs from end_time to end_time + challenge_period.
+    pub fn in_challenge_window(&self, clock: &Clock) -> bool {
+        self.challenge_period > 0
+            && self.effective_status(clock) == ElectionStatus::Ended
+            && self.now(clock) <= self.end_time.saturating_add(self.challenge_period)
+    }
+
+    /// Whether results are final and can be acted upon
+    /// Requires the election to have ended, the challenge window to have
+    /// passed and every challenge to be resolved.
+    pub fn outcome_final(&self, clock: &Clock) -> bool {
+        self.effective_status(clock) == ElectionStatus::Ended
+            && self.now(clock) > self.end_time.saturating_add(self.challenge_period)
+            && self.open_challenges == 0
+    }
+
+    /// Current time in this election's time base
+    pub fn now(&self, clock: &Clock) -> i64 {
+        self.time_base.now(clock)
+    }
+
+    /// Fail unless the election is currently accepting votes
+    pub fn require_active(&self, clock: &Clock) -> Result<()> {
+        match self.effective_status(clock) {
+            ElectionStatus::Active => Ok(()),
+            ElectionStatus::Pending => err!(GovError::ElectionNotStarted),
+            ElectionStatus::Ended => err!(GovError::ElectionEnded),
+            ElectionStatus::Cancelled => err!(GovError::ElectionNotActive),
+        }
+    }
+
+    /// Copy the configuration of another election (candidates and settings)
+    ///
+    /// Tallies, times, status, voter registry and bump are left untouched.
+    pub fn copy_configuration_from(&mut self, source: &Election) {
+        self.authority = source.authority;
+        self.candidates = source.candidates.clone();
+        self.use_compression = source.use_compression;
+        self.time_base = source.time_base;
+        self.privacy_enabled = source.privacy_enabled;
+        self.privacy_layer_program = source.privacy_layer_program;
+        self.on_success_hook = source.on_success_hook;
+        self.on_failure_hook = source.on_failure_hook;
+        self.shard_count = source.shard_count;
+        self.challenge_period = source.challenge_period;
+        self.challenge_bond = source.challenge_bond;
+        self.arbiter = source.arbiter;
+        self.voting_method = source.voting_method;
+        self.seats = source.seats;
+        self.candidate_amounts = source.candidate_amounts.clone();
+        self.budget_cap = source.budget_cap;
+        self.allow_revoting = source.allow_revoting;
+        self.token_weighting = source.token_weighting;
+    }
+
+    /// Count a ballot carrying `weight` in the election's turnout counters (see VoteTotals)
+    pub fn count_ballot(&mut self, ballot: &Ballot, weight: u64) -> Result<()> {
+        self.vote_totals().count(ballot, weight)
+    }
+
+    /// Take back a ballot counted with count_ballot (revoting)
+    pub fn uncount_ballot(&mut self, ballot: &Ballot, weight: u64) -> Result<()> {
+        self.vote_totals().uncount(ballot, weight)
+    }
+
+    fn vote_totals(&mut self) -> VoteTotals<'_> {
+        VoteTotals {
+            total_votes: &mut self.total_votes,
+            total_weight: &mut self.total_weight,
+            abstain_votes: &mut self.abstain_votes,
+            blank_votes: &mut self.blank_votes,
+        }
+    }
+
+    /// Weight of the abstain and blank ballots, which count toward quorum but not for candidates
+    pub fn abstentions(&self) -> u64 {
+        self.abstain_votes.saturating_add(self.blank_votes)
+    }
+
+    /// Weight quorum and thresholds are measured against
+    /// The snapshot's token supply for token-weighted elections, otherwise the
+    /// registered voters' weight.
+    pub fn eligible_weight(&self) -> u64 {
+        self.token_weighting
+            .map(|token_weighting| token_weighting.supply)
+            .unwrap_or(self.total_registered_weight)
+    }
+
+    /// Whether votes are weighted by token balances
+    pub fn is_token_weighted(&self) -> bool {
+        self.token_weighting.is_some()
+    }
+
+    /// Whether the winners come from a tabulated ElectionResult
+    /// True for tabulated voting methods and for every multi-seat election.
+    pub fn requires_tabulation(&self) -> bool {
+        self.voting_method.requires_tabulation() || self.seats > 1
+    }
+
+    /// Whether votes are tallied in shards rather than on this account
+    pub fn is_sharded(&self) -> bool {
+        self.shard_count > 0
+    }
+
+    /// Whether every shard has been folded into vote_counts
+    pub fn all_shards_merged(&self) -> bool {
+        self.shards_merged == self.shard_count
+    }
 }
 
 /// Status of an election
@@ -104,3 +317,61 @@ impl Default for ElectionStatus {
         ElectionStatus::Pending
     }
 }
+
+/// Clock an election's voting window is measured in
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TimeBase {
+    /// Unix timestamp from the cluster clock (can drift from wall time)
+    #[default]
+    UnixTimestamp,
+    /// Slot number
+    Slot,
+    /// Epoch number
+    Epoch,
+}
+
+impl TimeBase {
+    /// Current time in this time base
+    pub fn now(&self, clock: &Clock) -> i64 {
+        match self {
+            TimeBase::UnixTimestamp => clock.unix_timestamp,
+            TimeBase::Slot => clock.slot as i64,
+            TimeBase::Epoch => clock.epoch as i64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clock(slot: u64, epoch: u64, unix_timestamp: i64) -> Clock {
+        Clock {
+            slot,
+            epoch_start_timestamp: 0,
+            epoch,
+            leader_schedule_epoch: epoch + 1,
+            unix_timestamp,
+        }
+    }
+
+    #[test]
+    fn test_time_base_now() {
+        let clock = clock(250_000, 578, 1_700_000_000);
+
+        assert_eq!(TimeBase::UnixTimestamp.now(&clock), 1_700_000_000);
+        assert_eq!(TimeBase::Slot.now(&clock), 250_000);
+        assert_eq!(TimeBase::Epoch.now(&clock), 578);
+        assert_eq!(TimeBase::default(), TimeBase::UnixTimestamp);
+    }
+
+    #[test]
+    fn test_time_base_follows_its_own_clock() {
+        // Slots keep advancing while the cluster timestamp stalls
+        let before = clock(100, 0, 1_700_000_000);
+        let after = clock(150, 0, 1_700_000_000);
+
+        assert!(TimeBase::Slot.now(&after) > TimeBase::Slot.now(&before));
+        assert_eq!(TimeBase::UnixTimestamp.now(&after), TimeBase::UnixTimestamp.now(&before));
+    }
+}
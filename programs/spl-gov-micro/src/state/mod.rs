@@ -1,7 +1,23 @@
 pub mod election;
 pub mod voter;
 pub mod vote;
+pub mod tally_shard;
+pub mod ballot_group;
+pub mod challenge;
+pub mod ballot;
+pub mod result;
+pub mod conviction;
+pub mod delegation;
+pub mod token_voting;
 
 pub use election::*;
 pub use voter::*;
 pub use vote::*;
+pub use tally_shard::*;
+pub use ballot_group::*;
+pub use challenge::*;
+pub use ballot::*;
+pub use result::*;
+pub use conviction::*;
+pub use delegation::*;
+pub use token_voting::*;
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+
+/// Election Result - Regular account holding the tabulated outcome
+/// Written by tabulate_election after the election ends. Tabulation can be
+/// spread over several instructions; `complete` flips once it is done.
+#[account]
+#[derive(Debug)]
+pub struct ElectionResult {
+    /// The election this result belongs to
+    pub election: Pubkey,
+
+    /// Voting method (and its configuration) the result was tabulated with
+    pub voting_method: VotingMethod,
+
+    /// Whether tabulation has finished
+    pub complete: bool,
+
+    /// Winning candidates, in the order they were decided
+    pub winners: Vec<u8>,
+
+    /// Candidates tied at the last seat's vote count when the tie crossed
+    /// the cutoff (top-k methods); the earlier candidates were seated
+    pub tied_at_cutoff: Vec<u8>,
+
+    /// Eliminated candidates, in elimination order
+    pub eliminated: Vec<u8>,
+
+    /// Per-round record of the tabulation
+    pub rounds: Vec<TabulationRound>,
+
+    /// Droop quota in STV fixed-point units (0 for other methods)
+    pub quota: u128,
+
+    /// Complete finishing order, best first (Condorcet only)
+    pub order: Vec<u8>,
+
+    /// Copy of the N×N pairwise preference matrix, row-major (Condorcet only)
+    pub pairwise: Vec<u64>,
+
+    /// N×N Schulze beat-path strengths, row-major (Condorcet only)
+    pub strengths: Vec<u64>,
+
+    /// Sum of the funded candidates' amounts (budgeting only)
+    pub budget_spent: u64,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+/// One tabulation round
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TabulationRound {
+    /// Votes per candidate in this round (parallel to candidates array)
+    /// STV tallies are in fixed-point units (see utils::stv::STV_SCALE)
+    pub tallies: Vec<u128>,
+
+    /// Ballots with no continuing candidate left
+    pub exhausted: u128,
+
+    /// Candidates elected at the end of this round
+    pub elected: Vec<u8>,
+
+    /// Candidate eliminated at the end of this round
+    pub eliminated: Option<u8>,
+}
+
+impl TabulationRound {
+    /// Size of one round
+    /// 4 (vec len) + (10 * 16) (tallies) + 16 (exhausted) + 4 + 10 (elected) + 2 (eliminated: Option<u8>)
+    /// = 196 bytes
+    pub const SIZE: usize = 196;
+}
+
+impl ElectionResult {
+    /// Maximum number of rounds (one election or elimination per round)
+    pub const MAX_ROUNDS: usize = Election::MAX_CANDIDATES;
+
+    /// Size of the result account without method-specific data
+    /// 8 (discriminator)
+    /// + 32 (election)
+    /// + VotingMethod::SIZE (voting_method)
+    /// + 1 (complete)
+    /// + 4 + 10 (winners)
+    /// + 4 + 10 (tied_at_cutoff)
+    /// + 4 + 10 (eliminated)
+    /// + 4 + (10 * 196) (rounds)
+    /// + 16 (quota)
+    /// + 4 (order)
+    /// + 4 (pairwise)
+    /// + 4 (strengths)
+    /// + 8 (budget_spent)
+    /// + 1 (bump)
+    pub const SIZE: usize = 8 + 32 + VotingMethod::SIZE + 1
+        + 3 * (4 + Election::MAX_CANDIDATES)
+        + (4 + Self::MAX_ROUNDS * TabulationRound::SIZE)
+        + 16
+        + 4
+        + 2 * 4
+        + 8
+        + 1;
+
+    /// Room for the finishing order and both N×N matrices (Condorcet only)
+    /// 10 (order) + 2 * (10 * 10 * 8) (pairwise, strengths)
+    /// = 1610 bytes
+    pub const CONDORCET_SIZE: usize = Election::MAX_CANDIDATES
+        + 2 * (Election::MAX_CANDIDATES * Election::MAX_CANDIDATES * 8);
+
+    /// Account size for a result tabulated with the given voting method
+    pub fn space(voting_method: &VotingMethod) -> usize {
+        if voting_method.uses_pairwise_matrix() {
+            Self::SIZE + Self::CONDORCET_SIZE
+        } else {
+            Self::SIZE
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use crate::state::{Ballot, VoteTotals};
+
+/// Tally Shard - Regular account holding a slice of an election's tally
+/// Sharded elections spread votes across N shards so that concurrent votes
+/// write-lock different accounts instead of the single Election account.
+/// Each shard keeps its own nullifiers, growing as voters arrive; a voter
+/// always lands in the same shard because the shard is picked from their
+/// nullifier prefix.
+#[account]
+#[derive(Debug)]
+pub struct TallyShard {
+    /// The election this shard belongs to
+    pub election: Pubkey,
+
+    /// Index of this shard (0..election.shard_count)
+    pub shard_index: u8,
+
+    /// Vote counts for each candidate (parallel to election.candidates)
+    pub vote_counts: Vec<u64>,
+
+    /// Total number of ballots for candidates recorded in this shard
+    pub total_votes: u64,
+
+    /// Weight of the abstain ballots recorded in this shard (not part of total_votes)
+    pub abstain_votes: u64,
+
+    /// Weight of the blank ballots recorded in this shard (not part of total_votes)
+    pub blank_votes: u64,
+
+    /// Sum of the weights of the ballots counted in total_votes
+    pub total_weight: u64,
+
+    /// Nullifiers of the votes recorded in this shard
+    pub used_nullifiers: Vec<[u8; 32]>,
+
+    /// Whether this shard has been folded into the election tally
+    pub merged: bool,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl TallyShard {
+    /// Initial size allocation (without nullifiers)
+    /// 8 (discriminator)
+    /// + 32 (election)
+    /// + 1 (shard_index)
+    /// + 4 (vec len) + (10 * 8) (vote_counts: max 10 u64s)
+    /// + 8 (total_votes)
+    /// + 8 (abstain_votes)
+    /// + 8 (blank_votes)
+    /// + 8 (total_weight)
+    /// + 4 (vec len) (used_nullifiers)
+    /// + 1 (merged)
+    /// + 1 (bump)
+    /// = 8 + 32 + 1 + 84 + 8 + 8 + 8 + 8 + 4 + 1 + 1 = 163 bytes
+    pub const INIT_SIZE: usize = 163;
+
+    /// Nullifiers a new shard has room for
+    pub const INITIAL_NULLIFIERS: usize = 100;
+
+    /// Nullifiers added each time the shard grows
+    pub const NULLIFIER_GROWTH: usize = 100;
+
+    /// Account size with room for `nullifiers` nullifiers
+    pub const fn space(nullifiers: usize) -> usize {
+        Self::INIT_SIZE + (32 * nullifiers)
+    }
+
+    /// Maximum number of shards per election
+    pub const MAX_SHARDS: u8 = 16;
+
+    /// Shard a nullifier is routed to
+    pub fn index_for(nullifier_hash: &[u8; 32], shard_count: u8) -> u8 {
+        nullifier_hash[0] % shard_count
+    }
+
+    /// Count a ballot in the shard's turnout counters, as Election::count_ballot does
+    pub fn count_ballot(&mut self, ballot: &Ballot, weight: u64) -> Result<()> {
+        VoteTotals {
+            total_votes: &mut self.total_votes,
+            total_weight: &mut self.total_weight,
+            abstain_votes: &mut self.abstain_votes,
+            blank_votes: &mut self.blank_votes,
+        }
+        .count(ballot, weight)
+    }
+}
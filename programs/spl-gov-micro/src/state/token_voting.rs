@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+/// Token weighting of an election
+/// Each vote counts the voter's balance of `mint` instead of their registered
+/// weight. Voters still have to be registered to vote.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenWeighting {
+    /// Mint whose balances are counted
+    pub mint: Pubkey,
+
+    /// Where the balances come from
+    pub snapshot: TokenSnapshot,
+
+    /// Highest weight a single voter can vote with (0 = uncapped)
+    pub max_voter_weight: u64,
+
+    /// Total balance the snapshot covers, used as the eligible weight for quorum
+    pub supply: u64,
+}
+
+impl TokenWeighting {
+    /// 32 (mint) + 41 (snapshot) + 8 (max_voter_weight) + 8 (supply)
+    pub const SIZE: usize = 32 + TokenSnapshot::SIZE + 8 + 8;
+
+    /// Weight a balance votes with, after the per-voter cap
+    pub fn weight(&self, balance: u64) -> u64 {
+        if self.max_voter_weight > 0 {
+            balance.min(self.max_voter_weight)
+        } else {
+            balance
+        }
+    }
+}
+
+/// Source of the token balances of a token-weighted election
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenSnapshot {
+    /// Merkle root of (owner, balance) leaves posted by the authority
+    /// Voters prove their balance with prove_token_balance before voting.
+    Merkle {
+        root: [u8; 32],
+        /// Sum of the leaf balances, the eligible weight for quorum
+        /// Trusted authority input: it is not checked against the leaves.
+        total_balance: u64,
+    },
+    /// Live balances, locked by depositing them in the election's vault
+    /// The voter deposits from their token account; the tokens stay in
+    /// escrow while the vote counts, so they cannot vote twice.
+    Escrow,
+}
+
+impl TokenSnapshot {
+    /// 1 (variant) + 32 (root) + 8 (total_balance)
+    pub const SIZE: usize = 1 + 32 + 8;
+}
+
+/// Token Voter Weight - Regular account locking the token weight a voter votes with
+/// Proven from the Merkle snapshot or deposited in escrow. Every cast and
+/// recast counts the current weight, which stays locked while a vote counts.
+#[account]
+#[derive(Debug)]
+pub struct TokenVoterWeight {
+    /// Election the weight applies to
+    pub election: Pubkey,
+
+    /// The voter
+    pub voter: Pubkey,
+
+    /// Token account the balance was deposited from (default for Merkle snapshots)
+    pub token_account: Pubkey,
+
+    /// Token balance counted for the voter (tokens held in escrow)
+    pub balance: u64,
+
+    /// Weight the voter votes with (balance after the per-voter cap)
+    pub weight: u64,
+
+    /// Whether a counted vote uses this weight (escrowed tokens stay locked while it does)
+    pub vote_counted: bool,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl TokenVoterWeight {
+    /// Size of the token voter weight account
+    /// 8 (discriminator) + 32 (election) + 32 (voter) + 32 (token_account) + 8 (balance) + 8 (weight) + 1 (vote_counted) + 1 (bump)
+    /// = 122 bytes
+    pub const SIZE: usize = 8 + 32 + 32 + 32 + 8 + 8 + 1 + 1;
+
+    /// Whether the balance was deposited in the escrow vault (not proven from a Merkle snapshot)
+    pub fn is_escrowed(&self) -> bool {
+        self.token_account != Pubkey::default()
+    }
+}
@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use crate::errors::*;
+use crate::state::Ballot;
 
 /// Vote Record - Compressed account for historical archive
 /// Created after election ends for audit trail
@@ -12,26 +14,73 @@ pub struct VoteRecord {
     /// This is derived from voter pubkey + salt
     pub voter_hash: [u8; 32],
     
-    /// The choice that was voted for (index into candidates array)
-    pub choice: u8,
+    /// The ballot as cast, in the shape of the election's voting method
+    pub ballot: Ballot,
     
     /// Unix timestamp when vote was cast
     pub timestamp: i64,
     
     /// Optional: Transaction signature for verification
     pub signature: Option<[u8; 64]>,
+
+    /// Optional: Why the voter voted this way (from the VoteRationaleSubmitted event)
+    pub rationale: Option<VoteRationale>,
 }
 
 impl VoteRecord {
-    /// Size of a vote record
-    /// 32 (election) + 32 (voter_hash) + 1 (choice) + 8 (timestamp) + 1 (option tag) + 64 (signature)
-    /// = 138 bytes
-    pub const SIZE: usize = 138;
+    /// Largest vote record
+    /// 32 (election) + 32 (voter_hash) + 85 (ballot) + 8 (timestamp) + 1 (option tag) + 64 (signature) + 1 (option tag) + 285 (rationale)
+    /// = 508 bytes
+    pub const SIZE: usize = 138 + Ballot::MAX_SIZE + VoteRationale::MAX_SIZE;
     
-    /// Size without signature
-    /// 32 (election) + 32 (voter_hash) + 1 (choice) + 8 (timestamp) + 1 (option tag)
-    /// = 74 bytes
-    pub const SIZE_NO_SIG: usize = 74;
+    /// Largest vote record without signature or rationale
+    /// 32 (election) + 32 (voter_hash) + 85 (ballot) + 8 (timestamp) + 1 (option tag) + 1 (option tag)
+    /// = 159 bytes
+    pub const SIZE_NO_SIG: usize = 74 + Ballot::MAX_SIZE;
+}
+
+/// Rationale a voter attaches to their ballot
+/// Short reasons are given inline, longer ones are published elsewhere and
+/// referenced by hash so the text can be checked against the vote.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum VoteRationale {
+    /// Short rationale stated inline
+    Text {
+        /// Rationale text (max 280 bytes)
+        text: String,
+    },
+    /// Rationale published off-chain
+    Reference {
+        /// Hash of the published rationale
+        hash: [u8; 32],
+        /// Where the rationale is published (max 200 bytes)
+        uri: String,
+    },
+}
+
+impl VoteRationale {
+    /// Maximum inline rationale length in bytes
+    pub const MAX_TEXT_LEN: usize = 280;
+
+    /// Maximum rationale URI length in bytes
+    pub const MAX_URI_LEN: usize = 200;
+
+    /// Largest serialized rationale
+    /// 1 (variant) + 4 (string len) + 280 (text)
+    /// = 285 bytes
+    pub const MAX_SIZE: usize = 1 + 4 + Self::MAX_TEXT_LEN;
+
+    /// Check the rationale fits its length limit
+    pub fn validate(&self) -> Result<()> {
+        let fits = match self {
+            VoteRationale::Text { text } => !text.is_empty() && text.len() <= Self::MAX_TEXT_LEN,
+            VoteRationale::Reference { uri, .. } => !uri.is_empty() && uri.len() <= Self::MAX_URI_LEN,
+        };
+
+        require!(fits, GovError::InvalidRationale);
+
+        Ok(())
+    }
 }
 
 /// Nullifier to prevent double voting
@@ -87,3 +136,59 @@ impl NullifierSet {
     /// Limited by account size (10MB)
     pub const MAX_NULLIFIERS: usize = 300_000; // ~10MB / 32 bytes
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(len: usize) -> VoteRationale {
+        VoteRationale::Text { text: "a".repeat(len) }
+    }
+
+    fn reference(len: usize) -> VoteRationale {
+        VoteRationale::Reference { hash: [7; 32], uri: "u".repeat(len) }
+    }
+
+    #[test]
+    fn test_rationale_length_limits() {
+        assert!(text(1).validate().is_ok());
+        assert!(text(VoteRationale::MAX_TEXT_LEN).validate().is_ok());
+        assert!(text(VoteRationale::MAX_TEXT_LEN + 1).validate().is_err());
+        assert!(text(0).validate().is_err());
+
+        assert!(reference(1).validate().is_ok());
+        assert!(reference(VoteRationale::MAX_URI_LEN).validate().is_ok());
+        assert!(reference(VoteRationale::MAX_URI_LEN + 1).validate().is_err());
+        assert!(reference(0).validate().is_err());
+    }
+
+    #[test]
+    fn test_rationale_limits_count_utf8_bytes() {
+        // Each "é" is two bytes, so only half as many fit
+        let fits = VoteRationale::Text { text: "é".repeat(VoteRationale::MAX_TEXT_LEN / 2) };
+        let too_long = VoteRationale::Text { text: "é".repeat(VoteRationale::MAX_TEXT_LEN / 2 + 1) };
+
+        assert!(fits.validate().is_ok());
+        assert!(too_long.validate().is_err());
+    }
+
+    #[test]
+    fn test_vote_record_archives_rationale() {
+        let record = VoteRecord {
+            election: Pubkey::new_unique(),
+            voter_hash: [1; 32],
+            ballot: Ballot::Quadratic { votes: vec![u32::MAX; 20] },
+            timestamp: 1_700_000_000,
+            signature: Some([2; 64]),
+            rationale: Some(text(VoteRationale::MAX_TEXT_LEN)),
+        };
+
+        // The largest ballot, a signature and the longest rationale fill the record
+        let data = record.try_to_vec().unwrap();
+        assert_eq!(data.len(), VoteRecord::SIZE);
+
+        let archived = VoteRecord::try_from_slice(&data).unwrap();
+        assert_eq!(archived.ballot, record.ballot);
+        assert_eq!(archived.rationale, record.rationale);
+    }
+}
@@ -19,13 +19,19 @@ pub struct VoterRegistration {
 
     /// Unix timestamp when voter registered
     pub registered_at: i64,
+
+    /// Voting weight added to the tally by each of the voter's votes
+    pub weight: u64,
 }
 
+/// Voting weight of a registration that carries no explicit weight
+pub const DEFAULT_VOTER_WEIGHT: u64 = 1;
+
 impl VoterRegistration {
     /// Size of a voter registration record
-    /// 32 (wallet) + 32 (attestation) + 32 (election) + 8 (registered_at)
-    /// = 104 bytes
-    pub const SIZE: usize = 104;
+    /// 32 (wallet) + 32 (attestation) + 32 (election) + 8 (registered_at) + 8 (weight)
+    /// = 112 bytes
+    pub const SIZE: usize = 112;
 }
 
 /// Helper struct for merkle tree operations
@@ -19,6 +19,8 @@ pub struct CompressedVoterData {
     pub election: Pubkey,
     pub attestation: Pubkey,
     pub registered_at: i64,
+    /// Voting weight, covered by the leaf hash
+    pub weight: u64,
 }
 
 impl CompressedVoterData {
@@ -28,12 +30,14 @@ impl CompressedVoterData {
         election: Pubkey,
         attestation: Pubkey,
         registered_at: i64,
+        weight: u64,
     ) -> Self {
         Self {
             voter,
             election,
             attestation,
             registered_at,
+            weight,
         }
     }
 
@@ -150,19 +154,20 @@ pub fn compress_voter_registration(
     attestation: &Pubkey,
     election: &Pubkey,
     registered_at: i64,
+    weight: u64,
 ) -> Result<Vec<u8>> {
-    let data = CompressedVoterData::new(*voter, *election, *attestation, registered_at);
+    let data = CompressedVoterData::new(*voter, *election, *attestation, registered_at, weight);
     data.try_to_vec().map_err(|_| GovError::ArithmeticOverflow.into())
 }
 
 /// Decompress voter registration data (legacy function for compatibility)
 pub fn decompress_voter_registration(
     compressed_data: &[u8],
-) -> Result<(Pubkey, Pubkey, Pubkey, i64)> {
+) -> Result<(Pubkey, Pubkey, Pubkey, i64, u64)> {
     let data: CompressedVoterData = CompressedVoterData::try_from_slice(compressed_data)
         .map_err(|_| GovError::InvalidMerkleProof)?;
 
-    Ok((data.voter, data.election, data.attestation, data.registered_at))
+    Ok((data.voter, data.election, data.attestation, data.registered_at, data.weight))
 }
 
 /// Get the merkle tree size required for a given number of voters
@@ -191,12 +196,13 @@ mod tests {
         let attestation = Pubkey::new_unique();
         let registered_at = 1234567890i64;
 
-        let data = CompressedVoterData::new(voter, election, attestation, registered_at);
+        let data = CompressedVoterData::new(voter, election, attestation, registered_at, 5);
 
         assert_eq!(data.voter, voter);
         assert_eq!(data.election, election);
         assert_eq!(data.attestation, attestation);
         assert_eq!(data.registered_at, registered_at);
+        assert_eq!(data.weight, 5);
     }
 
     #[test]
@@ -206,16 +212,20 @@ mod tests {
         let attestation = Pubkey::new_unique();
         let registered_at = 1234567890i64;
 
-        let data = CompressedVoterData::new(voter, election, attestation, registered_at);
+        let data = CompressedVoterData::new(voter, election, attestation, registered_at, 1);
         let hash = data.to_leaf_hash().unwrap();
 
         // Hash should be 32 bytes
         assert_eq!(hash.len(), 32);
 
         // Same data should produce same hash
-        let data2 = CompressedVoterData::new(voter, election, attestation, registered_at);
+        let data2 = CompressedVoterData::new(voter, election, attestation, registered_at, 1);
         let hash2 = data2.to_leaf_hash().unwrap();
         assert_eq!(hash, hash2);
+
+        // The weight is committed in the leaf
+        let heavier = CompressedVoterData::new(voter, election, attestation, registered_at, 2);
+        assert_ne!(hash, heavier.to_leaf_hash().unwrap());
     }
 
     #[test]
@@ -225,13 +235,14 @@ mod tests {
         let attestation = Pubkey::new_unique();
         let registered_at = 1234567890i64;
 
-        let compressed = compress_voter_registration(&voter, &attestation, &election, registered_at).unwrap();
-        let (v, e, a, r) = decompress_voter_registration(&compressed).unwrap();
+        let compressed = compress_voter_registration(&voter, &attestation, &election, registered_at, 3).unwrap();
+        let (v, e, a, r, w) = decompress_voter_registration(&compressed).unwrap();
 
         assert_eq!(v, voter);
         assert_eq!(e, election);
         assert_eq!(a, attestation);
         assert_eq!(r, registered_at);
+        assert_eq!(w, 3);
     }
 
     #[test]
@@ -0,0 +1,159 @@
+//! Conviction voting math
+//!
+//! Conviction of a proposal follows y(n+1) = α·y(n) + x per period, where x
+//! is the weight staked on it and α the decay. Advancing several periods
+//! at once uses the closed form
+//!
+//!   y(n) = αⁿ·y(0) + x·(1 - αⁿ) / (1 - α)
+//!
+//! A proposal requesting r out of the remaining funds R passes once its
+//! conviction reaches ρ·S / ((1 - α)·(β - r/R)²), where S is the total voting
+//! weight, β the maximum share of funds a proposal may request and ρ a weight
+//! parameter. All arithmetic is integer and rounds down; conviction and
+//! thresholds saturate at u64::MAX instead of failing.
+
+/// Conviction units per unit of weight staked for one period
+pub const CONVICTION_SCALE: u64 = 1_000_000;
+
+/// Highest decay accepted, so conviction stays within 1000x the staked weight
+pub const MAX_DECAY_BPS: u16 = 9_990;
+
+/// Basis points in 100%
+const BPS: u128 = 10_000;
+
+/// Fixed-point precision of αⁿ
+const DECAY_PRECISION: u128 = 1_000_000_000_000;
+
+/// αⁿ in DECAY_PRECISION fixed point
+pub fn decay_factor(decay_bps: u16, steps: u64) -> u128 {
+    let mut factor = DECAY_PRECISION;
+    let mut base = decay_bps as u128 * DECAY_PRECISION / BPS;
+    let mut remaining = steps;
+
+    while remaining > 0 {
+        if remaining & 1 == 1 {
+            factor = factor * base / DECAY_PRECISION;
+        }
+        base = base * base / DECAY_PRECISION;
+        remaining >>= 1;
+    }
+
+    factor
+}
+
+/// Conviction after `steps` periods with `staked` weight held constant
+///
+/// `decay_bps` must be below 10000. Saturates at u64::MAX, so a large stake
+/// can't leave the conviction state unable to advance.
+pub fn advance_conviction(
+    conviction: u64,
+    staked: u64,
+    decay_bps: u16,
+    steps: u64,
+) -> u64 {
+    let factor = decay_factor(decay_bps, steps);
+
+    let decayed = conviction as u128 * factor / DECAY_PRECISION;
+
+    // At most u64::MAX * 1e6 * 1e12 * 1e4 / 1e12, well within u128
+    let growth = (staked as u128)
+        * CONVICTION_SCALE as u128
+        * (DECAY_PRECISION - factor)
+        / DECAY_PRECISION
+        * BPS
+        / (BPS - decay_bps as u128);
+
+    u64::try_from(decayed + growth).unwrap_or(u64::MAX)
+}
+
+/// Conviction needed to pass a proposal requesting `requested` out of `total_funds`
+///
+/// None when the request is at or above the maximum share of funds, or when
+/// the threshold is beyond the u64::MAX saturated conviction can reach.
+pub fn conviction_threshold(
+    requested: u64,
+    total_funds: u64,
+    total_weight: u64,
+    max_ratio_bps: u16,
+    weight_bps: u16,
+    decay_bps: u16,
+) -> Option<u64> {
+    let ratio_bps = (requested as u128) * BPS / (total_funds.max(1) as u128);
+    let max_ratio_bps = max_ratio_bps as u128;
+
+    if ratio_bps >= max_ratio_bps {
+        return None;
+    }
+
+    let diff = max_ratio_bps - ratio_bps;
+
+    let threshold = (weight_bps as u128)
+        .saturating_mul(total_weight as u128)
+        .saturating_mul(CONVICTION_SCALE as u128)
+        .saturating_mul(BPS * BPS)
+        / ((BPS - decay_bps as u128) * diff * diff);
+
+    u64::try_from(threshold).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decay_factor() {
+        assert_eq!(decay_factor(5000, 0), DECAY_PRECISION);
+        assert_eq!(decay_factor(5000, 1), DECAY_PRECISION / 2);
+        assert_eq!(decay_factor(5000, 2), DECAY_PRECISION / 4);
+        assert_eq!(decay_factor(9000, 2), DECAY_PRECISION * 81 / 100);
+    }
+
+    #[test]
+    fn test_conviction_growth_and_decay() {
+        // α = 0.9, 10 staked: 10, then 0.9 * 10 + 10 = 19
+        assert_eq!(advance_conviction(0, 10, 9000, 1), 10 * CONVICTION_SCALE);
+        assert_eq!(advance_conviction(0, 10, 9000, 2), 19 * CONVICTION_SCALE);
+
+        let one_step = advance_conviction(10 * CONVICTION_SCALE, 10, 9000, 1);
+        assert_eq!(one_step, 19 * CONVICTION_SCALE);
+
+        // Approaches x / (1 - α) = 100 and never exceeds it
+        let converged = advance_conviction(0, 10, 9000, 1_000);
+        assert!(converged <= 100 * CONVICTION_SCALE && converged > 99 * CONVICTION_SCALE);
+
+        // Unstaked conviction decays away
+        assert_eq!(advance_conviction(19 * CONVICTION_SCALE, 0, 5000, 1), 9_500_000);
+    }
+
+    #[test]
+    fn test_conviction_saturates_near_the_limit() {
+        // At the maximum decay 18e9 staked converges to 1.8e19, just below u64::MAX
+        let converged = advance_conviction(0, 18_000_000_000, MAX_DECAY_BPS, 100_000);
+        assert!(converged < u64::MAX && converged > 17_999_000_000 * CONVICTION_SCALE * 1_000);
+
+        // 19e9 would converge past it and stays at u64::MAX instead of failing
+        let saturated = advance_conviction(converged, 19_000_000_000, MAX_DECAY_BPS, 100_000);
+        assert_eq!(saturated, u64::MAX);
+        assert_eq!(advance_conviction(u64::MAX, u64::MAX, MAX_DECAY_BPS, 1), u64::MAX);
+
+        // and decays normally once unstaked
+        assert_eq!(advance_conviction(u64::MAX, 0, 5000, 1), u64::MAX / 2);
+
+        // A threshold past u64::MAX can't be told apart from saturation, so it is never met
+        assert_eq!(conviction_threshold(0, 1, u64::MAX, 10_000, 10_000, 9000), None);
+    }
+
+    #[test]
+    fn test_conviction_threshold() {
+        // ρ = 0.0025, S = 100, α = 0.9, β = 0.2, r/R = 0.1: 0.25 / (0.1 * 0.01) = 250
+        let threshold = conviction_threshold(100, 1_000, 100, 2000, 25, 9000).unwrap();
+        assert_eq!(threshold, 250 * CONVICTION_SCALE);
+
+        // Larger requests need more conviction
+        let larger = conviction_threshold(150, 1_000, 100, 2000, 25, 9000).unwrap();
+        assert!(larger > threshold);
+
+        // Requests at or above the maximum share can never pass
+        assert_eq!(conviction_threshold(200, 1_000, 100, 2000, 25, 9000), None);
+    }
+}
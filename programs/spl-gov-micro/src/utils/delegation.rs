@@ -0,0 +1,76 @@
+//! Delegation chain checks for liquid democracy
+//!
+//! A delegate passes the delegations chained to them in order: every link
+//! must delegate to the voter or to a delegator listed before it, so the
+//! chain forms a tree rooted at the voter. A link's depth is one more than
+//! the depth of its delegate (the voter is at depth 0). Delegators may only
+//! appear once and never as the voter, which rules out cycles.
+
+use anchor_lang::prelude::*;
+use crate::errors::*;
+
+/// Depth of each (delegator, delegate) link of a delegation tree rooted at `voter`
+pub fn delegation_depths(
+    voter: &Pubkey,
+    links: &[(Pubkey, Pubkey)],
+    max_depth: u8,
+) -> Result<Vec<u8>> {
+    let mut depths: Vec<u8> = Vec::with_capacity(links.len());
+
+    for (idx, (delegator, delegate)) in links.iter().enumerate() {
+        require!(
+            delegator != voter && !links[..idx].iter().any(|(earlier, _)| earlier == delegator),
+            GovError::InvalidDelegationChain
+        );
+
+        let delegate_depth = if delegate == voter {
+            0
+        } else {
+            links[..idx]
+                .iter()
+                .position(|(earlier, _)| earlier == delegate)
+                .map(|position| depths[position])
+                .ok_or(GovError::InvalidDelegationChain)?
+        };
+
+        require!(
+            delegate_depth < max_depth,
+            GovError::DelegationTooDeep
+        );
+
+        depths.push(delegate_depth + 1);
+    }
+
+    Ok(depths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delegation_chain() {
+        let (voter, a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        // A -> voter, B -> A, C -> voter
+        let depths = delegation_depths(&voter, &[(a, voter), (b, a), (c, voter)], 4).unwrap();
+        assert_eq!(depths, vec![1, 2, 1]);
+
+        // Links must come after the link they delegate to
+        assert!(delegation_depths(&voter, &[(b, a), (a, voter)], 4).is_err());
+
+        // Too deep
+        assert!(delegation_depths(&voter, &[(a, voter), (b, a), (c, b)], 2).is_err());
+    }
+
+    #[test]
+    fn test_delegation_cycles_rejected() {
+        let (voter, a) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        // The voter cannot be counted as their own delegator
+        assert!(delegation_depths(&voter, &[(a, voter), (voter, a)], 4).is_err());
+
+        // A delegator is only counted once
+        assert!(delegation_depths(&voter, &[(a, voter), (a, voter)], 4).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+//! Instant runoff (IRV) tabulation over aggregated ranked ballots
+//!
+//! Each round counts every ballot for its highest-ranked continuing
+//! candidate. A candidate with a majority of the continuing ballots wins;
+//! otherwise the candidate with the fewest votes is eliminated. Ties for
+//! elimination eliminate the candidate listed last.
+
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+
+/// Result of a single IRV round
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IrvRound {
+    /// Votes per candidate this round
+    pub tallies: Vec<u64>,
+    /// Ballots with no continuing candidate left
+    pub exhausted: u64,
+    /// What the round decided
+    pub outcome: IrvOutcome,
+}
+
+/// Decision taken at the end of an IRV round
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IrvOutcome {
+    /// Candidate reached a majority or is the last one standing
+    Winner(u8),
+    /// Candidate was eliminated, another round follows
+    Eliminated(u8),
+    /// No ballot counts for any continuing candidate
+    NoWinner,
+}
+
+/// Run one IRV round given the candidates eliminated so far
+pub fn irv_round(
+    candidate_count: usize,
+    rankings: &[RankingCount],
+    eliminated: &[u8],
+) -> Result<IrvRound> {
+    let mut tallies = vec![0u64; candidate_count];
+    let mut exhausted = 0u64;
+
+    for entry in rankings {
+        match entry.ranking.iter().find(|candidate| !eliminated.contains(candidate)) {
+            Some(candidate) => {
+                tallies[*candidate as usize] = tallies[*candidate as usize]
+                    .checked_add(entry.count)
+                    .ok_or(GovError::ArithmeticOverflow)?;
+            }
+            None => {
+                exhausted = exhausted
+                    .checked_add(entry.count)
+                    .ok_or(GovError::ArithmeticOverflow)?;
+            }
+        }
+    }
+
+    let continuing: Vec<usize> = (0..candidate_count)
+        .filter(|candidate| !eliminated.contains(&(*candidate as u8)))
+        .collect();
+
+    let continuing_total = continuing
+        .iter()
+        .try_fold(0u64, |sum, candidate| sum.checked_add(tallies[*candidate]))
+        .ok_or(GovError::ArithmeticOverflow)?;
+
+    let outcome = if continuing_total == 0 {
+        IrvOutcome::NoWinner
+    } else if let Some(winner) = continuing.iter().find(|candidate| tallies[**candidate] > continuing_total / 2) {
+        IrvOutcome::Winner(*winner as u8)
+    } else if continuing.len() == 1 {
+        IrvOutcome::Winner(continuing[0] as u8)
+    } else {
+        // Fewest votes is eliminated; ties eliminate the later candidate
+        let loser = continuing
+            .iter()
+            .rev()
+            .min_by_key(|candidate| tallies[**candidate])
+            .copied()
+            .unwrap_or(0);
+        IrvOutcome::Eliminated(loser as u8)
+    };
+
+    Ok(IrvRound {
+        tallies,
+        exhausted,
+        outcome,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rankings(entries: &[(&[u8], u64)]) -> Vec<RankingCount> {
+        entries
+            .iter()
+            .map(|(ranking, count)| RankingCount { ranking: ranking.to_vec(), count: *count })
+            .collect()
+    }
+
+    #[test]
+    fn test_irv_first_round_majority() {
+        let ballots = rankings(&[(&[0, 1], 3), (&[1], 2)]);
+        let round = irv_round(2, &ballots, &[]).unwrap();
+
+        assert_eq!(round.tallies, vec![3, 2]);
+        assert_eq!(round.outcome, IrvOutcome::Winner(0));
+    }
+
+    #[test]
+    fn test_irv_transfers_after_elimination() {
+        // A: 4, B: 3, C: 2 (C's voters prefer B)
+        let ballots = rankings(&[(&[0], 4), (&[1, 0], 3), (&[2, 1], 2)]);
+
+        let round1 = irv_round(3, &ballots, &[]).unwrap();
+        assert_eq!(round1.tallies, vec![4, 3, 2]);
+        assert_eq!(round1.outcome, IrvOutcome::Eliminated(2));
+
+        let round2 = irv_round(3, &ballots, &[2]).unwrap();
+        assert_eq!(round2.tallies, vec![4, 5, 0]);
+        assert_eq!(round2.outcome, IrvOutcome::Winner(1));
+    }
+
+    #[test]
+    fn test_irv_exhausted_ballots() {
+        // C's voters ranked nobody else
+        let ballots = rankings(&[(&[0], 2), (&[1], 2), (&[2], 1)]);
+
+        let round1 = irv_round(3, &ballots, &[]).unwrap();
+        assert_eq!(round1.outcome, IrvOutcome::Eliminated(2));
+
+        let round2 = irv_round(3, &ballots, &[2]).unwrap();
+        assert_eq!(round2.exhausted, 1);
+        // 2-2 tie between A and B: the later candidate is eliminated
+        assert_eq!(round2.outcome, IrvOutcome::Eliminated(1));
+
+        let round3 = irv_round(3, &ballots, &[2, 1]).unwrap();
+        assert_eq!(round3.outcome, IrvOutcome::Winner(0));
+    }
+
+    #[test]
+    fn test_irv_no_ballots() {
+        let round = irv_round(3, &[], &[]).unwrap();
+        assert_eq!(round.outcome, IrvOutcome::NoWinner);
+    }
+}
@@ -40,11 +40,23 @@ pub fn create_voter_leaf(
     voter: &Pubkey,
     election: &Pubkey,
     attestation: &Pubkey,
+    weight: u64,
 ) -> [u8; 32] {
-    let mut data = Vec::with_capacity(96);
+    let mut data = Vec::with_capacity(104);
     data.extend_from_slice(voter.as_ref());
     data.extend_from_slice(election.as_ref());
     data.extend_from_slice(attestation.as_ref());
+    data.extend_from_slice(&weight.to_le_bytes());
+    
+    let hash_result = hash(&data);
+    hash_result.to_bytes()
+}
+
+/// Create a leaf hash from a token snapshot entry (owner, balance)
+pub fn create_token_balance_leaf(owner: &Pubkey, balance: u64) -> [u8; 32] {
+    let mut data = Vec::with_capacity(40);
+    data.extend_from_slice(owner.as_ref());
+    data.extend_from_slice(&balance.to_le_bytes());
     
     let hash_result = hash(&data);
     hash_result.to_bytes()
@@ -58,4 +70,21 @@ mod tests {
     fn test_merkle_proof() {
         // TODO: Add merkle proof tests
     }
+
+    #[test]
+    fn test_token_balance_proof() {
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+
+        let alice_leaf = create_token_balance_leaf(&alice, 500);
+        let bob_leaf = create_token_balance_leaf(&bob, 1_000);
+        let root = hash_pair(&alice_leaf, &bob_leaf);
+
+        assert!(verify_merkle_proof(alice_leaf, root, &[bob_leaf], 0).unwrap());
+        assert!(verify_merkle_proof(bob_leaf, root, &[alice_leaf], 1).unwrap());
+
+        // A different balance is not in the snapshot
+        let inflated = create_token_balance_leaf(&alice, 5_000);
+        assert!(!verify_merkle_proof(inflated, root, &[bob_leaf], 0).unwrap());
+    }
 }
@@ -1,5 +1,19 @@
 pub mod merkle;
 pub mod compression;
+pub mod tally;
+pub mod irv;
+pub mod stv;
+pub mod schulze;
+pub mod conviction;
+pub mod delegation;
+pub mod realloc;
 
 pub use merkle::*;
 pub use compression::*;
+pub use tally::*;
+pub use irv::*;
+pub use stv::*;
+pub use schulze::*;
+pub use conviction::*;
+pub use delegation::*;
+pub use realloc::*;
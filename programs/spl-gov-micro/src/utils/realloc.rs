@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+/// Grow a program account to `new_len` bytes
+///
+/// The payer tops the account up to the rent-exempt minimum of the new size.
+/// An instruction can grow an account by at most 10 KiB.
+pub fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    let rent_exempt = Rent::get()?.minimum_balance(new_len);
+    let top_up = rent_exempt.saturating_sub(account.lamports());
+
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+
+    account.realloc(new_len, false)?;
+
+    Ok(())
+}
+
+/// Grow a program account to `grown_len` bytes unless it already has `required_len`
+///
+/// Used to make room for one more entry of an account's growing Vec before
+/// it is pushed; `grown_len` adds room for several entries at once.
+pub fn reserve_space<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    required_len: usize,
+    grown_len: usize,
+) -> Result<()> {
+    if account.data_len() < required_len {
+        grow_account(account, payer, system_program, grown_len)?;
+    }
+
+    Ok(())
+}
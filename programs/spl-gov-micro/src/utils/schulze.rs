@@ -0,0 +1,103 @@
+//! Schulze method over a pairwise preference matrix
+//!
+//! The strength of the direct link from i to j is d[i][j] if more ballots
+//! prefer i over j than j over i, otherwise 0. The beat-path strength p[i][j]
+//! is the strongest path from i to j, where a path is as strong as its
+//! weakest link. i beats j when p[i][j] > p[j][i].
+//!
+//! Candidates are ordered by how many others they beat (most first), ties
+//! broken by candidate index. The first candidate is the Schulze winner.
+
+/// Beat-path strengths and finishing order of a Schulze tabulation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchulzeOutcome {
+    /// N×N beat-path strengths, row-major
+    pub strengths: Vec<u64>,
+    /// Candidates, best first
+    pub order: Vec<u8>,
+}
+
+/// Run the Schulze method on an N×N row-major preference matrix
+pub fn schulze(candidate_count: usize, preferences: &[u64]) -> SchulzeOutcome {
+    let n = candidate_count;
+    let d = |i: usize, j: usize| preferences[i * n + j];
+
+    // Direct links
+    let mut p = vec![0u64; n * n];
+    for i in 0..n {
+        for j in 0..n {
+            if i != j && d(i, j) > d(j, i) {
+                p[i * n + j] = d(i, j);
+            }
+        }
+    }
+
+    // Widest paths (Floyd-Warshall)
+    for k in 0..n {
+        for i in 0..n {
+            if i == k {
+                continue;
+            }
+            for j in 0..n {
+                if j == i || j == k {
+                    continue;
+                }
+                let via = p[i * n + k].min(p[k * n + j]);
+                if via > p[i * n + j] {
+                    p[i * n + j] = via;
+                }
+            }
+        }
+    }
+
+    let wins = |i: usize| (0..n).filter(|j| *j != i && p[i * n + j] > p[j * n + i]).count();
+
+    let mut order: Vec<u8> = (0..n as u8).collect();
+    order.sort_by(|a, b| wins(*b as usize).cmp(&wins(*a as usize)).then(a.cmp(b)));
+
+    SchulzeOutcome {
+        strengths: p,
+        order,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schulze_condorcet_winner() {
+        // B beats A 3-2 and C 4-1, A beats C 3-2
+        let preferences = vec![
+            0, 2, 3,
+            3, 0, 4,
+            2, 1, 0,
+        ];
+
+        let outcome = schulze(3, &preferences);
+        assert_eq!(outcome.order, vec![1, 0, 2]);
+    }
+
+    #[test]
+    fn test_schulze_cycle() {
+        // Classic cycle: A > B (6-3), B > C (7-2), C > A (5-4)
+        // Beat paths: A->C = min(6, 7) = 6 beats C->A = 5, so A wins
+        let preferences = vec![
+            0, 6, 4,
+            3, 0, 7,
+            5, 2, 0,
+        ];
+
+        let outcome = schulze(3, &preferences);
+        assert_eq!(outcome.strengths[2], 6);
+        assert_eq!(outcome.strengths[2 * 3], 5);
+        assert_eq!(outcome.order, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_schulze_no_ballots() {
+        let outcome = schulze(3, &[0; 9]);
+        assert_eq!(outcome.strengths, vec![0; 9]);
+        assert_eq!(outcome.order, vec![0, 1, 2]);
+    }
+}
@@ -0,0 +1,294 @@
+//! Single transferable vote (STV) tabulation over aggregated ranked ballots
+//!
+//! All arithmetic is integer fixed-point (u128, so weighted ballots cannot
+//! overflow) and the on-chain result can be reproduced exactly off-chain by
+//! calling the same functions:
+//!
+//! - One ballot is worth `STV_SCALE` units
+//! - Droop quota = (floor(ballots / (seats + 1)) + 1) * STV_SCALE
+//! - Each round counts every ranking group for its highest-ranked
+//!   continuing candidate at the group's current value
+//! - If the continuing candidates fit in the remaining seats they are all
+//!   elected, highest tally first (ties go to the earlier candidate)
+//! - Otherwise the highest tally reaching the quota is elected (ties go to
+//!   the earlier candidate) and every group counting for them is scaled to
+//!   floor(value * surplus / tally) before transferring on
+//! - Otherwise the lowest tally is eliminated (ties eliminate the later candidate)
+
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+
+/// Fixed-point units per ballot
+pub const STV_SCALE: u128 = 1_000_000_000;
+
+/// Result of a single STV round
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StvRound {
+    /// Value per candidate this round, in fixed-point units
+    pub tallies: Vec<u128>,
+    /// Value of ballots with no continuing candidate left
+    pub exhausted: u128,
+    /// What the round decided
+    pub outcome: StvOutcome,
+}
+
+/// Decision taken at the end of an STV round
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StvOutcome {
+    /// Candidate reached the quota, their surplus was transferred
+    Elected(u8),
+    /// Remaining continuing candidates fill the remaining seats
+    ElectedRemaining(Vec<u8>),
+    /// Candidate was eliminated, another round follows
+    Eliminated(u8),
+    /// No ballot counts for any continuing candidate
+    NoWinner,
+}
+
+/// Droop quota for the given number of ballots and seats, in fixed-point units
+pub fn droop_quota(rankings: &[RankingCount], seats: u8) -> Result<u128> {
+    let ballots = rankings
+        .iter()
+        .try_fold(0u128, |sum, entry| sum.checked_add(entry.count as u128))
+        .ok_or(GovError::ArithmeticOverflow)?;
+
+    (ballots / (seats as u128 + 1) + 1)
+        .checked_mul(STV_SCALE)
+        .ok_or_else(|| error!(GovError::ArithmeticOverflow))
+}
+
+/// Starting value of each ranking group, in fixed-point units
+pub fn initial_weights(rankings: &[RankingCount]) -> Result<Vec<u128>> {
+    rankings
+        .iter()
+        .map(|entry| {
+            (entry.count as u128)
+                .checked_mul(STV_SCALE)
+                .ok_or_else(|| error!(GovError::ArithmeticOverflow))
+        })
+        .collect()
+}
+
+/// Run one STV round given the candidates elected and eliminated so far
+///
+/// `weights` holds the current value of each ranking group and is updated
+/// in place when a surplus is transferred.
+pub fn stv_round(
+    candidate_count: usize,
+    seats: u8,
+    quota: u128,
+    rankings: &[RankingCount],
+    weights: &mut [u128],
+    elected: &[u8],
+    eliminated: &[u8],
+) -> Result<StvRound> {
+    let is_continuing = |candidate: &u8| !elected.contains(candidate) && !eliminated.contains(candidate);
+
+    // Highest-ranked continuing candidate of each ranking group
+    let holders: Vec<Option<u8>> = rankings
+        .iter()
+        .map(|entry| entry.ranking.iter().copied().find(is_continuing))
+        .collect();
+
+    let mut tallies = vec![0u128; candidate_count];
+    let mut exhausted = 0u128;
+
+    for (holder, weight) in holders.iter().zip(weights.iter()) {
+        match holder {
+            Some(candidate) => {
+                tallies[*candidate as usize] = tallies[*candidate as usize]
+                    .checked_add(*weight)
+                    .ok_or(GovError::ArithmeticOverflow)?;
+            }
+            None => {
+                exhausted = exhausted
+                    .checked_add(*weight)
+                    .ok_or(GovError::ArithmeticOverflow)?;
+            }
+        }
+    }
+
+    let continuing: Vec<u8> = (0..candidate_count as u8)
+        .filter(is_continuing)
+        .collect();
+
+    let continuing_total = continuing
+        .iter()
+        .try_fold(0u128, |sum, candidate| sum.checked_add(tallies[*candidate as usize]))
+        .ok_or(GovError::ArithmeticOverflow)?;
+
+    let remaining_seats = (seats as usize).saturating_sub(elected.len());
+
+    // Highest tally first, ties go to the earlier candidate
+    let mut ranked = continuing.clone();
+    ranked.sort_by(|a, b| tallies[*b as usize].cmp(&tallies[*a as usize]).then(a.cmp(b)));
+
+    let outcome = if remaining_seats == 0 || continuing_total == 0 {
+        StvOutcome::NoWinner
+    } else if continuing.len() <= remaining_seats {
+        StvOutcome::ElectedRemaining(ranked)
+    } else if tallies[ranked[0] as usize] >= quota {
+        let winner = ranked[0];
+        let tally = tallies[winner as usize];
+        let surplus = tally - quota;
+
+        // Scale down the ballots counting for the winner to the surplus
+        for (holder, weight) in holders.iter().zip(weights.iter_mut()) {
+            if *holder == Some(winner) {
+                *weight = mul_div(*weight, surplus, tally);
+            }
+        }
+
+        StvOutcome::Elected(winner)
+    } else {
+        // Fewest votes is eliminated; ties eliminate the later candidate
+        let loser = ranked[ranked.len() - 1];
+        StvOutcome::Eliminated(loser)
+    };
+
+    Ok(StvRound {
+        tallies,
+        exhausted,
+        outcome,
+    })
+}
+
+/// floor(a * b / c) for a <= c, exact even when a * b exceeds u128
+///
+/// Falls back to a 256-bit product and shift-subtract division, the
+/// quotient is at most b so it always fits.
+fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product / c;
+    }
+
+    const LOW: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & LOW);
+    let (b_high, b_low) = (b >> 64, b & LOW);
+
+    let low_low = a_low * b_low;
+    let high_low = a_high * b_low;
+    let low_high = a_low * b_high;
+    let middle = (low_low >> 64) + (high_low & LOW) + (low_high & LOW);
+
+    let low = (low_low & LOW) | (middle << 64);
+    let high = a_high * b_high + (high_low >> 64) + (low_high >> 64) + (middle >> 64);
+
+    // high < c because the quotient fits in 128 bits
+    let mut remainder = high;
+    let mut quotient = 0u128;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= c {
+            remainder = remainder.wrapping_sub(c);
+            quotient |= 1;
+        }
+    }
+
+    quotient
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rankings(entries: &[(&[u8], u64)]) -> Vec<RankingCount> {
+        entries
+            .iter()
+            .map(|(ranking, count)| RankingCount { ranking: ranking.to_vec(), count: *count })
+            .collect()
+    }
+
+    #[test]
+    fn test_stv_surplus_transfer() {
+        // 12 ballots, 2 seats: quota = 12 / 3 + 1 = 5
+        let ballots = rankings(&[(&[0, 1], 6), (&[1], 2), (&[2], 4)]);
+        let quota = droop_quota(&ballots, 2).unwrap();
+        assert_eq!(quota, 5 * STV_SCALE);
+
+        let mut weights = initial_weights(&ballots).unwrap();
+
+        // A reaches the quota with 6, surplus of 1 moves on at 1/6 value
+        let round1 = stv_round(3, 2, quota, &ballots, &mut weights, &[], &[]).unwrap();
+        assert_eq!(round1.tallies, vec![6 * STV_SCALE, 2 * STV_SCALE, 4 * STV_SCALE]);
+        assert_eq!(round1.outcome, StvOutcome::Elected(0));
+        assert_eq!(weights, vec![STV_SCALE, 2 * STV_SCALE, 4 * STV_SCALE]);
+
+        // B: 3, C: 4, nobody reaches the quota
+        let round2 = stv_round(3, 2, quota, &ballots, &mut weights, &[0], &[]).unwrap();
+        assert_eq!(round2.tallies, vec![0, 3 * STV_SCALE, 4 * STV_SCALE]);
+        assert_eq!(round2.outcome, StvOutcome::Eliminated(1));
+
+        // C is the last continuing candidate for the last seat
+        let round3 = stv_round(3, 2, quota, &ballots, &mut weights, &[0], &[1]).unwrap();
+        assert_eq!(round3.exhausted, 3 * STV_SCALE);
+        assert_eq!(round3.outcome, StvOutcome::ElectedRemaining(vec![2]));
+    }
+
+    #[test]
+    fn test_stv_fractional_transfer_rounds_down() {
+        // 4 ballots, 2 seats: quota = 4 / 3 + 1 = 2, A's surplus is 1 of 3
+        let ballots = rankings(&[(&[0, 1], 2), (&[0, 2], 1), (&[1], 1)]);
+        let quota = droop_quota(&ballots, 2).unwrap();
+        assert_eq!(quota, 2 * STV_SCALE);
+
+        let mut weights = initial_weights(&ballots).unwrap();
+        let round = stv_round(3, 2, quota, &ballots, &mut weights, &[], &[]).unwrap();
+
+        assert_eq!(round.outcome, StvOutcome::Elected(0));
+        // Transferred values are floored, the lost unit is never counted
+        assert_eq!(weights, vec![666_666_666, 333_333_333, STV_SCALE]);
+    }
+
+    #[test]
+    fn test_stv_elect_remaining_order() {
+        let ballots = rankings(&[(&[2], 1), (&[1], 3)]);
+        let quota = droop_quota(&ballots, 2).unwrap();
+        let mut weights = initial_weights(&ballots).unwrap();
+
+        let round = stv_round(3, 2, quota, &ballots, &mut weights, &[], &[0]).unwrap();
+        assert_eq!(round.outcome, StvOutcome::ElectedRemaining(vec![1, 2]));
+    }
+
+    #[test]
+    fn test_stv_no_ballots() {
+        let mut weights = Vec::new();
+        let round = stv_round(3, 2, STV_SCALE, &[], &mut weights, &[], &[]).unwrap();
+        assert_eq!(round.outcome, StvOutcome::NoWinner);
+    }
+
+    #[test]
+    fn test_stv_large_weighted_ballots() {
+        // Weights far beyond u64::MAX / STV_SCALE
+        let big = u64::MAX / 2;
+        let ballots = rankings(&[(&[0, 1], big), (&[1], 1), (&[2], 2)]);
+        let quota = droop_quota(&ballots, 2).unwrap();
+        assert_eq!(quota, ((big as u128 + 3) / 3 + 1) * STV_SCALE);
+
+        let mut weights = initial_weights(&ballots).unwrap();
+        assert_eq!(weights[0], big as u128 * STV_SCALE);
+
+        let round1 = stv_round(3, 2, quota, &ballots, &mut weights, &[], &[]).unwrap();
+        assert_eq!(round1.outcome, StvOutcome::Elected(0));
+        assert_eq!(round1.tallies[0], big as u128 * STV_SCALE);
+
+        // The whole surplus moves on to B
+        let surplus = big as u128 * STV_SCALE - quota;
+        assert_eq!(weights[0], surplus);
+
+        let round2 = stv_round(3, 2, quota, &ballots, &mut weights, &[0], &[]).unwrap();
+        assert_eq!(round2.tallies[1], surplus + STV_SCALE);
+        assert_eq!(round2.outcome, StvOutcome::Elected(1));
+    }
+
+    #[test]
+    fn test_mul_div_wide_products() {
+        assert_eq!(mul_div(6, 5, 10), 3);
+        assert_eq!(mul_div(u128::MAX, 3, u128::MAX), 3);
+        assert_eq!(mul_div(u128::MAX - 1, u128::MAX - 2, u128::MAX), u128::MAX - 3);
+        assert_eq!(mul_div(1 << 100, 1 << 100, 1 << 101), 1 << 99);
+    }
+}
@@ -0,0 +1,889 @@
+//! Ballot validation and tallying shared by every vote path
+//! (single votes, batches, tally shards and ballot groups)
+
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+
+/// Check that a voting method's configuration fits the candidate list and seat count
+pub fn validate_voting_method(
+    method: &VotingMethod,
+    candidate_count: usize,
+    seats: u8,
+) -> Result<()> {
+    require!(
+        seats >= 1 && (seats as usize) <= candidate_count,
+        GovError::InvalidSeatCount
+    );
+
+    require!(
+        seats == 1 || method.supports_multiple_seats(),
+        GovError::InvalidSeatCount
+    );
+
+    match method {
+        VotingMethod::Plurality => {}
+        VotingMethod::Approval { max_approvals } => {
+            require!(
+                (*max_approvals as usize) <= candidate_count,
+                GovError::InvalidVotingMethod
+            );
+        }
+        VotingMethod::RankedChoice | VotingMethod::SingleTransferableVote | VotingMethod::Condorcet => {}
+        VotingMethod::Borda { .. } | VotingMethod::Cumulative | VotingMethod::Conviction => {}
+        VotingMethod::Budgeting { .. } => {}
+        VotingMethod::Quadratic { credits, .. } => {
+            require!(*credits > 0, GovError::InvalidVotingMethod);
+        }
+        VotingMethod::Score { min_score, max_score, .. } => {
+            require!(min_score < max_score, GovError::InvalidVotingMethod);
+        }
+        VotingMethod::Proposal { quorum_bps, pass_threshold_bps, veto_threshold_bps } => {
+            require!(
+                candidate_count == ProposalOption::ALL.len(),
+                GovError::InvalidVotingMethod
+            );
+
+            require!(
+                [quorum_bps, pass_threshold_bps, veto_threshold_bps]
+                    .iter()
+                    .all(|bps| **bps <= BPS_DENOMINATOR as u16),
+                GovError::InvalidThreshold
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that a ballot fits the election's voting method and candidates
+pub fn validate_ballot(
+    method: &VotingMethod,
+    candidate_count: usize,
+    seats: u8,
+    ballot: &Ballot,
+) -> Result<()> {
+    match (method, ballot) {
+        // Conviction elections vote by staking, not with ballots
+        (VotingMethod::Conviction, _) => return err!(GovError::BallotMethodMismatch),
+        // Every ballot method accepts abstaining or a blank ballot
+        (_, Ballot::Abstain | Ballot::Blank) => {}
+        (VotingMethod::Plurality, Ballot::Single { choice }) => {
+            require!(
+                (*choice as usize) < candidate_count,
+                GovError::InvalidChoice
+            );
+        }
+        (VotingMethod::Plurality, Ballot::Multiple { choices }) => {
+            require!(
+                seats > 1 && choices.len() <= seats as usize,
+                GovError::TooManyChoices
+            );
+
+            // Same rules as a ranking: nonempty, valid, no duplicates
+            validate_ranking(choices, candidate_count)?;
+        }
+        (VotingMethod::Approval { max_approvals }, Ballot::Approval { approved }) => {
+            // Bits beyond the candidate list are invalid
+            require!(
+                *approved != 0 && (*approved as u32) >> candidate_count == 0,
+                GovError::InvalidChoice
+            );
+
+            require!(
+                *max_approvals == 0 || approved.count_ones() <= *max_approvals as u32,
+                GovError::TooManyApprovals
+            );
+        }
+        (
+            VotingMethod::RankedChoice
+                | VotingMethod::SingleTransferableVote
+                | VotingMethod::Condorcet
+                | VotingMethod::Borda { .. },
+            Ballot::Ranked { ranking },
+        ) => {
+            validate_ranking(ranking, candidate_count)?;
+        }
+        (VotingMethod::Quadratic { .. }, Ballot::Quadratic { votes }) => {
+            require!(
+                votes.len() <= candidate_count && votes.iter().any(|amount| *amount > 0),
+                GovError::InvalidChoice
+            );
+        }
+        (VotingMethod::Proposal { .. }, Ballot::Proposal { .. }) => {}
+        (VotingMethod::Budgeting { points_per_voter: 0 }, Ballot::Approval { approved }) => {
+            require!(
+                *approved != 0 && (*approved as u32) >> candidate_count == 0,
+                GovError::InvalidChoice
+            );
+        }
+        (
+            VotingMethod::Cumulative | VotingMethod::Budgeting { points_per_voter: 1.. },
+            Ballot::Cumulative { allocations },
+        ) => {
+            require!(
+                allocations.len() <= candidate_count && allocations.iter().any(|amount| *amount > 0),
+                GovError::InvalidChoice
+            );
+        }
+        (VotingMethod::Score { min_score, max_score, .. }, Ballot::Score { scores }) => {
+            require!(
+                scores.len() <= candidate_count && scores.iter().any(|score| score.is_some()),
+                GovError::InvalidChoice
+            );
+
+            require!(
+                scores.iter().flatten().all(|score| score >= min_score && score <= max_score),
+                GovError::ScoreOutOfRange
+            );
+        }
+        _ => return err!(GovError::BallotMethodMismatch),
+    }
+
+    Ok(())
+}
+
+/// Check that a ranking lists distinct, valid candidates
+pub fn validate_ranking(ranking: &[u8], candidate_count: usize) -> Result<()> {
+    require!(
+        !ranking.is_empty() && ranking.len() <= candidate_count,
+        GovError::InvalidChoice
+    );
+
+    let mut seen = 0u16;
+    for candidate in ranking {
+        require!(
+            (*candidate as usize) < candidate_count && seen & (1 << candidate) == 0,
+            GovError::InvalidChoice
+        );
+        seen |= 1 << candidate;
+    }
+
+    Ok(())
+}
+
+/// Check that a ballot fits the voter's budget
+///
+/// Quadratic ballots spend voice credits, cumulative ballots spend their
+/// seats × weight allowance; every other ballot passes.
+pub fn check_credit_budget(
+    method: &VotingMethod,
+    seats: u8,
+    ballot: &Ballot,
+    weight: u64,
+) -> Result<()> {
+    let Some(budget) = method.credit_budget(seats, weight) else {
+        return Ok(());
+    };
+
+    match ballot {
+        Ballot::Quadratic { votes } => {
+            require!(
+                quadratic_cost(votes)? <= budget,
+                GovError::InsufficientCredits
+            );
+        }
+        Ballot::Cumulative { allocations } => {
+            let allocated = allocations
+                .iter()
+                .try_fold(0u64, |sum, amount| sum.checked_add(*amount))
+                .ok_or(GovError::ArithmeticOverflow)?;
+
+            require!(
+                allocated <= budget,
+                GovError::AllowanceExceeded
+            );
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Voice credits spent by a quadratic ballot: the sum of the squared votes
+pub fn quadratic_cost(votes: &[u32]) -> Result<u64> {
+    votes
+        .iter()
+        .try_fold(0u64, |cost, amount| cost.checked_add((*amount as u64) * (*amount as u64)))
+        .ok_or_else(|| error!(GovError::ArithmeticOverflow))
+}
+
+/// Add a validated ballot to the per-candidate vote counts
+///
+/// Ranked ballots add Borda points under a Borda method; otherwise they
+/// count toward their first preference here and the full ranking is kept
+/// for tabulation.
+/// Each vote counts `weight` times, except on quadratic and cumulative
+/// ballots whose amounts already come out of a weight-scaled budget.
+pub fn apply_ballot(
+    method: &VotingMethod,
+    vote_counts: &mut [u64],
+    ballot: &Ballot,
+    weight: u64,
+) -> Result<()> {
+    for_each_vote(method, vote_counts.len(), ballot, weight, |idx, votes| add_votes(vote_counts, idx, votes))
+}
+
+/// Take a previously applied ballot back out of the vote counts (revoting)
+pub fn remove_ballot(
+    method: &VotingMethod,
+    vote_counts: &mut [u64],
+    ballot: &Ballot,
+    weight: u64,
+) -> Result<()> {
+    for_each_vote(method, vote_counts.len(), ballot, weight, |idx, votes| sub_votes(vote_counts, idx, votes))
+}
+
+/// Count a validated score ballot toward each scored candidate's ballot count
+pub fn apply_score_ballots(
+    score_ballots: &mut [u64],
+    scores: &[Option<u8>],
+    weight: u64,
+) -> Result<()> {
+    for (idx, score) in scores.iter().enumerate() {
+        if score.is_some() {
+            add_votes(score_ballots, idx, weight)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Call `count` with (candidate index, votes) for everything a ballot adds
+fn for_each_vote(
+    method: &VotingMethod,
+    candidate_count: usize,
+    ballot: &Ballot,
+    weight: u64,
+    mut count: impl FnMut(usize, u64) -> Result<()>,
+) -> Result<()> {
+    let mut count_weighted = |idx: usize, votes: u64| -> Result<()> {
+        count(idx, votes.checked_mul(weight).ok_or(GovError::ArithmeticOverflow)?)
+    };
+
+    match ballot {
+        Ballot::Single { choice } => {
+            count_weighted(*choice as usize, 1)?;
+        }
+        Ballot::Multiple { choices } => {
+            for choice in choices {
+                count_weighted(*choice as usize, 1)?;
+            }
+        }
+        Ballot::Approval { approved } => {
+            for idx in approved_candidates(*approved, candidate_count) {
+                count_weighted(idx, 1)?;
+            }
+        }
+        Ballot::Ranked { ranking } => match method {
+            VotingMethod::Borda { scheme } => {
+                for (position, candidate) in ranking.iter().enumerate() {
+                    count_weighted(*candidate as usize, scheme.points(position, candidate_count))?;
+                }
+            }
+            _ => count_weighted(ranking[0] as usize, 1)?,
+        },
+        Ballot::Quadratic { votes } => {
+            for (idx, amount) in votes.iter().enumerate() {
+                count(idx, *amount as u64)?;
+            }
+        }
+        Ballot::Proposal { option } => {
+            count_weighted(option.index(), 1)?;
+        }
+        Ballot::Cumulative { allocations } => {
+            for (idx, amount) in allocations.iter().enumerate() {
+                count(idx, *amount)?;
+            }
+        }
+        Ballot::Score { scores } => {
+            for (idx, score) in scores.iter().enumerate() {
+                if let Some(score) = score {
+                    count_weighted(idx, *score as u64)?;
+                }
+            }
+        }
+        Ballot::Abstain | Ballot::Blank => {}
+    }
+
+    Ok(())
+}
+
+/// Votes a single-seat winner must exceed to pass, in the units of vote_counts
+///
+/// Half of what the winner could have received: one vote per unit of weight,
+/// the top Borda points per unit of weight, or the whole cumulative allowance.
+/// A quadratic voter's most votes on one candidate is the square root of
+/// their own credits, so quadratic winners need half of the votes bought.
+pub fn majority_threshold(method: &VotingMethod, seats: u8, vote_counts: &[u64], total_weight: u64) -> u64 {
+    let available = match method {
+        VotingMethod::Quadratic { .. } => vote_counts.iter().fold(0u64, |sum, votes| sum.saturating_add(*votes)),
+        VotingMethod::Cumulative => method.credit_budget(seats, total_weight).unwrap_or(0),
+        _ => total_weight.saturating_mul(method.top_points(vote_counts.len())),
+    };
+
+    available / 2
+}
+
+/// Basis points in 100%
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Outcome of a proposal election
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProposalOutcome {
+    /// Enough registered voters voted (abstentions included)
+    pub quorum_reached: bool,
+    /// NoWithVeto share exceeded the veto threshold
+    pub vetoed: bool,
+    /// Quorum reached, not vetoed and Yes above the pass threshold
+    pub passed: bool,
+}
+
+impl ProposalOutcome {
+    /// Option the outcome is reported as, if any
+    pub fn decisive_option(&self) -> Option<ProposalOption> {
+        if self.passed {
+            Some(ProposalOption::Yes)
+        } else if self.vetoed {
+            Some(ProposalOption::NoWithVeto)
+        } else {
+            None
+        }
+    }
+}
+
+/// Evaluate a proposal's vote counts
+///
+/// Abstain counts toward quorum but not toward the pass ratio. A veto share
+/// above the veto threshold fails the proposal regardless of Yes.
+/// `abstentions` is the weight of abstain and blank ballots, which only count
+/// toward quorum; quorum is measured against the election's eligible weight.
+pub fn tally_proposal(
+    quorum_bps: u16,
+    pass_threshold_bps: u16,
+    veto_threshold_bps: u16,
+    vote_counts: &[u64],
+    abstentions: u64,
+    eligible_weight: u64,
+) -> ProposalOutcome {
+    let count = |option: ProposalOption| vote_counts.get(option.index()).copied().unwrap_or(0) as u128;
+
+    let yes = count(ProposalOption::Yes);
+    let no = count(ProposalOption::No);
+    let abstain = count(ProposalOption::Abstain);
+    let veto = count(ProposalOption::NoWithVeto);
+
+    let participating = yes + no + abstain + veto;
+    let deciding = yes + no + veto;
+    let denominator = BPS_DENOMINATOR as u128;
+
+    let turnout = participating + abstentions as u128;
+
+    let quorum_reached = turnout > 0
+        && turnout * denominator >= (quorum_bps as u128) * (eligible_weight as u128);
+
+    let vetoed = veto_threshold_bps > 0
+        && veto * denominator > (veto_threshold_bps as u128) * participating;
+
+    let passed = quorum_reached
+        && !vetoed
+        && deciding > 0
+        && yes * denominator > (pass_threshold_bps as u128) * deciding;
+
+    ProposalOutcome {
+        quorum_reached,
+        vetoed,
+        passed,
+    }
+}
+
+/// Projects funded by a participatory budget
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BudgetSelection {
+    /// Funded candidates, in selection order
+    pub winners: Vec<u8>,
+    /// Sum of the funded candidates' costs
+    pub spent: u64,
+}
+
+/// Greedy budget selection
+///
+/// Candidates are considered by votes (most first, ties to the earlier
+/// candidate); each is funded if its cost still fits in the remaining
+/// budget, otherwise it is skipped. Candidates without votes are never funded.
+pub fn select_within_budget(
+    vote_counts: &[u64],
+    costs: &[u64],
+    budget_cap: u64,
+) -> BudgetSelection {
+    let mut ranked: Vec<u8> = (0..vote_counts.len() as u8)
+        .filter(|idx| vote_counts[*idx as usize] > 0)
+        .collect();
+
+    ranked.sort_by(|a, b| vote_counts[*b as usize].cmp(&vote_counts[*a as usize]).then(a.cmp(b)));
+
+    let mut winners = Vec::new();
+    let mut spent = 0u64;
+
+    for candidate in ranked {
+        let cost = costs[candidate as usize];
+        if let Some(total) = spent.checked_add(cost).filter(|total| *total <= budget_cap) {
+            winners.push(candidate);
+            spent = total;
+        }
+    }
+
+    BudgetSelection {
+        winners,
+        spent,
+    }
+}
+
+/// Winners of a top-k count
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TopCandidates {
+    /// The `seats` candidates with the most votes, best first
+    pub winners: Vec<u8>,
+    /// Candidates sharing the vote count at the cutoff when that tie crosses
+    /// it; the earlier candidates among them were seated
+    pub tied_at_cutoff: Vec<u8>,
+}
+
+/// The `seats` candidates with the most votes, best first
+///
+/// Candidates without votes cannot win. Ties go to the earlier candidate;
+/// a tie across the last seat is reported in `tied_at_cutoff`.
+pub fn top_candidates(vote_counts: &[u64], seats: u8) -> TopCandidates {
+    let mut ranked: Vec<u8> = (0..vote_counts.len() as u8)
+        .filter(|idx| vote_counts[*idx as usize] > 0)
+        .collect();
+
+    ranked.sort_by(|a, b| vote_counts[*b as usize].cmp(&vote_counts[*a as usize]).then(a.cmp(b)));
+
+    let seats = seats as usize;
+    let tied_at_cutoff = if ranked.len() > seats && seats > 0 {
+        let cutoff = vote_counts[ranked[seats - 1] as usize];
+        if vote_counts[ranked[seats] as usize] == cutoff {
+            ranked.iter().copied().filter(|idx| vote_counts[*idx as usize] == cutoff).collect()
+        } else {
+            Vec::new()
+        }
+    } else {
+        Vec::new()
+    };
+
+    ranked.truncate(seats);
+
+    TopCandidates {
+        winners: ranked,
+        tied_at_cutoff,
+    }
+}
+
+/// Winner of a score election under the given rule
+///
+/// Mean comparison is exact (cross-multiplied); candidates nobody scored
+/// cannot win. Ties go to the earlier candidate.
+pub fn score_winner(
+    rule: ScoreRule,
+    sums: &[u64],
+    ballots: &[u64],
+) -> Option<u8> {
+    let mut winner: Option<usize> = None;
+
+    for idx in 0..sums.len() {
+        if ballots[idx] == 0 {
+            continue;
+        }
+
+        let better = match winner {
+            None => true,
+            Some(best) => match rule {
+                ScoreRule::HighestSum => sums[idx] > sums[best],
+                ScoreRule::HighestMean => {
+                    (sums[idx] as u128) * (ballots[best] as u128) > (sums[best] as u128) * (ballots[idx] as u128)
+                }
+            },
+        };
+
+        if better {
+            winner = Some(idx);
+        }
+    }
+
+    winner.map(|idx| idx as u8)
+}
+
+/// Indices of the candidates set in an approval bitmask
+pub fn approved_candidates(approved: u16, candidate_count: usize) -> impl Iterator<Item = usize> {
+    (0..candidate_count).filter(move |idx| approved & (1 << idx) != 0)
+}
+
+fn add_votes(vote_counts: &mut [u64], idx: usize, votes: u64) -> Result<()> {
+    let count = vote_counts.get_mut(idx).ok_or(GovError::InvalidChoice)?;
+    *count = count.checked_add(votes).ok_or(GovError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+fn sub_votes(vote_counts: &mut [u64], idx: usize, votes: u64) -> Result<()> {
+    let count = vote_counts.get_mut(idx).ok_or(GovError::InvalidChoice)?;
+    *count = count.checked_sub(votes).ok_or(GovError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plurality_ballot() {
+        let method = VotingMethod::Plurality;
+        let mut counts = vec![0u64; 3];
+
+        let ballot = Ballot::Single { choice: 2 };
+        validate_ballot(&method, 3, 1, &ballot).unwrap();
+        apply_ballot(&method, &mut counts, &ballot, 1).unwrap();
+        assert_eq!(counts, vec![0, 0, 1]);
+
+        assert!(validate_ballot(&method, 3, 1, &Ballot::Single { choice: 3 }).is_err());
+        assert!(validate_ballot(&method, 3, 1, &Ballot::Approval { approved: 0b1 }).is_err());
+    }
+
+    #[test]
+    fn test_approval_ballot() {
+        let method = VotingMethod::Approval { max_approvals: 2 };
+        let mut counts = vec![0u64; 4];
+
+        let ballot = Ballot::Approval { approved: 0b1010 };
+        validate_ballot(&method, 4, 1, &ballot).unwrap();
+        apply_ballot(&method, &mut counts, &ballot, 1).unwrap();
+        assert_eq!(counts, vec![0, 1, 0, 1]);
+
+        // Empty ballot, candidate out of range, over the cap
+        assert!(validate_ballot(&method, 4, 1, &Ballot::Approval { approved: 0 }).is_err());
+        assert!(validate_ballot(&method, 4, 1, &Ballot::Approval { approved: 0b10000 }).is_err());
+        assert!(validate_ballot(&method, 4, 1, &Ballot::Approval { approved: 0b0111 }).is_err());
+
+        // No cap
+        let uncapped = VotingMethod::Approval { max_approvals: 0 };
+        validate_ballot(&uncapped, 4, 1, &Ballot::Approval { approved: 0b1111 }).unwrap();
+    }
+
+    #[test]
+    fn test_ranked_ballot() {
+        let method = VotingMethod::RankedChoice;
+        let mut counts = vec![0u64; 3];
+
+        let ballot = Ballot::Ranked { ranking: vec![2, 0] };
+        validate_ballot(&method, 3, 1, &ballot).unwrap();
+        apply_ballot(&method, &mut counts, &ballot, 1).unwrap();
+        assert_eq!(counts, vec![0, 0, 1]);
+
+        // Empty, duplicate, out of range
+        assert!(validate_ballot(&method, 3, 1, &Ballot::Ranked { ranking: vec![] }).is_err());
+        assert!(validate_ballot(&method, 3, 1, &Ballot::Ranked { ranking: vec![1, 1] }).is_err());
+        assert!(validate_ballot(&method, 3, 1, &Ballot::Ranked { ranking: vec![3] }).is_err());
+    }
+
+    #[test]
+    fn test_quadratic_ballot() {
+        let method = VotingMethod::Quadratic { credits: 10, scale_by_weight: false };
+        let mut counts = vec![0u64; 3];
+
+        // 3² + 1² = 10 credits
+        let ballot = Ballot::Quadratic { votes: vec![3, 0, 1] };
+        validate_ballot(&method, 3, 1, &ballot).unwrap();
+        check_credit_budget(&method, 1, &ballot, 1).unwrap();
+        apply_ballot(&method, &mut counts, &ballot, 1).unwrap();
+        assert_eq!(counts, vec![3, 0, 1]);
+
+        // 2² + 2² + 2² = 12 credits, over budget
+        let expensive = Ballot::Quadratic { votes: vec![2, 2, 2] };
+        assert!(check_credit_budget(&method, 1, &expensive, 1).is_err());
+
+        // Budget scaled by weight
+        let weighted = VotingMethod::Quadratic { credits: 10, scale_by_weight: true };
+        check_credit_budget(&weighted, 1, &expensive, 2).unwrap();
+
+        // Empty and too long
+        assert!(validate_ballot(&method, 3, 1, &Ballot::Quadratic { votes: vec![0, 0] }).is_err());
+        assert!(validate_ballot(&method, 3, 1, &Ballot::Quadratic { votes: vec![1, 0, 0, 1] }).is_err());
+    }
+
+    #[test]
+    fn test_score_ballot() {
+        let method = VotingMethod::Score { min_score: 0, max_score: 10, winner_rule: ScoreRule::HighestMean };
+        let mut sums = vec![0u64; 3];
+        let mut ballots = vec![0u64; 3];
+
+        for scores in [vec![Some(9), Some(6), None], vec![Some(3), Some(6), None], vec![None, None, Some(10)]] {
+            let ballot = Ballot::Score { scores: scores.clone() };
+            validate_ballot(&method, 3, 1, &ballot).unwrap();
+            apply_ballot(&method, &mut sums, &ballot, 1).unwrap();
+            apply_score_ballots(&mut ballots, &scores, 1).unwrap();
+        }
+
+        assert_eq!(sums, vec![12, 12, 10]);
+        assert_eq!(ballots, vec![2, 2, 1]);
+
+        // C has the highest mean (10), A and B tie on sum (A is listed first)
+        assert_eq!(score_winner(ScoreRule::HighestMean, &sums, &ballots), Some(2));
+        assert_eq!(score_winner(ScoreRule::HighestSum, &sums, &ballots), Some(0));
+        assert_eq!(score_winner(ScoreRule::HighestMean, &[0, 0], &[0, 0]), None);
+
+        // Out of range, nothing scored
+        assert!(validate_ballot(&method, 3, 1, &Ballot::Score { scores: vec![Some(11)] }).is_err());
+        assert!(validate_ballot(&method, 3, 1, &Ballot::Score { scores: vec![None, None] }).is_err());
+    }
+
+    #[test]
+    fn test_borda_ballot() {
+        let standard = VotingMethod::Borda { scheme: BordaScheme::Standard };
+        let mut counts = vec![0u64; 4];
+
+        // 3, 2, 1 points; the unranked candidate gets 0
+        let ballot = Ballot::Ranked { ranking: vec![2, 0, 3] };
+        validate_ballot(&standard, 4, 1, &ballot).unwrap();
+        apply_ballot(&standard, &mut counts, &ballot, 1).unwrap();
+        assert_eq!(counts, vec![2, 0, 3, 1]);
+        assert_eq!(standard.top_points(4), 3);
+
+        let dowdall = VotingMethod::Borda { scheme: BordaScheme::Dowdall };
+        let mut counts = vec![0u64; 4];
+
+        // 1, 1/2, 1/3 in units of 1/2520
+        apply_ballot(&dowdall, &mut counts, &ballot, 1).unwrap();
+        assert_eq!(counts, vec![1260, 0, 2520, 840]);
+    }
+
+    #[test]
+    fn test_majority_threshold() {
+        // One vote per unit of weight: 3 of 5 weight passes, 2 doesn't
+        let plurality = VotingMethod::Plurality;
+        assert_eq!(majority_threshold(&plurality, 1, &[3, 2], 5), 2);
+
+        let approval = VotingMethod::Approval { max_approvals: 0 };
+        assert_eq!(majority_threshold(&approval, 1, &[4, 4, 1], 5), 2);
+
+        // Top Borda points per unit of weight
+        let borda = VotingMethod::Borda { scheme: BordaScheme::Standard };
+        assert_eq!(majority_threshold(&borda, 1, &[6, 3, 0, 0], 2), 3);
+
+        let dowdall = VotingMethod::Borda { scheme: BordaScheme::Dowdall };
+        assert_eq!(majority_threshold(&dowdall, 1, &[2520, 1260, 840], 1), 1260);
+
+        // Quadratic voters with 100 credits buy at most 10 votes each, not 100
+        let quadratic = VotingMethod::Quadratic { credits: 100, scale_by_weight: false };
+        let mut counts = vec![0u64; 2];
+        for ballot in [vec![10, 0], vec![6, 8]] {
+            let ballot = Ballot::Quadratic { votes: ballot };
+            check_credit_budget(&quadratic, 1, &ballot, 1).unwrap();
+            apply_ballot(&quadratic, &mut counts, &ballot, 1).unwrap();
+        }
+        assert_eq!(counts, vec![16, 8]);
+        let threshold = majority_threshold(&quadratic, 1, &counts, 2);
+        assert_eq!(threshold, 12);
+        assert!(counts[0] > threshold);
+
+        // An even split has no majority of the votes bought
+        assert_eq!(majority_threshold(&quadratic, 1, &[7, 7], 2), 7);
+
+        // The whole allowance could go to one candidate
+        let cumulative = VotingMethod::Cumulative;
+        let mut counts = vec![0u64; 3];
+        for (allocations, weight) in [(vec![3, 0, 0], 3), (vec![0, 1, 1], 2)] {
+            let ballot = Ballot::Cumulative { allocations };
+            check_credit_budget(&cumulative, 1, &ballot, weight).unwrap();
+            apply_ballot(&cumulative, &mut counts, &ballot, weight).unwrap();
+        }
+        assert_eq!(majority_threshold(&cumulative, 1, &counts, 5), 2);
+        assert!(counts[0] > 2);
+    }
+
+    #[test]
+    fn test_cumulative_ballot() {
+        let method = VotingMethod::Cumulative;
+        let mut counts = vec![0u64; 4];
+
+        // 3 seats, weight 2: 6 votes to distribute
+        let ballot = Ballot::Cumulative { allocations: vec![0, 6] };
+        validate_ballot(&method, 4, 1, &ballot).unwrap();
+        check_credit_budget(&method, 3, &ballot, 2).unwrap();
+        apply_ballot(&method, &mut counts, &ballot, 1).unwrap();
+
+        let ballot = Ballot::Cumulative { allocations: vec![2, 0, 2, 2] };
+        check_credit_budget(&method, 3, &ballot, 2).unwrap();
+        apply_ballot(&method, &mut counts, &ballot, 1).unwrap();
+        assert_eq!(counts, vec![2, 6, 2, 2]);
+
+        let over = Ballot::Cumulative { allocations: vec![4, 3] };
+        assert!(check_credit_budget(&method, 3, &over, 2).is_err());
+
+        // Ties go to the earlier candidate, the tie at the cutoff is reported
+        let top = top_candidates(&counts, 3);
+        assert_eq!(top.winners, vec![1, 0, 2]);
+        assert_eq!(top.tied_at_cutoff, vec![0, 2, 3]);
+        assert_eq!(top_candidates(&[0, 0, 1], 2).winners, vec![2]);
+    }
+
+    #[test]
+    fn test_multi_winner_plurality_ballot() {
+        let method = VotingMethod::Plurality;
+        let mut counts = vec![0u64; 4];
+
+        let ballot = Ballot::Multiple { choices: vec![3, 1] };
+        validate_ballot(&method, 4, 2, &ballot).unwrap();
+        apply_ballot(&method, &mut counts, &ballot, 1).unwrap();
+        assert_eq!(counts, vec![0, 1, 0, 1]);
+
+        // More choices than seats, duplicates, single-seat election
+        assert!(validate_ballot(&method, 4, 2, &Ballot::Multiple { choices: vec![0, 1, 2] }).is_err());
+        assert!(validate_ballot(&method, 4, 2, &Ballot::Multiple { choices: vec![1, 1] }).is_err());
+        assert!(validate_ballot(&method, 4, 1, &Ballot::Multiple { choices: vec![1] }).is_err());
+
+        // No tie at the cutoff
+        let top = top_candidates(&[5, 3, 3, 1], 3);
+        assert_eq!(top.winners, vec![0, 1, 2]);
+        assert!(top.tied_at_cutoff.is_empty());
+    }
+
+    #[test]
+    fn test_proposal_outcome() {
+        let method = VotingMethod::Proposal { quorum_bps: 4000, pass_threshold_bps: 5000, veto_threshold_bps: 3340 };
+        validate_voting_method(&method, 4, 1).unwrap();
+        assert!(validate_voting_method(&method, 3, 1).is_err());
+
+        let mut counts = vec![0u64; 4];
+        let ballot = Ballot::Proposal { option: ProposalOption::Abstain };
+        validate_ballot(&method, 4, 1, &ballot).unwrap();
+        apply_ballot(&method, &mut counts, &ballot, 1).unwrap();
+        assert_eq!(counts, vec![0, 0, 1, 0]);
+
+        // Yes 3, No 2, Abstain 5 of 20 registered: abstentions make quorum, 3/5 passes
+        let outcome = tally_proposal(4000, 5000, 3340, &[3, 2, 5, 0], 0, 20);
+        assert!(outcome.quorum_reached && outcome.passed);
+        assert_eq!(outcome.decisive_option(), Some(ProposalOption::Yes));
+
+        // Same votes without the abstentions miss quorum
+        assert!(!tally_proposal(4000, 5000, 3340, &[3, 2, 0, 0], 0, 20).quorum_reached);
+
+        // Veto above a third of all votes fails despite a Yes majority
+        let outcome = tally_proposal(4000, 5000, 3340, &[6, 0, 0, 4], 0, 20);
+        assert!(outcome.vetoed && !outcome.passed);
+        assert_eq!(outcome.decisive_option(), Some(ProposalOption::NoWithVeto));
+
+        // Exactly at the pass threshold fails
+        assert!(!tally_proposal(0, 5000, 0, &[2, 2, 0, 0], 0, 0).passed);
+
+        // Abstain and blank ballots make quorum but do not change the ratio
+        let outcome = tally_proposal(4000, 5000, 3340, &[3, 2, 0, 0], 3, 20);
+        assert!(outcome.quorum_reached && outcome.passed);
+        assert!(!tally_proposal(4000, 5000, 3340, &[0, 0, 0, 0], 8, 20).passed);
+    }
+
+    #[test]
+    fn test_abstain_and_blank_ballots() {
+        let methods = [
+            VotingMethod::Plurality,
+            VotingMethod::Approval { max_approvals: 0 },
+            VotingMethod::RankedChoice,
+            VotingMethod::Cumulative,
+            VotingMethod::Proposal { quorum_bps: 0, pass_threshold_bps: 5000, veto_threshold_bps: 0 },
+        ];
+
+        for method in methods {
+            for ballot in [Ballot::Abstain, Ballot::Blank] {
+                let mut counts = vec![0u64; 4];
+                validate_ballot(&method, 4, 1, &ballot).unwrap();
+                check_credit_budget(&method, 1, &ballot, 1).unwrap();
+                apply_ballot(&method, &mut counts, &ballot, 1).unwrap();
+                assert_eq!(counts, vec![0; 4]);
+            }
+        }
+
+        // Conviction elections take stakes only
+        assert!(validate_ballot(&VotingMethod::Conviction, 4, 1, &Ballot::Abstain).is_err());
+        assert!(validate_ballot(&VotingMethod::Conviction, 4, 1, &Ballot::Blank).is_err());
+    }
+
+    #[test]
+    fn test_budget_selection() {
+        let points = VotingMethod::Budgeting { points_per_voter: 5 };
+        let mut counts = vec![0u64; 4];
+
+        let ballot = Ballot::Cumulative { allocations: vec![1, 0, 4] };
+        validate_ballot(&points, 4, 1, &ballot).unwrap();
+        check_credit_budget(&points, 1, &ballot, 1).unwrap();
+        apply_ballot(&points, &mut counts, &ballot, 1).unwrap();
+        assert!(check_credit_budget(&points, 1, &Ballot::Cumulative { allocations: vec![6] }, 1).is_err());
+        assert!(validate_ballot(&points, 4, 1, &Ballot::Approval { approved: 0b1 }).is_err());
+
+        let approval = VotingMethod::Budgeting { points_per_voter: 0 };
+        let ballot = Ballot::Approval { approved: 0b1011 };
+        validate_ballot(&approval, 4, 1, &ballot).unwrap();
+        apply_ballot(&approval, &mut counts, &ballot, 1).unwrap();
+        assert_eq!(counts, vec![2, 1, 4, 1]);
+
+        // Budget 100: C (60) funded, A (50) no longer fits, B (30) and D (10) do
+        let selection = select_within_budget(&counts, &[50, 30, 60, 10], 100);
+        assert_eq!(selection.winners, vec![2, 1, 3]);
+        assert_eq!(selection.spent, 100);
+    }
+
+    #[test]
+    fn test_remove_ballot() {
+        let borda = VotingMethod::Borda { scheme: BordaScheme::Standard };
+        let mut counts = vec![0u64; 3];
+
+        let first = Ballot::Ranked { ranking: vec![0, 1] };
+        let second = Ballot::Ranked { ranking: vec![2, 0, 1] };
+        apply_ballot(&borda, &mut counts, &first, 1).unwrap();
+        apply_ballot(&borda, &mut counts, &second, 1).unwrap();
+
+        // Recasting the first ballot as the second moves all of its points
+        remove_ballot(&borda, &mut counts, &first, 1).unwrap();
+        apply_ballot(&borda, &mut counts, &second, 1).unwrap();
+        assert_eq!(counts, vec![2, 0, 4]);
+
+        // Removing a ballot that was never counted underflows
+        let plurality = VotingMethod::Plurality;
+        assert!(remove_ballot(&plurality, &mut [0, 0], &Ballot::Single { choice: 1 }, 1).is_err());
+    }
+
+    #[test]
+    fn test_weighted_ballot() {
+        let plurality = VotingMethod::Plurality;
+        let mut counts = vec![0u64; 3];
+
+        apply_ballot(&plurality, &mut counts, &Ballot::Single { choice: 1 }, 5).unwrap();
+        apply_ballot(&plurality, &mut counts, &Ballot::Single { choice: 2 }, 1).unwrap();
+        assert_eq!(counts, vec![0, 5, 1]);
+
+        // Cumulative allocations already come out of a weight-scaled allowance
+        let cumulative = VotingMethod::Cumulative;
+        let ballot = Ballot::Cumulative { allocations: vec![2, 0, 1] };
+        check_credit_budget(&cumulative, 1, &ballot, 3).unwrap();
+        apply_ballot(&cumulative, &mut counts, &ballot, 3).unwrap();
+        assert_eq!(counts, vec![2, 5, 2]);
+
+        remove_ballot(&plurality, &mut counts, &Ballot::Single { choice: 1 }, 5).unwrap();
+        assert_eq!(counts, vec![2, 0, 2]);
+    }
+
+    #[test]
+    fn test_ballot_outside_vote_counts() {
+        // A ballot validated against more candidates than vote_counts holds errors instead of panicking
+        let plurality = VotingMethod::Plurality;
+        let mut counts = vec![0u64; 2];
+
+        let ballot = Ballot::Single { choice: 3 };
+        validate_ballot(&plurality, 4, 1, &ballot).unwrap();
+        assert!(apply_ballot(&plurality, &mut counts, &ballot, 1).is_err());
+        assert!(remove_ballot(&plurality, &mut counts, &ballot, 1).is_err());
+        assert_eq!(counts, vec![0, 0]);
+
+        let proposal = VotingMethod::Proposal { quorum_bps: 0, pass_threshold_bps: 5000, veto_threshold_bps: 0 };
+        let ballot = Ballot::Proposal { option: ProposalOption::NoWithVeto };
+        assert!(apply_ballot(&proposal, &mut counts, &ballot, 1).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+mod common;
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use common::*;
+use mpl_gov_micro::{accounts, instruction, Ballot, BallotGroup, GovError};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+
+const GROUP_ID: u64 = 7;
+
+fn ballot_group_pda(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"ballot_group", authority.as_ref(), &GROUP_ID.to_le_bytes()], &mpl_gov_micro::ID).0
+}
+
+/// Clone of `source` voting from `start`
+async fn clone_election(context: &mut ProgramTestContext, source: &TestElection, clone_id: u64, start: u64, reuse_voter_registry: bool) -> Pubkey {
+    send(context, &[clone_election_ix(source, clone_id, start, reuse_voter_registry)], &[&source.authority]).await.unwrap();
+    clone_pda(&source.authority.pubkey(), clone_id)
+}
+
+/// Close `source`'s registration and return a start slot for its clones
+async fn end_registration(context: &mut ProgramTestContext, source: &TestElection) -> u64 {
+    warp_to(context, source.end + 1).await;
+    current_slot(context).await + START_DELAY
+}
+
+fn create_ballot_group_ix(authority: &Pubkey, elections: &[Pubkey]) -> Instruction {
+    let mut metas = accounts::CreateBallotGroup {
+        ballot_group: ballot_group_pda(authority),
+        authority: *authority,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    metas.extend(elections.iter().map(|election| AccountMeta::new(*election, false)));
+
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: metas,
+        data: instruction::CreateBallotGroup { group_id: GROUP_ID }.data(),
+    }
+}
+
+fn group_ballot_ix(authority: &Pubkey, voter_registry: &Pubkey, voter: &Pubkey, elections: &[Pubkey], choices: &[u8]) -> Instruction {
+    let mut metas = accounts::CastGroupBallot {
+        ballot_group: ballot_group_pda(authority),
+        voter_registration: Some(registration_pda(voter_registry, voter)),
+        voter: *voter,
+        attestation: None,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    metas.extend(elections.iter().map(|election| AccountMeta::new(*election, false)));
+
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: metas,
+        data: instruction::CastGroupBallot {
+            ballots: choices.iter().map(|choice| Ballot::Single { choice: *choice }).collect(),
+            merkle_proof: vec![],
+            leaf_index: None,
+            registered_at: None,
+            voter_weight: None,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn test_group_members_share_a_registry() {
+    let mut context = start().await;
+    let source = create_election(&mut context, 2).await;
+    let authority = source.authority.pubkey();
+    let start = end_registration(&mut context, &source).await;
+    let shared_registry = clone_election(&mut context, &source, 1, start, true).await;
+    let own_registry = clone_election(&mut context, &source, 2, start, false).await;
+
+    let ix = create_ballot_group_ix(&authority, &[shared_registry, own_registry]);
+    let result = send(&mut context, &[ix], &[&source.authority]).await;
+    assert_gov_error(result, GovError::InvalidBallotGroup);
+    assert!(!account_exists(&mut context, ballot_group_pda(&authority)).await);
+
+    let also_shared = clone_election(&mut context, &source, 3, start, true).await;
+    let ix = create_ballot_group_ix(&authority, &[shared_registry, also_shared]);
+    send(&mut context, &[ix], &[&source.authority]).await.unwrap();
+
+    let group: BallotGroup = fetch(&mut context, ballot_group_pda(&authority)).await;
+    assert_eq!(group.elections, vec![shared_registry, also_shared]);
+    assert_eq!(group.voter_registry, source.address);
+}
+
+#[tokio::test]
+async fn test_group_ballot_counts_once_in_every_member() {
+    let mut context = start().await;
+    let source = create_election(&mut context, 2).await;
+    let authority = source.authority.pubkey();
+    let voter = register_voter(&mut context, &source.address).await;
+    let other = register_voter(&mut context, &source.address).await;
+
+    let start = end_registration(&mut context, &source).await;
+    let first = clone_election(&mut context, &source, 1, start, true).await;
+    let second = clone_election(&mut context, &source, 2, start, true).await;
+    let elections = [first, second];
+    send(&mut context, &[create_ballot_group_ix(&authority, &elections)], &[&source.authority]).await.unwrap();
+
+    warp_to(&mut context, start).await;
+
+    // Members only take votes through the group
+    let vote = cast_vote_ix(&first, &source.address, &voter.pubkey(), Ballot::Single { choice: 0 });
+    let result = send(&mut context, &[vote], &[&voter]).await;
+    assert_gov_error(result, GovError::ElectionInBallotGroup);
+
+    // Ballots follow the group's election order
+    let reversed = group_ballot_ix(&authority, &source.address, &voter.pubkey(), &[second, first], &[0, 1]);
+    let result = send(&mut context, &[reversed], &[&voter]).await;
+    assert_gov_error(result, GovError::InvalidBallotGroup);
+
+    let ballot = group_ballot_ix(&authority, &source.address, &voter.pubkey(), &elections, &[0, 1]);
+    send(&mut context, &[ballot], &[&voter]).await.unwrap();
+
+    // One group-level nullifier covers every member
+    let again = group_ballot_ix(&authority, &source.address, &voter.pubkey(), &elections, &[1, 0]);
+    let result = send(&mut context, &[again], &[&voter]).await;
+    assert_gov_error(result, GovError::AlreadyVoted);
+
+    let ballot = group_ballot_ix(&authority, &source.address, &other.pubkey(), &elections, &[0, 0]);
+    send(&mut context, &[ballot], &[&other]).await.unwrap();
+
+    assert_eq!(fetch_election(&mut context, first).await.vote_counts, vec![2, 0]);
+    assert_eq!(fetch_election(&mut context, second).await.vote_counts, vec![1, 1]);
+
+    let group: BallotGroup = fetch(&mut context, ballot_group_pda(&authority)).await;
+    assert_eq!(group.used_nullifiers.len(), 2);
+}
+
+#[tokio::test]
+async fn test_full_group_grows_for_more_voters() {
+    let mut context = start().await;
+    let source = create_election(&mut context, 2).await;
+    let authority = source.authority.pubkey();
+    let voter_count = BallotGroup::INITIAL_NULLIFIERS + 5;
+    let voters = register_voters(&mut context, &source.address, voter_count).await;
+
+    let start = end_registration(&mut context, &source).await;
+    let first = clone_election(&mut context, &source, 1, start, true).await;
+    let second = clone_election(&mut context, &source, 2, start, true).await;
+    let elections = [first, second];
+    send(&mut context, &[create_ballot_group_ix(&authority, &elections)], &[&source.authority]).await.unwrap();
+
+    warp_to(&mut context, start).await;
+    for batch in voters.chunks(4) {
+        let ballots: Vec<_> = batch
+            .iter()
+            .map(|voter| group_ballot_ix(&authority, &source.address, &voter.pubkey(), &elections, &[0, 1]))
+            .collect();
+        let signers: Vec<&Keypair> = batch.iter().collect();
+        send(&mut context, &ballots, &signers).await.unwrap();
+    }
+
+    let group: BallotGroup = fetch(&mut context, ballot_group_pda(&authority)).await;
+    assert_eq!(group.used_nullifiers.len(), voter_count);
+    assert_eq!(fetch_election(&mut context, first).await.vote_counts, vec![voter_count as u64, 0]);
+
+    let len = context.banks_client.get_account(ballot_group_pda(&authority)).await.unwrap().unwrap().data.len();
+    assert_eq!(len, BallotGroup::space(BallotGroup::INITIAL_NULLIFIERS + BallotGroup::NULLIFIER_GROWTH));
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use common::*;
+use mpl_gov_micro::{accounts, instruction, Ballot, DelegationScope, GovError, VoteInput};
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+
+fn batch_vote_ix(election: &Pubkey, voter: &Pubkey, choice: u8) -> Instruction {
+    let mut metas = accounts::CastBatchVotes {
+        voter: *voter,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    metas.push(AccountMeta::new(*election, false));
+    metas.push(AccountMeta::new_readonly(registration_pda(election, voter), false));
+    metas.push(AccountMeta::new(nullifier_set_pda(election), false));
+
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: metas,
+        data: instruction::CastBatchVotes {
+            votes: vec![VoteInput {
+                election: *election,
+                ballot: Ballot::Single { choice },
+                merkle_proof: vec![],
+                rationale: None,
+            }],
+        }
+        .data(),
+    }
+}
+
+fn delegation_pda(scope: &DelegationScope, delegator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"delegation", scope.key().as_ref(), delegator.as_ref()], &mpl_gov_micro::ID).0
+}
+
+#[tokio::test]
+async fn test_batch_uses_the_voter_nullifier() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+    let first = register_voter(&mut context, &election.address).await;
+    let voter = register_voter(&mut context, &election.address).await;
+
+    warp_to(&mut context, election.start).await;
+
+    // The first cast_vote creates the nullifier set
+    let vote = cast_vote_ix(&election.address, &election.address, &first.pubkey(), Ballot::Single { choice: 0 });
+    send(&mut context, &[vote], &[&first]).await.unwrap();
+
+    send(&mut context, &[batch_vote_ix(&election.address, &voter.pubkey(), 1)], &[&voter]).await.unwrap();
+
+    let result = send(&mut context, &[batch_vote_ix(&election.address, &voter.pubkey(), 0)], &[&voter]).await;
+    assert_gov_error(result, GovError::AlreadyVoted);
+
+    let vote = cast_vote_ix(&election.address, &election.address, &voter.pubkey(), Ballot::Single { choice: 0 });
+    let result = send(&mut context, &[vote], &[&voter]).await;
+    assert_gov_error(result, GovError::AlreadyVoted);
+
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![1, 1]);
+}
+
+#[tokio::test]
+async fn test_batch_cannot_recount_a_delegator() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+    let delegator = register_voter(&mut context, &election.address).await;
+    let delegate = register_voter(&mut context, &election.address).await;
+
+    let scope = DelegationScope::Election(election.address);
+    let delegation = delegation_pda(&scope, &delegator.pubkey());
+    let delegate_ix = Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::DelegateVote {
+            election: election.address,
+            delegation,
+            voter_registration: Some(registration_pda(&election.address, &delegator.pubkey())),
+            delegator: delegator.pubkey(),
+            attestation: None,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::DelegateVote {
+            scope,
+            delegate: delegate.pubkey(),
+            merkle_proof: vec![],
+            leaf_index: None,
+            registered_at: None,
+            voter_weight: None,
+        }
+        .data(),
+    };
+    send(&mut context, &[delegate_ix], &[&delegator]).await.unwrap();
+
+    warp_to(&mut context, election.start).await;
+
+    let mut vote = cast_vote_ix(&election.address, &election.address, &delegate.pubkey(), Ballot::Single { choice: 0 });
+    vote.accounts.push(AccountMeta::new_readonly(delegation, false));
+    send(&mut context, &[vote], &[&delegate]).await.unwrap();
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![2, 0]);
+
+    // The delegator's weight was counted with the delegate's ballot
+    let result = send(&mut context, &[batch_vote_ix(&election.address, &delegator.pubkey(), 1)], &[&delegator]).await;
+    assert_gov_error(result, GovError::AlreadyVoted);
+
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![2, 0]);
+}
@@ -0,0 +1,236 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::incinerator;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use common::*;
+use mpl_gov_micro::{
+    accounts, instruction, Ballot, Challenge, ChallengeKind, ElectionResult, ElectionStatus, GovError,
+};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+
+/// Slots after end_time during which challenges can be posted
+const CHALLENGE_PERIOD: u64 = 20;
+
+const BOND: u64 = 50_000_000;
+
+fn challenge_pda(election: &Pubkey, challenger: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"challenge", election.as_ref(), challenger.as_ref()], &mpl_gov_micro::ID).0
+}
+
+fn configure_ix(election: &TestElection, bond: u64, arbiter: Option<Pubkey>) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::ConfigureChallengeWindow {
+            election: election.address,
+            authority: election.authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::ConfigureChallengeWindow {
+            challenge_period: CHALLENGE_PERIOD as i64,
+            challenge_bond: bond,
+            arbiter,
+        }
+        .data(),
+    }
+}
+
+fn post_ix(election: &Pubkey, challenger: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::PostChallenge {
+            election: *election,
+            challenge: challenge_pda(election, challenger),
+            challenger: *challenger,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::PostChallenge {
+            kind: ChallengeKind::TallyMismatch,
+            evidence_hash: [7; 32],
+        }
+        .data(),
+    }
+}
+
+fn resolve_ix(election: &TestElection, challenger: &Pubkey, resolver: &Pubkey, upheld: bool) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::ResolveChallenge {
+            election: election.address,
+            challenge: challenge_pda(&election.address, challenger),
+            resolver: *resolver,
+            challenger: *challenger,
+            incinerator: incinerator::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::ResolveChallenge { upheld }.data(),
+    }
+}
+
+async fn lamports(context: &mut ProgramTestContext, address: Pubkey) -> u64 {
+    context.banks_client.get_balance(address).await.unwrap()
+}
+
+/// Election with a challenge window and an arbiter, and one vote for candidate 0
+async fn challenged_election(context: &mut ProgramTestContext) -> (TestElection, Keypair) {
+    let arbiter = funded_keypair(context).await;
+    let election = election_with_window(context, Some(arbiter.pubkey())).await;
+    (election, arbiter)
+}
+
+async fn election_with_window(context: &mut ProgramTestContext, arbiter: Option<Pubkey>) -> TestElection {
+    let election = create_election(context, 2).await;
+    send(context, &[configure_ix(&election, BOND, arbiter)], &[&election.authority]).await.unwrap();
+
+    let voter = register_voter(context, &election.address).await;
+    warp_to(context, election.start).await;
+    let vote = cast_vote_ix(&election.address, &election.address, &voter.pubkey(), Ballot::Single { choice: 0 });
+    send(context, &[vote], &[&voter]).await.unwrap();
+
+    election
+}
+
+#[tokio::test]
+async fn test_challenges_only_during_the_window() {
+    let mut context = start().await;
+    let (election, _arbiter) = challenged_election(&mut context).await;
+    let early = funded_keypair(&mut context).await;
+    let challenger = funded_keypair(&mut context).await;
+    let late = funded_keypair(&mut context).await;
+
+    let result = send(&mut context, &[post_ix(&election.address, &early.pubkey())], &[&early]).await;
+    assert_gov_error(result, GovError::ChallengeWindowClosed);
+
+    warp_to(&mut context, election.end + 1).await;
+    let before = lamports(&mut context, challenger.pubkey()).await;
+    send(&mut context, &[post_ix(&election.address, &challenger.pubkey())], &[&challenger]).await.unwrap();
+
+    let challenge: Challenge = fetch(&mut context, challenge_pda(&election.address, &challenger.pubkey())).await;
+    assert_eq!(challenge.challenger, challenger.pubkey());
+    assert_eq!(challenge.bond, BOND);
+    assert!(before - lamports(&mut context, challenger.pubkey()).await >= BOND);
+    assert_eq!(fetch_election(&mut context, election.address).await.open_challenges, 1);
+
+    warp_to(&mut context, election.end + CHALLENGE_PERIOD + 1).await;
+    let result = send(&mut context, &[post_ix(&election.address, &late.pubkey())], &[&late]).await;
+    assert_gov_error(result, GovError::ChallengeWindowClosed);
+}
+
+#[tokio::test]
+async fn test_tabulation_waits_for_the_final_outcome() {
+    let mut context = start().await;
+    let (election, arbiter) = challenged_election(&mut context).await;
+    let payer = context.payer.pubkey();
+    let challenger = funded_keypair(&mut context).await;
+
+    warp_to(&mut context, election.end + 1).await;
+    let result = send(&mut context, &[tabulate_ix(&payer, &election.address)], &[]).await;
+    assert_gov_error(result, GovError::OutcomeNotFinal);
+
+    send(&mut context, &[post_ix(&election.address, &challenger.pubkey())], &[&challenger]).await.unwrap();
+
+    // The window has passed but the challenge is still open
+    warp_to(&mut context, election.end + CHALLENGE_PERIOD + 1).await;
+    let result = send(&mut context, &[tabulate_ix(&payer, &election.address)], &[]).await;
+    assert_gov_error(result, GovError::OutcomeNotFinal);
+
+    // With an arbiter set the authority can't resolve
+    let authority = election.authority.pubkey();
+    let resolve = resolve_ix(&election, &challenger.pubkey(), &authority, false);
+    let result = send(&mut context, &[resolve], &[&election.authority]).await;
+    assert_gov_error(result, GovError::UnauthorizedResolver);
+
+    let challenge = challenge_pda(&election.address, &challenger.pubkey());
+    let held = lamports(&mut context, challenge).await;
+    let authority_before = lamports(&mut context, authority).await;
+    let challenger_before = lamports(&mut context, challenger.pubkey()).await;
+    let resolve = resolve_ix(&election, &challenger.pubkey(), &arbiter.pubkey(), false);
+    send(&mut context, &[resolve], &[&arbiter]).await.unwrap();
+
+    // The forfeited bond is burned and only the rent goes back to the challenger
+    assert!(held > BOND);
+    assert_eq!(lamports(&mut context, challenger.pubkey()).await - challenger_before, held - BOND);
+    assert_eq!(lamports(&mut context, authority).await, authority_before);
+    assert!(!account_exists(&mut context, challenge).await);
+    assert_eq!(fetch_election(&mut context, election.address).await.open_challenges, 0);
+
+    send(&mut context, &[tabulate_ix(&payer, &election.address)], &[]).await.unwrap();
+    let result: ElectionResult = fetch(&mut context, result_pda(&election.address)).await;
+    assert!(result.complete);
+    assert_eq!(result.winners, vec![0]);
+}
+
+#[tokio::test]
+async fn test_upheld_challenge_cancels_the_election() {
+    let mut context = start().await;
+    let (election, arbiter) = challenged_election(&mut context).await;
+    let payer = context.payer.pubkey();
+    let challenger = funded_keypair(&mut context).await;
+
+    warp_to(&mut context, election.end + 1).await;
+    send(&mut context, &[post_ix(&election.address, &challenger.pubkey())], &[&challenger]).await.unwrap();
+
+    let challenge = challenge_pda(&election.address, &challenger.pubkey());
+    let held = lamports(&mut context, challenge).await;
+    let before = lamports(&mut context, challenger.pubkey()).await;
+    let resolve = resolve_ix(&election, &challenger.pubkey(), &arbiter.pubkey(), true);
+    send(&mut context, &[resolve], &[&arbiter]).await.unwrap();
+    assert_eq!(lamports(&mut context, challenger.pubkey()).await - before, held);
+    assert!(!account_exists(&mut context, challenge).await);
+    assert_eq!(fetch_election(&mut context, election.address).await.status, ElectionStatus::Cancelled);
+
+    warp_to(&mut context, election.end + CHALLENGE_PERIOD + 1).await;
+    let result = send(&mut context, &[tabulate_ix(&payer, &election.address)], &[]).await;
+    assert_gov_error(result, GovError::ElectionCancelled);
+}
+
+#[tokio::test]
+async fn test_authority_resolves_without_an_arbiter() {
+    let mut context = start().await;
+    let election = election_with_window(&mut context, None).await;
+    let challenger = funded_keypair(&mut context).await;
+    let outsider = funded_keypair(&mut context).await;
+
+    warp_to(&mut context, election.end + 1).await;
+    send(&mut context, &[post_ix(&election.address, &challenger.pubkey())], &[&challenger]).await.unwrap();
+
+    let resolve = resolve_ix(&election, &challenger.pubkey(), &outsider.pubkey(), true);
+    let result = send(&mut context, &[resolve], &[&outsider]).await;
+    assert_gov_error(result, GovError::UnauthorizedResolver);
+
+    let authority = election.authority.pubkey();
+    let challenge = challenge_pda(&election.address, &challenger.pubkey());
+    let held = lamports(&mut context, challenge).await;
+    let authority_before = lamports(&mut context, authority).await;
+    let challenger_before = lamports(&mut context, challenger.pubkey()).await;
+    let resolve = resolve_ix(&election, &challenger.pubkey(), &authority, false);
+    send(&mut context, &[resolve], &[&election.authority]).await.unwrap();
+
+    // Rejecting doesn't pay the authority, only the transaction fee leaves its balance
+    assert!(lamports(&mut context, authority).await <= authority_before);
+    assert_eq!(lamports(&mut context, challenger.pubkey()).await - challenger_before, held - BOND);
+    assert!(!account_exists(&mut context, challenge).await);
+    assert_eq!(fetch_election(&mut context, election.address).await.open_challenges, 0);
+}
+
+#[tokio::test]
+async fn test_window_requires_a_bond() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+    let arbiter = Pubkey::new_unique();
+
+    // Free challenges could hold the outcome open forever
+    let result = send(&mut context, &[configure_ix(&election, 0, Some(arbiter))], &[&election.authority]).await;
+    assert_gov_error(result, GovError::ChallengeBondRequired);
+
+    send(&mut context, &[configure_ix(&election, BOND, None)], &[&election.authority]).await.unwrap();
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!((stored.challenge_bond, stored.arbiter), (BOND, None));
+
+    send(&mut context, &[configure_ix(&election, BOND, Some(arbiter))], &[&election.authority]).await.unwrap();
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!((stored.challenge_bond, stored.arbiter), (BOND, Some(arbiter)));
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use common::*;
+use mpl_gov_micro::{accounts, instruction, Ballot, GovError, TimeBase};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::Signer;
+
+/// Election using compression without a merkle tree (registrations tracked by root only)
+async fn create_compressed_election(context: &mut ProgramTestContext) -> TestElection {
+    let authority = funded_keypair(context).await;
+    let address = election_pda(&authority.pubkey());
+    let start = current_slot(context).await + START_DELAY;
+    let end = start + VOTING_SLOTS;
+
+    let ix = Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::CreateElection {
+            election: address,
+            merkle_tree: None,
+            authority: authority.pubkey(),
+            compression_program: None,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CreateElection {
+            candidates: vec!["Yes".to_string(), "No".to_string()],
+            start_time: start as i64,
+            end_time: end as i64,
+            use_compression: true,
+            max_voters: 0,
+            time_base: TimeBase::Slot,
+        }
+        .data(),
+    };
+    send(context, &[ix], &[&authority]).await.unwrap();
+
+    TestElection { address, authority, start, end }
+}
+
+#[tokio::test]
+async fn test_registry_is_reused_once_the_source_ends() {
+    let mut context = start().await;
+    let source = create_election(&mut context, 2).await;
+    let voter = register_voter(&mut context, &source.address).await;
+
+    // The source still takes registrations, a copy of its totals would go stale
+    let result = send(&mut context, &[clone_election_ix(&source, 1, source.start, true)], &[&source.authority]).await;
+    assert_gov_error(result, GovError::ElectionNotEnded);
+
+    // Registration closes with the election
+    warp_to(&mut context, source.end + 1).await;
+    let late = funded_keypair(&mut context).await;
+    let ix = register_voter_ix(&source.address, &late.pubkey());
+    let result = send(&mut context, &[ix], &[&late]).await;
+    assert_gov_error(result, GovError::ElectionEnded);
+
+    let start = current_slot(&mut context).await + START_DELAY;
+    send(&mut context, &[clone_election_ix(&source, 1, start, true)], &[&source.authority]).await.unwrap();
+    let clone = clone_pda(&source.authority.pubkey(), 1);
+
+    let stored = fetch_election(&mut context, clone).await;
+    let registry = fetch_election(&mut context, source.address).await;
+    assert_eq!(stored.voter_registry, source.address);
+    assert_eq!(stored.voter_merkle_root, registry.voter_merkle_root);
+    assert_eq!((stored.total_registered, stored.total_registered_weight), (1, 1));
+
+    // Voters of a clone register in the source's registry
+    let ix = register_voter_ix(&clone, &late.pubkey());
+    let result = send(&mut context, &[ix], &[&late]).await;
+    assert_gov_error(result, GovError::SharedVoterRegistry);
+
+    // The source's voters vote in the clone with their existing registration
+    warp_to(&mut context, start).await;
+    let vote = cast_vote_ix(&clone, &source.address, &voter.pubkey(), Ballot::Single { choice: 1 });
+    send(&mut context, &[vote], &[&voter]).await.unwrap();
+
+    let stored = fetch_election(&mut context, clone).await;
+    assert_eq!(stored.vote_counts, vec![0, 1]);
+    assert_eq!(stored.total_registered, fetch_election(&mut context, source.address).await.total_registered);
+}
+
+#[tokio::test]
+async fn test_clone_without_reuse_has_its_own_registry() {
+    let mut context = start().await;
+    let source = create_election(&mut context, 2).await;
+    register_voter(&mut context, &source.address).await;
+
+    send(&mut context, &[clone_election_ix(&source, 1, source.start, false)], &[&source.authority]).await.unwrap();
+    let clone = clone_pda(&source.authority.pubkey(), 1);
+
+    let stored = fetch_election(&mut context, clone).await;
+    assert_eq!(stored.voter_registry, clone);
+    assert_eq!((stored.total_registered, stored.voter_merkle_root), (0, [0; 32]));
+
+    // Both elections keep registering on their own
+    register_voter(&mut context, &source.address).await;
+    register_voter(&mut context, &clone).await;
+    assert_eq!(fetch_election(&mut context, source.address).await.total_registered, 2);
+    assert_eq!(fetch_election(&mut context, clone).await.total_registered, 1);
+}
+
+#[tokio::test]
+async fn test_compressed_registry_is_only_cloned_by_reuse() {
+    let mut context = start().await;
+    let source = create_compressed_election(&mut context).await;
+
+    let result = send(&mut context, &[clone_election_ix(&source, 1, source.start, false)], &[&source.authority]).await;
+    assert_gov_error(result, GovError::CompressedRegistryNotCloned);
+
+    warp_to(&mut context, source.end + 1).await;
+    let start = current_slot(&mut context).await + START_DELAY;
+    send(&mut context, &[clone_election_ix(&source, 1, start, true)], &[&source.authority]).await.unwrap();
+
+    let stored = fetch_election(&mut context, clone_pda(&source.authority.pubkey(), 1)).await;
+    assert!(stored.use_compression);
+    assert_eq!(stored.voter_registry, source.address);
+}
@@ -0,0 +1,377 @@
+//! Shared helpers for the program-test suites
+//!
+//! The program runs natively inside solana-program-test. Elections use the
+//! Slot time base so tests move through the voting window by warping slots.
+
+#![allow(dead_code)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{Instruction, InstructionError};
+use anchor_lang::{system_program, AccountDeserialize, InstructionData, ToAccountMetas};
+use mpl_gov_micro::{accounts, instruction, Ballot, DelegationScope, Election, GovError, TimeBase};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+/// Slots between creating an election and its start
+pub const START_DELAY: u64 = 10;
+
+/// Length of the voting window in slots
+pub const VOTING_SLOTS: u64 = 100;
+
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> anchor_lang::solana_program::entrypoint::ProgramResult {
+    // Anchor's entry ties the account infos to the slice's lifetime
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    mpl_gov_micro::entry(program_id, accounts, data)
+}
+
+/// The program under test, for suites that add accounts before starting
+pub fn program_test() -> ProgramTest {
+    ProgramTest::new("mpl_gov_micro", mpl_gov_micro::ID, processor!(process_instruction))
+}
+
+pub async fn start() -> ProgramTestContext {
+    program_test().start_with_context().await
+}
+
+/// Send a transaction paid by the test payer
+pub async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> std::result::Result<(), BanksClientError> {
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+
+    context.banks_client.process_transaction(transaction).await
+}
+
+/// Assert a transaction failed with the given program error
+pub fn assert_gov_error(result: std::result::Result<(), BanksClientError>, error: GovError) {
+    let expected = anchor_lang::error::ERROR_CODE_OFFSET + error as u32;
+
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        ))) => assert_eq!(code, expected, "expected {:?}", error),
+        other => panic!("expected {:?}, got {:?}", error, other),
+    }
+}
+
+/// A funded keypair
+pub async fn funded_keypair(context: &mut ProgramTestContext) -> Keypair {
+    let keypair = Keypair::new();
+    let transfer = anchor_lang::solana_program::system_instruction::transfer(
+        &context.payer.pubkey(),
+        &keypair.pubkey(),
+        1_000_000_000,
+    );
+    send(context, &[transfer], &[]).await.unwrap();
+    keypair
+}
+
+pub async fn current_slot(context: &mut ProgramTestContext) -> u64 {
+    context.banks_client.get_root_slot().await.unwrap()
+}
+
+pub async fn warp_to(context: &mut ProgramTestContext, slot: u64) {
+    context.warp_to_slot(slot).unwrap();
+}
+
+pub async fn fetch<T: AccountDeserialize>(context: &mut ProgramTestContext, address: Pubkey) -> T {
+    let account = context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .expect("account exists");
+    T::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+pub async fn account_exists(context: &mut ProgramTestContext, address: Pubkey) -> bool {
+    context.banks_client.get_account(address).await.unwrap().is_some()
+}
+
+pub fn election_pda(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"election", authority.as_ref()], &mpl_gov_micro::ID).0
+}
+
+pub fn registration_pda(voter_registry: &Pubkey, voter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"voter_registration", voter_registry.as_ref(), voter.as_ref()],
+        &mpl_gov_micro::ID,
+    )
+    .0
+}
+
+pub fn voter_ballot_pda(election: &Pubkey, voter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"voter_ballot", election.as_ref(), voter.as_ref()],
+        &mpl_gov_micro::ID,
+    )
+    .0
+}
+
+pub fn result_pda(election: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"result", election.as_ref()], &mpl_gov_micro::ID).0
+}
+
+pub fn clone_pda(authority: &Pubkey, clone_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"election", authority.as_ref(), &clone_id.to_le_bytes()], &mpl_gov_micro::ID).0
+}
+
+pub fn delegation_pda(scope: &DelegationScope, delegator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"delegation", scope.key().as_ref(), delegator.as_ref()], &mpl_gov_micro::ID).0
+}
+
+pub fn nullifier_set_pda(election: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"nullifiers", election.as_ref()], &mpl_gov_micro::ID).0
+}
+
+/// Election in the Slot time base, starting START_DELAY slots from now
+pub struct TestElection {
+    pub address: Pubkey,
+    pub authority: Keypair,
+    pub start: u64,
+    pub end: u64,
+}
+
+pub async fn create_election(context: &mut ProgramTestContext, candidates: usize) -> TestElection {
+    let authority = funded_keypair(context).await;
+    let address = election_pda(&authority.pubkey());
+    let start = current_slot(context).await + START_DELAY;
+    let end = start + VOTING_SLOTS;
+
+    let ix = Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::CreateElection {
+            election: address,
+            merkle_tree: None,
+            authority: authority.pubkey(),
+            compression_program: None,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CreateElection {
+            candidates: (0..candidates).map(|i| format!("Candidate {}", i)).collect(),
+            start_time: start as i64,
+            end_time: end as i64,
+            use_compression: false,
+            max_voters: 0,
+            time_base: TimeBase::Slot,
+        }
+        .data(),
+    };
+    send(context, &[ix], &[&authority]).await.unwrap();
+
+    TestElection { address, authority, start, end }
+}
+
+pub fn register_voter_ix(election: &Pubkey, voter: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::RegisterVoter {
+            election: *election,
+            voter_registration: Some(registration_pda(election, voter)),
+            merkle_tree: None,
+            voter: *voter,
+            attestation: Pubkey::new_unique(),
+            compression_program: None,
+            authority: None,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::RegisterVoter { weight: 1 }.data(),
+    }
+}
+
+/// A funded voter registered in the election
+pub async fn register_voter(context: &mut ProgramTestContext, election: &Pubkey) -> Keypair {
+    let voter = funded_keypair(context).await;
+    let ix = register_voter_ix(election, &voter.pubkey());
+    send(context, &[ix], &[&voter]).await.unwrap();
+    voter
+}
+
+/// `count` funded voters registered in the election, four per transaction
+pub async fn register_voters(context: &mut ProgramTestContext, election: &Pubkey, count: usize) -> Vec<Keypair> {
+    let mut voters = Vec::with_capacity(count);
+    while voters.len() < count {
+        let batch: Vec<Keypair> = (0..4.min(count - voters.len())).map(|_| Keypair::new()).collect();
+        let payer = context.payer.pubkey();
+        let funding: Vec<_> = batch
+            .iter()
+            .map(|voter| anchor_lang::solana_program::system_instruction::transfer(&payer, &voter.pubkey(), 100_000_000))
+            .collect();
+        send(context, &funding, &[]).await.unwrap();
+
+        let register: Vec<_> = batch.iter().map(|voter| register_voter_ix(election, &voter.pubkey())).collect();
+        let signers: Vec<&Keypair> = batch.iter().collect();
+        send(context, &register, &signers).await.unwrap();
+        voters.extend(batch);
+    }
+    voters
+}
+
+/// A funded voter registered by the authority with a non-default weight
+pub async fn register_weighted_voter(context: &mut ProgramTestContext, election: &TestElection, weight: u64) -> Keypair {
+    let voter = funded_keypair(context).await;
+    let mut ix = register_voter_ix(&election.address, &voter.pubkey());
+    ix.accounts = accounts::RegisterVoter {
+        election: election.address,
+        voter_registration: Some(registration_pda(&election.address, &voter.pubkey())),
+        merkle_tree: None,
+        voter: voter.pubkey(),
+        attestation: Pubkey::new_unique(),
+        compression_program: None,
+        authority: Some(election.authority.pubkey()),
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    ix.data = instruction::RegisterVoter { weight }.data();
+    send(context, &[ix], &[&voter, &election.authority]).await.unwrap();
+    voter
+}
+
+pub fn set_revoting_ix(election: &TestElection) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::SetRevoting {
+            election: election.address,
+            authority: election.authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::SetRevoting { allow_revoting: true }.data(),
+    }
+}
+
+/// delegate_vote in legacy mode, with the registration from the election's voter registry
+pub fn delegate_vote_ix(election: &Pubkey, voter_registry: &Pubkey, delegator: &Pubkey, delegate: &Pubkey) -> Instruction {
+    let scope = DelegationScope::Election(*election);
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::DelegateVote {
+            election: *election,
+            delegation: delegation_pda(&scope, delegator),
+            voter_registration: Some(registration_pda(voter_registry, delegator)),
+            delegator: *delegator,
+            attestation: None,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::DelegateVote {
+            scope,
+            delegate: *delegate,
+            merkle_proof: vec![],
+            leaf_index: None,
+            registered_at: None,
+            voter_weight: None,
+        }
+        .data(),
+    }
+}
+
+/// cast_vote in legacy mode, with the registration from the election's voter registry
+pub fn cast_vote_ix(election: &Pubkey, voter_registry: &Pubkey, voter: &Pubkey, ballot: Ballot) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::CastVote {
+            election: *election,
+            voter_registration: Some(registration_pda(voter_registry, voter)),
+            nullifier_set: nullifier_set_pda(election),
+            ranked_ballots: None,
+            pairwise_matrix: None,
+            voter_ballot: None,
+            token_voter_weight: None,
+            voter: *voter,
+            attestation: None,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CastVote {
+            ballot,
+            merkle_proof: vec![],
+            leaf_index: None,
+            registered_at: None,
+            voter_weight: None,
+            rationale: None,
+        }
+        .data(),
+    }
+}
+
+/// cast_vote keeping the voter's ballot record, for elections that allow revoting
+pub fn revote_ix(election: &Pubkey, voter: &Pubkey, ballot: Ballot) -> Instruction {
+    let mut ix = cast_vote_ix(election, election, voter, ballot);
+    ix.accounts = accounts::CastVote {
+        election: *election,
+        voter_registration: Some(registration_pda(election, voter)),
+        nullifier_set: nullifier_set_pda(election),
+        ranked_ballots: None,
+        pairwise_matrix: None,
+        voter_ballot: Some(voter_ballot_pda(election, voter)),
+        token_voter_weight: None,
+        voter: *voter,
+        attestation: None,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    ix
+}
+
+/// clone_election of `source` voting from `start` for VOTING_SLOTS slots
+pub fn clone_election_ix(source: &TestElection, clone_id: u64, start: u64, reuse_voter_registry: bool) -> Instruction {
+    let authority = source.authority.pubkey();
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::CloneElection {
+            source_election: source.address,
+            election: clone_pda(&authority, clone_id),
+            authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CloneElection {
+            clone_id,
+            start_time: start as i64,
+            end_time: (start + VOTING_SLOTS) as i64,
+            reuse_voter_registry,
+        }
+        .data(),
+    }
+}
+
+pub async fn fetch_election(context: &mut ProgramTestContext, address: Pubkey) -> Election {
+    fetch::<Election>(context, address).await
+}
+
+/// tabulate_election without ranked ballots, pairwise matrix or conviction state
+pub fn tabulate_ix(payer: &Pubkey, election: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::TabulateElection {
+            election: *election,
+            ranked_ballots: None,
+            pairwise_matrix: None,
+            conviction_state: None,
+            result: result_pda(election),
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::TabulateElection { max_rounds: 10 }.data(),
+    }
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use common::*;
+use mpl_gov_micro::utils::conviction::conviction_threshold;
+use mpl_gov_micro::{accounts, instruction, ConvictionState, VotingMethod};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+
+const DECAY_BPS: u16 = 9_000;
+const MAX_RATIO_BPS: u16 = 9_000;
+const WEIGHT_BPS: u16 = 10;
+
+/// Slots per conviction period
+const PERIOD: u64 = 10;
+
+const TOTAL_FUNDS: u64 = 100;
+
+fn conviction_pda(election: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"conviction", election.as_ref()], &mpl_gov_micro::ID).0
+}
+
+fn stake_pda(election: &Pubkey, voter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"conviction_stake", election.as_ref(), voter.as_ref()], &mpl_gov_micro::ID).0
+}
+
+/// Conviction election whose proposals request `amounts` out of TOTAL_FUNDS
+async fn conviction_election(context: &mut ProgramTestContext, amounts: Vec<u64>) -> TestElection {
+    let election = create_election(context, amounts.len()).await;
+    let authority = election.authority.pubkey();
+    let unused = |seed: &[u8]| Pubkey::find_program_address(&[seed, election.address.as_ref()], &mpl_gov_micro::ID).0;
+
+    let set_method = Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::SetVotingMethod {
+            election: election.address,
+            ranked_ballots: unused(b"ranked_ballots"),
+            pairwise_matrix: unused(b"pairwise_matrix"),
+            authority,
+        }
+        .to_account_metas(None),
+        data: instruction::SetVotingMethod { voting_method: VotingMethod::Conviction, seats: 1 }.data(),
+    };
+    let set_amounts = Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::SetCandidateAmounts { election: election.address, authority }.to_account_metas(None),
+        data: instruction::SetCandidateAmounts { amounts, budget_cap: 0 }.data(),
+    };
+    let init = Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::InitConviction {
+            election: election.address,
+            conviction_state: conviction_pda(&election.address),
+            authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::InitConviction {
+            decay_bps: DECAY_BPS,
+            max_ratio_bps: MAX_RATIO_BPS,
+            weight_bps: WEIGHT_BPS,
+            period: PERIOD as i64,
+            total_funds: TOTAL_FUNDS,
+        }
+        .data(),
+    };
+    send(context, &[set_method, set_amounts, init], &[&election.authority]).await.unwrap();
+
+    election
+}
+
+async fn stake(context: &mut ProgramTestContext, election: &Pubkey, voter: &Keypair, candidate: u8) {
+    let ix = Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::StakeConviction {
+            election: *election,
+            conviction_state: conviction_pda(election),
+            stake: stake_pda(election, &voter.pubkey()),
+            voter_registration: Some(registration_pda(election, &voter.pubkey())),
+            voter: voter.pubkey(),
+            attestation: None,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::StakeConviction {
+            candidate: Some(candidate),
+            merkle_proof: Vec::new(),
+            leaf_index: None,
+            registered_at: None,
+            voter_weight: None,
+        }
+        .data(),
+    };
+    send(context, &[ix], &[voter]).await.unwrap();
+}
+
+async fn update(context: &mut ProgramTestContext, election: &Pubkey) -> ConvictionState {
+    let ix = Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::UpdateConviction {
+            election: *election,
+            conviction_state: conviction_pda(election),
+        }
+        .to_account_metas(None),
+        data: instruction::UpdateConviction {}.data(),
+    };
+    send(context, &[ix], &[]).await.unwrap();
+    fetch(context, conviction_pda(election)).await
+}
+
+#[tokio::test]
+async fn test_passed_proposals_commit_their_funds() {
+    let mut context = start().await;
+    let election = conviction_election(&mut context, vec![60, 60, 30]).await;
+
+    let voters = register_voters(&mut context, &election.address, 3).await;
+    warp_to(&mut context, election.start).await;
+    for (candidate, voter) in voters.iter().enumerate() {
+        stake(&mut context, &election.address, voter, candidate as u8).await;
+    }
+
+    // Every proposal clears its threshold against the total funds after one period,
+    // but once proposal 0 commits 60 the second 60 no longer fits
+    warp_to(&mut context, election.start + PERIOD).await;
+    let state = update(&mut context, &election.address).await;
+    let eligible_weight = voters.len() as u64;
+    for (requested, conviction) in [60, 60, 30].into_iter().zip(&state.conviction) {
+        let threshold = conviction_threshold(requested, TOTAL_FUNDS, eligible_weight, MAX_RATIO_BPS, WEIGHT_BPS, DECAY_BPS);
+        assert!(*conviction >= threshold.unwrap());
+    }
+    assert_eq!(state.passed, vec![0]);
+    assert_eq!(state.committed_funds, 60);
+
+    // Proposal 2 asks for 30 of the remaining 40, a higher threshold that takes another period
+    let remaining = TOTAL_FUNDS - state.committed_funds;
+    let threshold = conviction_threshold(30, remaining, eligible_weight, MAX_RATIO_BPS, WEIGHT_BPS, DECAY_BPS).unwrap();
+    assert!(state.conviction[2] < threshold);
+
+    warp_to(&mut context, election.start + 2 * PERIOD).await;
+    let state = update(&mut context, &election.address).await;
+    assert!(state.conviction[2] >= threshold);
+    assert_eq!(state.passed, vec![0, 2]);
+    assert_eq!(state.committed_funds, 90);
+
+    // The skipped proposal keeps building conviction but never fits
+    warp_to(&mut context, election.end).await;
+    let state = update(&mut context, &election.address).await;
+    assert_eq!(state.passed, vec![0, 2]);
+    assert_eq!(state.committed_funds, 90);
+}
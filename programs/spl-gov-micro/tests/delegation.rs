@@ -0,0 +1,250 @@
+mod common;
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use common::*;
+use mpl_gov_micro::{accounts, instruction, Ballot, Delegation, DelegationScope, GovError};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+
+/// cast_vote passing the delegations of `delegators`, in order, as remaining accounts
+fn delegated_vote_ix(election: &Pubkey, voter: &Pubkey, choice: u8, delegators: &[&Keypair]) -> Instruction {
+    let scope = DelegationScope::Election(*election);
+    let mut ix = cast_vote_ix(election, election, voter, Ballot::Single { choice });
+    ix.accounts.extend(
+        delegators
+            .iter()
+            .map(|delegator| AccountMeta::new_readonly(delegation_pda(&scope, &delegator.pubkey()), false)),
+    );
+    ix
+}
+
+fn revoke_ix(election: &Pubkey, delegator: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::RevokeDelegation {
+            delegation: delegation_pda(&DelegationScope::Election(*election), delegator),
+            delegator: *delegator,
+        }
+        .to_account_metas(None),
+        data: instruction::RevokeDelegation {}.data(),
+    }
+}
+
+/// delegate_vote for every election of `authority`, proving registration in `election`'s registry
+fn authority_delegate_ix(election: &Pubkey, voter_registry: &Pubkey, authority: &Pubkey, delegator: &Pubkey, delegate: &Pubkey) -> Instruction {
+    let scope = DelegationScope::Authority(*authority);
+    let mut ix = delegate_vote_ix(election, voter_registry, delegator, delegate);
+    ix.accounts[1].pubkey = delegation_pda(&scope, delegator);
+    ix.data = instruction::DelegateVote {
+        scope,
+        delegate: *delegate,
+        merkle_proof: vec![],
+        leaf_index: None,
+        registered_at: None,
+        voter_weight: None,
+    }
+    .data();
+    ix
+}
+
+async fn delegate(context: &mut ProgramTestContext, election: &Pubkey, delegator: &Keypair, delegate: &Pubkey) {
+    let ix = delegate_vote_ix(election, election, &delegator.pubkey(), delegate);
+    send(context, &[ix], &[delegator]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_delegate_votes_with_the_chained_weight() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+
+    let voter = register_voter(&mut context, &election.address).await;
+    let direct = register_weighted_voter(&mut context, &election, 2).await;
+    let chained = register_weighted_voter(&mut context, &election, 3).await;
+
+    // chained -> direct -> voter
+    delegate(&mut context, &election.address, &direct, &voter.pubkey()).await;
+    delegate(&mut context, &election.address, &chained, &direct.pubkey()).await;
+
+    let stored: Delegation = fetch(&mut context, delegation_pda(&DelegationScope::Election(election.address), &chained.pubkey())).await;
+    assert_eq!((stored.delegate, stored.weight), (direct.pubkey(), 3));
+
+    warp_to(&mut context, election.start).await;
+
+    // Links have to follow the link they delegate to
+    let vote = delegated_vote_ix(&election.address, &voter.pubkey(), 1, &[&chained, &direct]);
+    let result = send(&mut context, &[vote], &[&voter]).await;
+    assert_gov_error(result, GovError::InvalidDelegationChain);
+
+    let vote = delegated_vote_ix(&election.address, &voter.pubkey(), 1, &[&direct, &chained]);
+    send(&mut context, &[vote], &[&voter]).await.unwrap();
+
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!(stored.vote_counts, vec![0, 6]);
+    assert_eq!((stored.total_votes, stored.total_weight), (1, 6));
+
+    // Counted delegators can no longer vote themselves
+    let vote = cast_vote_ix(&election.address, &election.address, &chained.pubkey(), Ballot::Single { choice: 0 });
+    let result = send(&mut context, &[vote], &[&chained]).await;
+    assert_gov_error(result, GovError::AlreadyVoted);
+}
+
+#[tokio::test]
+async fn test_delegator_who_voted_is_not_counted() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+
+    let voter = register_voter(&mut context, &election.address).await;
+    let delegator = register_weighted_voter(&mut context, &election, 2).await;
+    delegate(&mut context, &election.address, &delegator, &voter.pubkey()).await;
+
+    warp_to(&mut context, election.start).await;
+    let vote = cast_vote_ix(&election.address, &election.address, &delegator.pubkey(), Ballot::Single { choice: 0 });
+    send(&mut context, &[vote], &[&delegator]).await.unwrap();
+
+    let vote = delegated_vote_ix(&election.address, &voter.pubkey(), 1, &[&delegator]);
+    let result = send(&mut context, &[vote], &[&voter]).await;
+    assert_gov_error(result, GovError::DelegatorAlreadyVoted);
+
+    // The delegate still votes with their own weight
+    let vote = delegated_vote_ix(&election.address, &voter.pubkey(), 1, &[]);
+    send(&mut context, &[vote], &[&voter]).await.unwrap();
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![2, 1]);
+}
+
+#[tokio::test]
+async fn test_revoked_delegator_votes_themselves() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+
+    let voter = register_voter(&mut context, &election.address).await;
+    let delegator = register_weighted_voter(&mut context, &election, 2).await;
+    delegate(&mut context, &election.address, &delegator, &voter.pubkey()).await;
+
+    send(&mut context, &[revoke_ix(&election.address, &delegator.pubkey())], &[&delegator]).await.unwrap();
+    let scope = DelegationScope::Election(election.address);
+    assert!(!account_exists(&mut context, delegation_pda(&scope, &delegator.pubkey())).await);
+
+    warp_to(&mut context, election.start).await;
+
+    // The closed delegation can't be counted for the delegate
+    let vote = delegated_vote_ix(&election.address, &voter.pubkey(), 1, &[&delegator]);
+    assert!(send(&mut context, &[vote], &[&voter]).await.is_err());
+
+    let vote = cast_vote_ix(&election.address, &election.address, &delegator.pubkey(), Ballot::Single { choice: 0 });
+    send(&mut context, &[vote], &[&delegator]).await.unwrap();
+
+    let vote = delegated_vote_ix(&election.address, &voter.pubkey(), 1, &[]);
+    send(&mut context, &[vote], &[&voter]).await.unwrap();
+
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!(stored.vote_counts, vec![2, 1]);
+    assert_eq!((stored.total_votes, stored.total_weight), (2, 3));
+}
+
+#[tokio::test]
+async fn test_delegation_chains_stop_at_max_depth() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+
+    let voter = register_voter(&mut context, &election.address).await;
+    let mut chain: Vec<Keypair> = Vec::new();
+    for _ in 0..=Delegation::MAX_DEPTH {
+        let delegator = register_voter(&mut context, &election.address).await;
+        let delegate_key = chain.last().map(|link| link.pubkey()).unwrap_or(voter.pubkey());
+        delegate(&mut context, &election.address, &delegator, &delegate_key).await;
+        chain.push(delegator);
+    }
+    let links: Vec<&Keypair> = chain.iter().collect();
+
+    warp_to(&mut context, election.start).await;
+
+    // One link past MAX_DEPTH
+    let vote = delegated_vote_ix(&election.address, &voter.pubkey(), 0, &links);
+    let result = send(&mut context, &[vote], &[&voter]).await;
+    assert_gov_error(result, GovError::DelegationTooDeep);
+
+    let max_depth = Delegation::MAX_DEPTH as usize;
+    let vote = delegated_vote_ix(&election.address, &voter.pubkey(), 0, &links[..max_depth]);
+    send(&mut context, &[vote], &[&voter]).await.unwrap();
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![1 + max_depth as u64, 0]);
+}
+
+#[tokio::test]
+async fn test_sharded_elections_take_no_delegations() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+    let authority = election.authority.pubkey();
+
+    let voter = register_voter(&mut context, &election.address).await;
+    let delegator = register_voter(&mut context, &election.address).await;
+
+    let configure = Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::ConfigureTallyShards { election: election.address, authority }.to_account_metas(None),
+        data: instruction::ConfigureTallyShards { shard_count: 2 }.data(),
+    };
+    send(&mut context, &[configure], &[&election.authority]).await.unwrap();
+
+    let ix = delegate_vote_ix(&election.address, &election.address, &delegator.pubkey(), &voter.pubkey());
+    let result = send(&mut context, &[ix], &[&delegator]).await;
+    assert_gov_error(result, GovError::ElectionIsSharded);
+
+    // A delegation for all of the authority's elections can't be counted here either
+    let ix = authority_delegate_ix(&election.address, &election.address, &authority, &delegator.pubkey(), &voter.pubkey());
+    send(&mut context, &[ix], &[&delegator]).await.unwrap();
+
+    warp_to(&mut context, election.start).await;
+    let mut vote = cast_vote_ix(&election.address, &election.address, &voter.pubkey(), Ballot::Single { choice: 0 });
+    let scope = DelegationScope::Authority(authority);
+    vote.accounts.push(AccountMeta::new_readonly(delegation_pda(&scope, &delegator.pubkey()), false));
+    let result = send(&mut context, &[vote], &[&voter]).await;
+    assert_gov_error(result, GovError::ElectionIsSharded);
+}
+
+#[tokio::test]
+async fn test_grouped_elections_take_no_delegations() {
+    let mut context = start().await;
+    let source = create_election(&mut context, 2).await;
+    let authority = source.authority.pubkey();
+
+    let voter = register_voter(&mut context, &source.address).await;
+    let delegator = register_voter(&mut context, &source.address).await;
+
+    // Two clones sharing the source's registry, grouped on one ballot
+    warp_to(&mut context, source.end + 1).await;
+    let start = current_slot(&mut context).await + START_DELAY;
+    let clones = [clone_election_ix(&source, 1, start, true), clone_election_ix(&source, 2, start, true)];
+    send(&mut context, &clones, &[&source.authority]).await.unwrap();
+    let elections = [clone_pda(&authority, 1), clone_pda(&authority, 2)];
+
+    let group_id = 7u64;
+    let mut metas = accounts::CreateBallotGroup {
+        ballot_group: Pubkey::find_program_address(&[b"ballot_group", authority.as_ref(), &group_id.to_le_bytes()], &mpl_gov_micro::ID).0,
+        authority,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    metas.extend(elections.iter().map(|election| AccountMeta::new(*election, false)));
+    let create_group = Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: metas,
+        data: instruction::CreateBallotGroup { group_id }.data(),
+    };
+    send(&mut context, &[create_group], &[&source.authority]).await.unwrap();
+
+    let ix = delegate_vote_ix(&elections[0], &source.address, &delegator.pubkey(), &voter.pubkey());
+    let result = send(&mut context, &[ix], &[&delegator]).await;
+    assert_gov_error(result, GovError::ElectionInBallotGroup);
+
+    let ix = authority_delegate_ix(&elections[0], &source.address, &authority, &delegator.pubkey(), &voter.pubkey());
+    send(&mut context, &[ix], &[&delegator]).await.unwrap();
+
+    warp_to(&mut context, start).await;
+    let mut vote = cast_vote_ix(&elections[0], &source.address, &voter.pubkey(), Ballot::Single { choice: 0 });
+    let scope = DelegationScope::Authority(authority);
+    vote.accounts.push(AccountMeta::new_readonly(delegation_pda(&scope, &delegator.pubkey()), false));
+    let result = send(&mut context, &[vote], &[&voter]).await;
+    assert_gov_error(result, GovError::ElectionInBallotGroup);
+}
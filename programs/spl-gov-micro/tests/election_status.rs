@@ -0,0 +1,132 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::bpf_loader;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::*;
+use mpl_gov_micro::{accounts, instruction, Ballot, ElectionStatus, GovError};
+use solana_program_test::tokio;
+use solana_sdk::account::Account;
+use solana_sdk::signature::{Keypair, Signer};
+
+fn tick_ix(election: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::Tick { election: *election }.to_account_metas(None),
+        data: instruction::Tick {}.data(),
+    }
+}
+
+fn private_tally_ix(election: &TestElection, privacy_layer_program: &Pubkey, tally: Vec<u64>) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::ReceivePrivateTally {
+            election: election.address,
+            privacy_layer_program: *privacy_layer_program,
+            authority: election.authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::ReceivePrivateTally { tally, proof: vec![] }.data(),
+    }
+}
+
+#[tokio::test]
+async fn test_tick_persists_status_transitions() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+
+    send(&mut context, &[tick_ix(&election.address)], &[]).await.unwrap();
+    assert_eq!(fetch_election(&mut context, election.address).await.status, ElectionStatus::Pending);
+
+    // The stored status lags behind the clock until something persists it
+    warp_to(&mut context, election.start).await;
+    assert_eq!(fetch_election(&mut context, election.address).await.status, ElectionStatus::Pending);
+    send(&mut context, &[tick_ix(&election.address)], &[]).await.unwrap();
+    assert_eq!(fetch_election(&mut context, election.address).await.status, ElectionStatus::Active);
+
+    // end_time itself is still part of the voting window
+    warp_to(&mut context, election.end).await;
+    send(&mut context, &[tick_ix(&election.address)], &[]).await.unwrap();
+    assert_eq!(fetch_election(&mut context, election.address).await.status, ElectionStatus::Active);
+
+    warp_to(&mut context, election.end + 1).await;
+    send(&mut context, &[tick_ix(&election.address)], &[]).await.unwrap();
+    assert_eq!(fetch_election(&mut context, election.address).await.status, ElectionStatus::Ended);
+
+    // Ended is final
+    warp_to(&mut context, election.end + 10).await;
+    send(&mut context, &[tick_ix(&election.address)], &[]).await.unwrap();
+    assert_eq!(fetch_election(&mut context, election.address).await.status, ElectionStatus::Ended);
+}
+
+#[tokio::test]
+async fn test_votes_follow_the_effective_status() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+    let voter = register_voter(&mut context, &election.address).await;
+    let late_voter = register_voter(&mut context, &election.address).await;
+
+    let vote = cast_vote_ix(&election.address, &election.address, &voter.pubkey(), Ballot::Single { choice: 0 });
+
+    let result = send(&mut context, std::slice::from_ref(&vote), &[&voter]).await;
+    assert_gov_error(result, GovError::ElectionNotStarted);
+
+    // No tick needed: the vote itself sees the election as active and persists it
+    warp_to(&mut context, election.start).await;
+    send(&mut context, &[vote], &[&voter]).await.unwrap();
+
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!(stored.vote_counts, vec![1, 0]);
+    assert_eq!(stored.status, ElectionStatus::Active);
+
+    // Stored status is still Active, but the window has closed
+    warp_to(&mut context, election.end + 1).await;
+    let late_vote = cast_vote_ix(&election.address, &election.address, &late_voter.pubkey(), Ballot::Single { choice: 1 });
+    let result = send(&mut context, &[late_vote], &[&late_voter]).await;
+    assert_gov_error(result, GovError::ElectionEnded);
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![1, 0]);
+}
+
+#[tokio::test]
+async fn test_private_tally_waits_for_the_end() {
+    let privacy_layer = Keypair::new();
+    let mut program_test = program_test();
+    program_test.add_account(
+        privacy_layer.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: bpf_loader::ID,
+            executable: true,
+            rent_epoch: 0,
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+    let election = create_election(&mut context, 2).await;
+
+    let enable = Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::EnablePrivateVoting {
+            election: election.address,
+            privacy_layer_program: privacy_layer.pubkey(),
+            authority: election.authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::EnablePrivateVoting {}.data(),
+    };
+    send(&mut context, &[enable], &[&election.authority]).await.unwrap();
+
+    warp_to(&mut context, election.start).await;
+    let tally = private_tally_ix(&election, &privacy_layer.pubkey(), vec![3, 4]);
+    let result = send(&mut context, std::slice::from_ref(&tally), &[&privacy_layer, &election.authority]).await;
+    assert_gov_error(result, GovError::ElectionNotEnded);
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![0, 0]);
+
+    warp_to(&mut context, election.end + 1).await;
+    send(&mut context, &[tally], &[&privacy_layer, &election.authority]).await.unwrap();
+
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!(stored.vote_counts, vec![3, 4]);
+    assert_eq!(stored.total_votes, 7);
+}
@@ -0,0 +1,237 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use common::*;
+use mpl_gov_micro::{accounts, instruction, Ballot, ElectionResult, RankedBallots, VotingMethod};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+
+fn ranked_ballots_pda(election: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"ranked_ballots", election.as_ref()], &mpl_gov_micro::ID).0
+}
+
+fn pairwise_matrix_pda(election: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"pairwise_matrix", election.as_ref()], &mpl_gov_micro::ID).0
+}
+
+/// Ranked election with its ranked ballot store
+async fn ranked_election(
+    context: &mut ProgramTestContext,
+    candidates: usize,
+    voting_method: VotingMethod,
+    seats: u8,
+) -> TestElection {
+    let election = create_election(context, candidates).await;
+    let payer = context.payer.pubkey();
+
+    let set_method = Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::SetVotingMethod {
+            election: election.address,
+            ranked_ballots: ranked_ballots_pda(&election.address),
+            pairwise_matrix: pairwise_matrix_pda(&election.address),
+            authority: election.authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::SetVotingMethod { voting_method, seats }.data(),
+    };
+    let init = Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::InitRankedBallots {
+            election: election.address,
+            ranked_ballots: ranked_ballots_pda(&election.address),
+            payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::InitRankedBallots {}.data(),
+    };
+    send(context, &[set_method, init], &[&election.authority]).await.unwrap();
+
+    election
+}
+
+fn ranked_vote_ix(election: &Pubkey, voter: &Pubkey, ranking: Vec<u8>) -> Instruction {
+    let mut ix = cast_vote_ix(election, election, voter, Ballot::Ranked { ranking });
+    ix.accounts = accounts::CastVote {
+        election: *election,
+        voter_registration: Some(registration_pda(election, voter)),
+        nullifier_set: nullifier_set_pda(election),
+        ranked_ballots: Some(ranked_ballots_pda(election)),
+        pairwise_matrix: None,
+        voter_ballot: None,
+        token_voter_weight: None,
+        voter: *voter,
+        attestation: None,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    ix
+}
+
+async fn account_len(context: &mut ProgramTestContext, address: Pubkey) -> usize {
+    let account = context.banks_client.get_account(address).await.unwrap().unwrap();
+    account.data.len()
+}
+
+/// `count` distinct two-candidate rankings over `candidates` candidates
+fn distinct_rankings(candidates: u8, count: usize) -> Vec<Vec<u8>> {
+    (0..candidates)
+        .flat_map(|first| (0..candidates).filter(move |second| *second != first).map(move |second| vec![first, second]))
+        .take(count)
+        .collect()
+}
+
+fn ranked_tabulate_ix(payer: &Pubkey, election: &Pubkey) -> Instruction {
+    let mut tabulate = tabulate_ix(payer, election);
+    tabulate.accounts[1].pubkey = ranked_ballots_pda(election);
+    tabulate.accounts[1].is_writable = true;
+    tabulate
+}
+
+#[tokio::test]
+async fn test_full_ranked_ballots_grow_for_new_rankings() {
+    let mut context = start().await;
+    let election = ranked_election(&mut context, 10, VotingMethod::RankedChoice, 1).await;
+
+    // One more distinct ranking than a new account has room for
+    let rankings = distinct_rankings(10, RankedBallots::INITIAL_RANKINGS + 1);
+
+    let mut voters: Vec<Keypair> = Vec::new();
+    for _ in 0..=rankings.len() {
+        voters.push(register_voter(&mut context, &election.address).await);
+    }
+
+    warp_to(&mut context, election.start).await;
+
+    let (last, filling) = rankings.split_last().unwrap();
+    for (voter, ranking) in voters.iter().zip(filling) {
+        let vote = ranked_vote_ix(&election.address, &voter.pubkey(), ranking.clone());
+        send(&mut context, &[vote], &[voter]).await.unwrap();
+    }
+    assert_eq!(account_len(&mut context, ranked_ballots_pda(&election.address)).await, RankedBallots::SIZE);
+
+    // The store is full, the next new ranking grows it instead of being refused
+    let voter = &voters[filling.len()];
+    let vote = ranked_vote_ix(&election.address, &voter.pubkey(), last.clone());
+    send(&mut context, &[vote], &[voter]).await.unwrap();
+
+    let grown = RankedBallots::space(RankedBallots::INITIAL_RANKINGS + RankedBallots::GROWTH_RANKINGS);
+    assert_eq!(account_len(&mut context, ranked_ballots_pda(&election.address)).await, grown);
+
+    // A ranking already recorded only adds to its count
+    let voter = voters.last().unwrap();
+    let vote = ranked_vote_ix(&election.address, &voter.pubkey(), last.clone());
+    send(&mut context, &[vote], &[voter]).await.unwrap();
+    assert_eq!(account_len(&mut context, ranked_ballots_pda(&election.address)).await, grown);
+
+    let stored: RankedBallots = fetch(&mut context, ranked_ballots_pda(&election.address)).await;
+    assert_eq!(stored.rankings.len(), rankings.len());
+    assert_eq!(stored.rankings.last().unwrap().count, 2);
+
+    // Tabulation reads every ranking
+    warp_to(&mut context, election.end + 1).await;
+    let payer = context.payer.pubkey();
+    let tabulate = ranked_tabulate_ix(&payer, &election.address);
+    for _ in 0..2 {
+        send(&mut context, std::slice::from_ref(&tabulate), &[]).await.unwrap();
+    }
+
+    let result: ElectionResult = fetch(&mut context, result_pda(&election.address)).await;
+    assert!(result.complete);
+    assert_eq!(result.winners.len(), 1);
+
+    // Instant runoff keeps no ranking group values or matrices
+    assert_eq!(account_len(&mut context, result_pda(&election.address)).await, ElectionResult::SIZE);
+}
+
+#[tokio::test]
+async fn test_stv_values_are_kept_with_the_rankings() {
+    let mut context = start().await;
+    let election = ranked_election(&mut context, 10, VotingMethod::SingleTransferableVote, 2).await;
+
+    // Enough rankings that the store's spare room can't hold their values
+    let rankings = distinct_rankings(10, RankedBallots::INITIAL_RANKINGS + RankedBallots::GROWTH_RANKINGS + 1);
+    let voters = register_voters(&mut context, &election.address, rankings.len()).await;
+
+    warp_to(&mut context, election.start).await;
+    for (voter, ranking) in voters.iter().zip(&rankings) {
+        let vote = ranked_vote_ix(&election.address, &voter.pubkey(), ranking.clone());
+        send(&mut context, &[vote], &[voter]).await.unwrap();
+    }
+
+    warp_to(&mut context, election.end + 1).await;
+    let payer = context.payer.pubkey();
+    let tabulate = ranked_tabulate_ix(&payer, &election.address);
+    for _ in 0..2 {
+        send(&mut context, std::slice::from_ref(&tabulate), &[]).await.unwrap();
+    }
+
+    // The result stays the same size, the ranked ballots hold the STV value of every group
+    assert_eq!(account_len(&mut context, result_pda(&election.address)).await, ElectionResult::SIZE);
+    let ranked_len = account_len(&mut context, ranked_ballots_pda(&election.address)).await;
+    assert_eq!(ranked_len, RankedBallots::space_with_weights(rankings.len()));
+
+    let stored: RankedBallots = fetch(&mut context, ranked_ballots_pda(&election.address)).await;
+    assert_eq!(stored.ballot_weights.len(), rankings.len());
+
+    let result: ElectionResult = fetch(&mut context, result_pda(&election.address)).await;
+    assert!(result.complete);
+    assert_eq!(result.winners.len(), 2);
+}
+
+#[tokio::test]
+async fn test_condorcet_result_has_room_for_the_matrices() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 3).await;
+    let payer = context.payer.pubkey();
+
+    let set_method = Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::SetVotingMethod {
+            election: election.address,
+            ranked_ballots: ranked_ballots_pda(&election.address),
+            pairwise_matrix: pairwise_matrix_pda(&election.address),
+            authority: election.authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::SetVotingMethod { voting_method: VotingMethod::Condorcet, seats: 1 }.data(),
+    };
+    let init = Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::InitPairwiseMatrix {
+            election: election.address,
+            pairwise_matrix: pairwise_matrix_pda(&election.address),
+            payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::InitPairwiseMatrix {}.data(),
+    };
+    send(&mut context, &[set_method, init], &[&election.authority]).await.unwrap();
+
+    let voters = register_voters(&mut context, &election.address, 3).await;
+    warp_to(&mut context, election.start).await;
+    for (voter, ranking) in voters.iter().zip([vec![1, 0, 2], vec![1, 2, 0], vec![0, 1, 2]]) {
+        let mut vote = ranked_vote_ix(&election.address, &voter.pubkey(), ranking);
+        vote.accounts[3].pubkey = mpl_gov_micro::ID;
+        vote.accounts[4].pubkey = pairwise_matrix_pda(&election.address);
+        vote.accounts[4].is_writable = true;
+        send(&mut context, &[vote], &[voter]).await.unwrap();
+    }
+
+    warp_to(&mut context, election.end + 1).await;
+    let mut tabulate = tabulate_ix(&payer, &election.address);
+    tabulate.accounts[2].pubkey = pairwise_matrix_pda(&election.address);
+    send(&mut context, &[tabulate], &[]).await.unwrap();
+
+    let len = account_len(&mut context, result_pda(&election.address)).await;
+    assert_eq!(len, ElectionResult::SIZE + ElectionResult::CONDORCET_SIZE);
+
+    let result: ElectionResult = fetch(&mut context, result_pda(&election.address)).await;
+    assert!(result.complete);
+    assert_eq!(result.winners, vec![1]);
+    assert_eq!((result.pairwise.len(), result.strengths.len()), (9, 9));
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use anchor_lang::prelude::AccountMeta;
+use common::*;
+use mpl_gov_micro::{Ballot, DelegationScope, GovError, VoterBallot};
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn test_recast_moves_the_previous_ballot() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 3).await;
+    send(&mut context, &[set_revoting_ix(&election)], &[&election.authority]).await.unwrap();
+
+    let alice = register_weighted_voter(&mut context, &election, 3).await;
+    let bob = register_voter(&mut context, &election.address).await;
+
+    warp_to(&mut context, election.start).await;
+    let vote = revote_ix(&election.address, &alice.pubkey(), Ballot::Single { choice: 0 });
+    send(&mut context, &[vote], &[&alice]).await.unwrap();
+    let vote = revote_ix(&election.address, &bob.pubkey(), Ballot::Single { choice: 1 });
+    send(&mut context, &[vote], &[&bob]).await.unwrap();
+
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!(stored.vote_counts, vec![3, 1, 0]);
+    assert_eq!((stored.total_votes, stored.total_weight), (2, 4));
+
+    // Only the last ballot counts, and a recast is not another vote
+    let recast = revote_ix(&election.address, &alice.pubkey(), Ballot::Single { choice: 2 });
+    send(&mut context, &[recast], &[&alice]).await.unwrap();
+
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!(stored.vote_counts, vec![0, 1, 3]);
+    assert_eq!((stored.total_votes, stored.total_weight), (2, 4));
+
+    // Abstaining moves the weight out of the candidate tallies and the vote totals
+    let recast = revote_ix(&election.address, &alice.pubkey(), Ballot::Abstain);
+    send(&mut context, &[recast], &[&alice]).await.unwrap();
+
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!(stored.vote_counts, vec![0, 1, 0]);
+    assert_eq!((stored.total_votes, stored.total_weight, stored.abstain_votes), (1, 1, 3));
+
+    let recast = revote_ix(&election.address, &alice.pubkey(), Ballot::Single { choice: 1 });
+    send(&mut context, &[recast], &[&alice]).await.unwrap();
+
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!(stored.vote_counts, vec![0, 4, 0]);
+    assert_eq!((stored.total_votes, stored.total_weight, stored.abstain_votes), (2, 4, 0));
+
+    let ballot: VoterBallot = fetch(&mut context, voter_ballot_pda(&election.address, &alice.pubkey())).await;
+    assert_eq!(ballot.ballot, Ballot::Single { choice: 1 });
+    assert_eq!((ballot.weight, ballot.delegated_weight), (3, 0));
+}
+
+#[tokio::test]
+async fn test_recast_requires_revoting_and_the_ballot_record() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+    let voter = register_voter(&mut context, &election.address).await;
+
+    warp_to(&mut context, election.start).await;
+    let vote = cast_vote_ix(&election.address, &election.address, &voter.pubkey(), Ballot::Single { choice: 0 });
+    send(&mut context, &[vote], &[&voter]).await.unwrap();
+
+    // Revoting is off by default
+    let recast = revote_ix(&election.address, &voter.pubkey(), Ballot::Single { choice: 1 });
+    let result = send(&mut context, &[recast], &[&voter]).await;
+    assert_gov_error(result, GovError::AlreadyVoted);
+
+    let other = create_election(&mut context, 2).await;
+    send(&mut context, &[set_revoting_ix(&other)], &[&other.authority]).await.unwrap();
+    let voter = register_voter(&mut context, &other.address).await;
+
+    // With revoting every vote keeps its ballot record to take back out later
+    warp_to(&mut context, other.start).await;
+    let vote = cast_vote_ix(&other.address, &other.address, &voter.pubkey(), Ballot::Single { choice: 0 });
+    let result = send(&mut context, &[vote], &[&voter]).await;
+    assert_gov_error(result, GovError::VoterBallotRequired);
+    assert_eq!(fetch_election(&mut context, other.address).await.vote_counts, vec![0, 0]);
+}
+
+#[tokio::test]
+async fn test_recast_keeps_the_delegated_weight() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+    send(&mut context, &[set_revoting_ix(&election)], &[&election.authority]).await.unwrap();
+
+    let delegate = register_voter(&mut context, &election.address).await;
+    let delegator = register_weighted_voter(&mut context, &election, 2).await;
+
+    let delegate_vote = delegate_vote_ix(&election.address, &election.address, &delegator.pubkey(), &delegate.pubkey());
+    send(&mut context, &[delegate_vote], &[&delegator]).await.unwrap();
+
+    warp_to(&mut context, election.start).await;
+    let scope = DelegationScope::Election(election.address);
+    let mut vote = revote_ix(&election.address, &delegate.pubkey(), Ballot::Single { choice: 0 });
+    vote.accounts.push(AccountMeta::new_readonly(delegation_pda(&scope, &delegator.pubkey()), false));
+    send(&mut context, &[vote], &[&delegate]).await.unwrap();
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![3, 0]);
+
+    // The delegation was used up by the first ballot and moves with the recast
+    let recast = revote_ix(&election.address, &delegate.pubkey(), Ballot::Single { choice: 1 });
+    send(&mut context, &[recast], &[&delegate]).await.unwrap();
+
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!(stored.vote_counts, vec![0, 3]);
+    assert_eq!((stored.total_votes, stored.total_weight), (1, 3));
+
+    let ballot: VoterBallot = fetch(&mut context, voter_ballot_pda(&election.address, &delegate.pubkey())).await;
+    assert_eq!((ballot.weight, ballot.delegated_weight), (3, 2));
+
+    // The counted delegator has no ballot of their own to recast
+    let vote = revote_ix(&election.address, &delegator.pubkey(), Ballot::Single { choice: 0 });
+    let result = send(&mut context, &[vote], &[&delegator]).await;
+    assert_gov_error(result, GovError::AlreadyVoted);
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![0, 3]);
+}
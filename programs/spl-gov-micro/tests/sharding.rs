@@ -0,0 +1,233 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use common::*;
+use mpl_gov_micro::{accounts, instruction, Ballot, GovError, TallyShard, VoteNullifier};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+
+fn tally_shard_pda(election: &Pubkey, shard_index: u8) -> Pubkey {
+    Pubkey::find_program_address(&[b"tally_shard", election.as_ref(), &[shard_index]], &mpl_gov_micro::ID).0
+}
+
+/// Shard a voter's nullifier assigns them to
+fn shard_of(election: &Pubkey, voter: &Pubkey, shard_count: u8) -> u8 {
+    TallyShard::index_for(&VoteNullifier::new(voter, election, 0).nullifier_hash, shard_count)
+}
+
+fn configure_tally_shards_ix(election: &TestElection, shard_count: u8) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::ConfigureTallyShards {
+            election: election.address,
+            authority: election.authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::ConfigureTallyShards { shard_count }.data(),
+    }
+}
+
+fn init_tally_shard_ix(payer: &Pubkey, election: &Pubkey, shard_index: u8) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::InitTallyShard {
+            election: *election,
+            tally_shard: tally_shard_pda(election, shard_index),
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::InitTallyShard { shard_index }.data(),
+    }
+}
+
+fn sharded_vote_ix(election: &Pubkey, voter: &Pubkey, shard_index: u8, choice: u8) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::CastShardedVote {
+            election: *election,
+            tally_shard: tally_shard_pda(election, shard_index),
+            voter_registration: Some(registration_pda(election, voter)),
+            voter: *voter,
+            attestation: None,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CastShardedVote {
+            ballot: Ballot::Single { choice },
+            merkle_proof: vec![],
+            leaf_index: None,
+            registered_at: None,
+            voter_weight: None,
+        }
+        .data(),
+    }
+}
+
+fn merge_ix(election: &Pubkey, shard_index: u8) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::MergeTallyShard {
+            election: *election,
+            tally_shard: tally_shard_pda(election, shard_index),
+        }
+        .to_account_metas(None),
+        data: instruction::MergeTallyShard {}.data(),
+    }
+}
+
+/// Two-candidate election with `shard_count` initialized shards
+async fn sharded_election(context: &mut ProgramTestContext, shard_count: u8) -> TestElection {
+    let election = create_election(context, 2).await;
+    send(context, &[configure_tally_shards_ix(&election, shard_count)], &[&election.authority]).await.unwrap();
+
+    let payer = context.payer.pubkey();
+    let init: Vec<_> = (0..shard_count).map(|index| init_tally_shard_ix(&payer, &election.address, index)).collect();
+    send(context, &init, &[]).await.unwrap();
+
+    election
+}
+
+/// Registered voters until both shards of a two-shard election have one
+async fn voter_per_shard(context: &mut ProgramTestContext, election: &Pubkey) -> [Keypair; 2] {
+    let mut voters: [Option<Keypair>; 2] = [None, None];
+    while voters.iter().any(Option::is_none) {
+        let voter = register_voter(context, election).await;
+        let shard = shard_of(election, &voter.pubkey(), 2) as usize;
+        if voters[shard].is_none() {
+            voters[shard] = Some(voter);
+        }
+    }
+    voters.map(Option::unwrap)
+}
+
+#[tokio::test]
+async fn test_voter_counts_once_across_shards() {
+    let mut context = start().await;
+    let election = sharded_election(&mut context, 2).await;
+    let voter = register_voter(&mut context, &election.address).await;
+    let home = shard_of(&election.address, &voter.pubkey(), 2);
+    let other = 1 - home;
+
+    warp_to(&mut context, election.start).await;
+
+    // Sharded elections don't take votes on the election account
+    let vote = cast_vote_ix(&election.address, &election.address, &voter.pubkey(), Ballot::Single { choice: 0 });
+    let result = send(&mut context, &[vote], &[&voter]).await;
+    assert_gov_error(result, GovError::ElectionIsSharded);
+
+    send(&mut context, &[sharded_vote_ix(&election.address, &voter.pubkey(), home, 0)], &[&voter]).await.unwrap();
+
+    let result = send(&mut context, &[sharded_vote_ix(&election.address, &voter.pubkey(), home, 1)], &[&voter]).await;
+    assert_gov_error(result, GovError::AlreadyVoted);
+
+    // The other shard has no record of the voter but won't take them either
+    let result = send(&mut context, &[sharded_vote_ix(&election.address, &voter.pubkey(), other, 1)], &[&voter]).await;
+    assert_gov_error(result, GovError::InvalidTallyShard);
+
+    let shard: TallyShard = fetch(&mut context, tally_shard_pda(&election.address, home)).await;
+    assert_eq!(shard.vote_counts, vec![1, 0]);
+    assert_eq!(shard.total_votes, 1);
+    let shard: TallyShard = fetch(&mut context, tally_shard_pda(&election.address, other)).await;
+    assert_eq!(shard.vote_counts, vec![0, 0]);
+}
+
+#[tokio::test]
+async fn test_merge_folds_every_shard_once() {
+    let mut context = start().await;
+    let election = sharded_election(&mut context, 2).await;
+    let voters = voter_per_shard(&mut context, &election.address).await;
+    let payer = context.payer.pubkey();
+
+    warp_to(&mut context, election.start).await;
+    for (shard, voter) in voters.iter().enumerate() {
+        let vote = sharded_vote_ix(&election.address, &voter.pubkey(), shard as u8, shard as u8);
+        send(&mut context, &[vote], &[voter]).await.unwrap();
+    }
+
+    let result = send(&mut context, &[merge_ix(&election.address, 0)], &[]).await;
+    assert_gov_error(result, GovError::ElectionNotEnded);
+
+    warp_to(&mut context, election.end + 1).await;
+    send(&mut context, &[merge_ix(&election.address, 0)], &[]).await.unwrap();
+
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!(stored.vote_counts, vec![1, 0]);
+    assert_eq!(stored.shards_merged, 1);
+    assert_eq!(stored.shard_count, 2);
+
+    let result = send(&mut context, &[merge_ix(&election.address, 0)], &[]).await;
+    assert_gov_error(result, GovError::ShardAlreadyMerged);
+
+    // The outcome waits for the remaining shard
+    let result = send(&mut context, &[tabulate_ix(&payer, &election.address)], &[]).await;
+    assert_gov_error(result, GovError::ShardsNotMerged);
+
+    send(&mut context, &[merge_ix(&election.address, 1)], &[]).await.unwrap();
+
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!(stored.vote_counts, vec![1, 1]);
+    assert_eq!(stored.total_votes, 2);
+    assert_eq!(stored.total_weight, 2);
+    assert_eq!(stored.shards_merged, 2);
+    assert!(stored.all_shards_merged());
+
+    send(&mut context, &[tabulate_ix(&payer, &election.address)], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_dropped_shard_is_not_merged() {
+    let mut context = start().await;
+    let election = sharded_election(&mut context, 2).await;
+
+    // Shard 1 was created before the election went down to one shard
+    send(&mut context, &[configure_tally_shards_ix(&election, 1)], &[&election.authority]).await.unwrap();
+
+    let voter = register_voter(&mut context, &election.address).await;
+    warp_to(&mut context, election.start).await;
+    send(&mut context, &[sharded_vote_ix(&election.address, &voter.pubkey(), 0, 1)], &[&voter]).await.unwrap();
+
+    warp_to(&mut context, election.end + 1).await;
+    let result = send(&mut context, &[merge_ix(&election.address, 1)], &[]).await;
+    assert_gov_error(result, GovError::InvalidTallyShard);
+    assert_eq!(fetch_election(&mut context, election.address).await.shards_merged, 0);
+
+    send(&mut context, &[merge_ix(&election.address, 0)], &[]).await.unwrap();
+
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!(stored.vote_counts, vec![0, 1]);
+    assert!(stored.all_shards_merged());
+}
+
+#[tokio::test]
+async fn test_full_shard_grows_for_more_voters() {
+    let mut context = start().await;
+    let election = sharded_election(&mut context, 1).await;
+    let shard = tally_shard_pda(&election.address, 0);
+    let voter_count = TallyShard::INITIAL_NULLIFIERS + 5;
+
+    let voters = register_voters(&mut context, &election.address, voter_count).await;
+
+    warp_to(&mut context, election.start).await;
+    let initial_len = context.banks_client.get_account(shard).await.unwrap().unwrap().data.len();
+    assert_eq!(initial_len, TallyShard::space(TallyShard::INITIAL_NULLIFIERS));
+
+    for batch in voters.chunks(4) {
+        let votes: Vec<_> = batch.iter().map(|voter| sharded_vote_ix(&election.address, &voter.pubkey(), 0, 1)).collect();
+        let signers: Vec<&Keypair> = batch.iter().collect();
+        send(&mut context, &votes, &signers).await.unwrap();
+    }
+
+    let stored: TallyShard = fetch(&mut context, shard).await;
+    assert_eq!(stored.used_nullifiers.len(), voter_count);
+    assert_eq!(stored.vote_counts, vec![0, voter_count as u64]);
+
+    let grown_len = context.banks_client.get_account(shard).await.unwrap().unwrap().data.len();
+    assert_eq!(grown_len, TallyShard::space(TallyShard::INITIAL_NULLIFIERS + TallyShard::NULLIFIER_GROWTH));
+
+    // Voters already in the grown shard are still caught
+    let result = send(&mut context, &[sharded_vote_ix(&election.address, &voters[0].pubkey(), 0, 0)], &[&voters[0]]).await;
+    assert_gov_error(result, GovError::AlreadyVoted);
+}
@@ -0,0 +1,566 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use common::*;
+use mpl_gov_micro::utils::merkle::create_token_balance_leaf;
+use mpl_gov_micro::{
+    accounts, instruction, Ballot, ElectionResult, GovError, ProposalOption, TokenSnapshot, TokenVoterWeight, VoterBallot,
+    VotingMethod,
+};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+
+fn token_weight_pda(election: &Pubkey, voter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"token_weight", election.as_ref(), voter.as_ref()], &mpl_gov_micro::ID).0
+}
+
+fn vault_pda(election: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"token_vault", election.as_ref()], &mpl_gov_micro::ID).0
+}
+
+async fn create_mint(context: &mut ProgramTestContext) -> Keypair {
+    let mint = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let payer = context.payer.pubkey();
+
+    let ixs = [
+        anchor_lang::solana_program::system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_mint(&spl_token::ID, &mint.pubkey(), &payer, None, 0).unwrap(),
+    ];
+    send(context, &ixs, &[&mint]).await.unwrap();
+    mint
+}
+
+/// Token account of `owner` holding `amount` freshly minted tokens
+async fn create_token_account(context: &mut ProgramTestContext, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+    let account = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let payer = context.payer.pubkey();
+
+    let mut ixs = vec![
+        anchor_lang::solana_program::system_instruction::create_account(
+            &payer,
+            &account.pubkey(),
+            rent.minimum_balance(spl_token::state::Account::LEN),
+            spl_token::state::Account::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_account(&spl_token::ID, &account.pubkey(), mint, owner).unwrap(),
+    ];
+    if amount > 0 {
+        ixs.push(spl_token::instruction::mint_to(&spl_token::ID, mint, &account.pubkey(), &payer, &[], amount).unwrap());
+    }
+    send(context, &ixs, &[&account]).await.unwrap();
+    account.pubkey()
+}
+
+async fn token_balance(context: &mut ProgramTestContext, account: Pubkey) -> u64 {
+    let account = context.banks_client.get_account(account).await.unwrap().unwrap();
+    spl_token::state::Account::unpack(&account.data).unwrap().amount
+}
+
+fn set_token_weighting_ix(election: &TestElection, mint: &Pubkey, snapshot: TokenSnapshot, max_voter_weight: u64) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::SetTokenWeighting {
+            election: election.address,
+            mint: *mint,
+            vault: vault_pda(&election.address),
+            authority: election.authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::SetTokenWeighting { snapshot, max_voter_weight }.data(),
+    }
+}
+
+fn deposit_ix(election: &Pubkey, mint: &Pubkey, voter: &Pubkey, voter_token_account: &Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::DepositTokens {
+            election: *election,
+            token_voter_weight: token_weight_pda(election, voter),
+            vault: vault_pda(election),
+            mint: *mint,
+            voter_token_account: *voter_token_account,
+            voter: *voter,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::DepositTokens { amount }.data(),
+    }
+}
+
+fn withdraw_ix(election: &Pubkey, voter: &Pubkey, voter_token_account: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::WithdrawTokens {
+            election: *election,
+            token_voter_weight: token_weight_pda(election, voter),
+            vault: Some(vault_pda(election)),
+            voter_token_account: Some(*voter_token_account),
+            voter: *voter,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::WithdrawTokens {}.data(),
+    }
+}
+
+/// withdraw_tokens closing a balance proven under a Merkle snapshot
+fn close_proven_weight_ix(election: &Pubkey, voter: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::WithdrawTokens {
+            election: *election,
+            token_voter_weight: token_weight_pda(election, voter),
+            vault: None,
+            voter_token_account: None,
+            voter: *voter,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::WithdrawTokens {}.data(),
+    }
+}
+
+/// retract_vote, with the voter's ballot record when revoting
+fn retract_ix(election: &Pubkey, voter: &Pubkey, revoting: bool) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::RetractVote {
+            election: *election,
+            nullifier_set: nullifier_set_pda(election),
+            voter_ballot: revoting.then(|| voter_ballot_pda(election, voter)),
+            token_voter_weight: token_weight_pda(election, voter),
+            voter: *voter,
+        }
+        .to_account_metas(None),
+        data: instruction::RetractVote {}.data(),
+    }
+}
+
+/// cast_vote with the voter's token weight (and ballot record when revoting)
+fn token_vote_ix(election: &Pubkey, voter: &Pubkey, choice: u8, revoting: bool) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::CastVote {
+            election: *election,
+            voter_registration: Some(registration_pda(election, voter)),
+            nullifier_set: nullifier_set_pda(election),
+            ranked_ballots: None,
+            pairwise_matrix: None,
+            voter_ballot: revoting.then(|| voter_ballot_pda(election, voter)),
+            token_voter_weight: Some(token_weight_pda(election, voter)),
+            voter: *voter,
+            attestation: None,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CastVote {
+            ballot: Ballot::Single { choice },
+            merkle_proof: vec![],
+            leaf_index: None,
+            registered_at: None,
+            voter_weight: None,
+            rationale: None,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn test_moved_escrow_balance_is_counted_once() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+    let mint = create_mint(&mut context).await.pubkey();
+
+    let alice = register_voter(&mut context, &election.address).await;
+    let bob = register_voter(&mut context, &election.address).await;
+    let alice_tokens = create_token_account(&mut context, &mint, &alice.pubkey(), 100).await;
+    let bob_tokens = create_token_account(&mut context, &mint, &bob.pubkey(), 0).await;
+
+    let setup = [
+        set_token_weighting_ix(&election, &mint, TokenSnapshot::Escrow, 0),
+        set_revoting_ix(&election),
+    ];
+    send(&mut context, &setup, &[&election.authority]).await.unwrap();
+
+    warp_to(&mut context, election.start).await;
+
+    let deposit = deposit_ix(&election.address, &mint, &alice.pubkey(), &alice_tokens, 100);
+    let vote = token_vote_ix(&election.address, &alice.pubkey(), 0, true);
+    send(&mut context, &[deposit, vote], &[&alice]).await.unwrap();
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![100, 0]);
+
+    // The counted tokens sit in the vault and cannot be moved while the vote counts
+    assert_eq!(token_balance(&mut context, alice_tokens).await, 0);
+    let withdraw = withdraw_ix(&election.address, &alice.pubkey(), &alice_tokens);
+    let result = send(&mut context, std::slice::from_ref(&withdraw), &[&alice]).await;
+    assert_gov_error(result, GovError::EscrowLocked);
+
+    // Moving them means taking the vote back first
+    send(&mut context, &[retract_ix(&election.address, &alice.pubkey(), true)], &[&alice]).await.unwrap();
+    send(&mut context, &[withdraw], &[&alice]).await.unwrap();
+
+    let transfer = spl_token::instruction::transfer(&spl_token::ID, &alice_tokens, &bob_tokens, &alice.pubkey(), &[], 100).unwrap();
+    send(&mut context, &[transfer], &[&alice]).await.unwrap();
+
+    let deposit = deposit_ix(&election.address, &mint, &bob.pubkey(), &bob_tokens, 100);
+    let vote = token_vote_ix(&election.address, &bob.pubkey(), 1, true);
+    send(&mut context, &[deposit, vote], &[&bob]).await.unwrap();
+
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!(stored.vote_counts, vec![0, 100]);
+    assert_eq!(stored.total_weight, 100);
+
+    // Without tokens in escrow Alice has nothing left to vote with
+    let vote = token_vote_ix(&election.address, &alice.pubkey(), 0, true);
+    assert!(send(&mut context, &[vote], &[&alice]).await.is_err());
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![0, 100]);
+}
+
+#[tokio::test]
+async fn test_snapshot_balance_belongs_to_its_owner() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+    let mint = create_mint(&mut context).await.pubkey();
+
+    let alice = register_voter(&mut context, &election.address).await;
+    let bob = register_voter(&mut context, &election.address).await;
+
+    let alice_leaf = create_token_balance_leaf(&alice.pubkey(), 500);
+    let bob_leaf = create_token_balance_leaf(&bob.pubkey(), 0);
+    let root = hashv(&[&alice_leaf, &bob_leaf]).to_bytes();
+
+    let snapshot = TokenSnapshot::Merkle { root, total_balance: 500 };
+    send(&mut context, &[set_token_weighting_ix(&election, &mint, snapshot, 0)], &[&election.authority]).await.unwrap();
+
+    let prove = |voter: &Pubkey| Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::ProveTokenBalance {
+            election: election.address,
+            token_voter_weight: token_weight_pda(&election.address, voter),
+            voter: *voter,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::ProveTokenBalance { balance: 500, merkle_proof: vec![bob_leaf], leaf_index: 0 }.data(),
+    };
+
+    // Bob cannot claim Alice's snapshot entry
+    let result = send(&mut context, &[prove(&bob.pubkey())], &[&bob]).await;
+    assert_gov_error(result, GovError::InvalidMerkleProof);
+
+    send(&mut context, &[prove(&alice.pubkey())], &[&alice]).await.unwrap();
+    let weight: TokenVoterWeight = fetch(&mut context, token_weight_pda(&election.address, &alice.pubkey())).await;
+    assert_eq!(weight.weight, 500);
+
+    warp_to(&mut context, election.start).await;
+    let vote = token_vote_ix(&election.address, &alice.pubkey(), 1, false);
+    send(&mut context, std::slice::from_ref(&vote), &[&alice]).await.unwrap();
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![0, 500]);
+
+    // A second vote with the same balance is refused
+    assert!(send(&mut context, &[vote], &[&alice]).await.is_err());
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![0, 500]);
+}
+
+#[tokio::test]
+async fn test_deposits_add_up_to_the_capped_weight() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+    let mint = create_mint(&mut context).await.pubkey();
+
+    let voter = register_voter(&mut context, &election.address).await;
+    let tokens = create_token_account(&mut context, &mint, &voter.pubkey(), 100).await;
+
+    let setup = set_token_weighting_ix(&election, &mint, TokenSnapshot::Escrow, 60);
+    send(&mut context, &[setup], &[&election.authority]).await.unwrap();
+
+    // Deposits are taken before the election starts
+    let deposit = deposit_ix(&election.address, &mint, &voter.pubkey(), &tokens, 40);
+    send(&mut context, &[deposit], &[&voter]).await.unwrap();
+    let weight: TokenVoterWeight = fetch(&mut context, token_weight_pda(&election.address, &voter.pubkey())).await;
+    assert_eq!((weight.balance, weight.weight), (40, 40));
+
+    warp_to(&mut context, election.start).await;
+    let deposit = deposit_ix(&election.address, &mint, &voter.pubkey(), &tokens, 40);
+    send(&mut context, &[deposit], &[&voter]).await.unwrap();
+    let weight: TokenVoterWeight = fetch(&mut context, token_weight_pda(&election.address, &voter.pubkey())).await;
+    assert_eq!((weight.balance, weight.weight), (80, 60));
+    assert_eq!(token_balance(&mut context, vault_pda(&election.address)).await, 80);
+
+    send(&mut context, &[token_vote_ix(&election.address, &voter.pubkey(), 1, false)], &[&voter]).await.unwrap();
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!(stored.vote_counts, vec![0, 60]);
+    assert_eq!(stored.total_weight, 60);
+
+    // The counted deposit can't grow either
+    let deposit = deposit_ix(&election.address, &mint, &voter.pubkey(), &tokens, 20);
+    let result = send(&mut context, &[deposit], &[&voter]).await;
+    assert_gov_error(result, GovError::EscrowLocked);
+}
+
+#[tokio::test]
+async fn test_deposit_stays_locked_until_the_end_without_revoting() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+    let mint = create_mint(&mut context).await.pubkey();
+
+    let voter = register_voter(&mut context, &election.address).await;
+    let tokens = create_token_account(&mut context, &mint, &voter.pubkey(), 100).await;
+
+    let setup = set_token_weighting_ix(&election, &mint, TokenSnapshot::Escrow, 0);
+    send(&mut context, &[setup], &[&election.authority]).await.unwrap();
+
+    // Without a counted vote the deposit can leave at any time
+    let deposit = deposit_ix(&election.address, &mint, &voter.pubkey(), &tokens, 100);
+    let withdraw = withdraw_ix(&election.address, &voter.pubkey(), &tokens);
+    send(&mut context, &[deposit], &[&voter]).await.unwrap();
+    send(&mut context, std::slice::from_ref(&withdraw), &[&voter]).await.unwrap();
+    assert_eq!(token_balance(&mut context, tokens).await, 100);
+    assert!(!account_exists(&mut context, token_weight_pda(&election.address, &voter.pubkey())).await);
+
+    warp_to(&mut context, election.start).await;
+    let deposit = deposit_ix(&election.address, &mint, &voter.pubkey(), &tokens, 100);
+    let vote = token_vote_ix(&election.address, &voter.pubkey(), 0, false);
+    send(&mut context, &[deposit, vote], &[&voter]).await.unwrap();
+
+    let result = send(&mut context, &[retract_ix(&election.address, &voter.pubkey(), false)], &[&voter]).await;
+    assert_gov_error(result, GovError::RevotingDisabled);
+
+    let result = send(&mut context, std::slice::from_ref(&withdraw), &[&voter]).await;
+    assert_gov_error(result, GovError::EscrowLocked);
+
+    warp_to(&mut context, election.end + 1).await;
+    send(&mut context, &[withdraw], &[&voter]).await.unwrap();
+    assert_eq!(token_balance(&mut context, tokens).await, 100);
+    assert_eq!(token_balance(&mut context, vault_pda(&election.address)).await, 0);
+
+    // The vote still counts after the tokens are back
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![100, 0]);
+}
+
+#[tokio::test]
+async fn test_recast_counts_the_current_token_weight() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+    let mint = create_mint(&mut context).await.pubkey();
+
+    let voter = register_voter(&mut context, &election.address).await;
+    let tokens = create_token_account(&mut context, &mint, &voter.pubkey(), 100).await;
+
+    let setup = [
+        set_token_weighting_ix(&election, &mint, TokenSnapshot::Escrow, 0),
+        set_revoting_ix(&election),
+    ];
+    send(&mut context, &setup, &[&election.authority]).await.unwrap();
+
+    warp_to(&mut context, election.start).await;
+    let deposit = deposit_ix(&election.address, &mint, &voter.pubkey(), &tokens, 40);
+    let vote = token_vote_ix(&election.address, &voter.pubkey(), 0, true);
+    send(&mut context, &[deposit, vote], &[&voter]).await.unwrap();
+
+    let recast = token_vote_ix(&election.address, &voter.pubkey(), 1, true);
+    send(&mut context, std::slice::from_ref(&recast), &[&voter]).await.unwrap();
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![0, 40]);
+
+    // Retracting unlocks the escrow, the topped-up weight counts from then on
+    let retract = retract_ix(&election.address, &voter.pubkey(), true);
+    let deposit = deposit_ix(&election.address, &mint, &voter.pubkey(), &tokens, 20);
+    let vote = token_vote_ix(&election.address, &voter.pubkey(), 0, true);
+    send(&mut context, &[retract, deposit, vote], &[&voter]).await.unwrap();
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![60, 0]);
+
+    send(&mut context, &[recast], &[&voter]).await.unwrap();
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!(stored.vote_counts, vec![0, 60]);
+    assert_eq!(stored.total_weight, 60);
+
+    let ballot: VoterBallot = fetch(&mut context, voter_ballot_pda(&election.address, &voter.pubkey())).await;
+    assert_eq!((ballot.weight, ballot.delegated_weight), (60, 0));
+}
+
+#[tokio::test]
+async fn test_escrow_configuration_is_final_once_tokens_are_deposited() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+    let mint = create_mint(&mut context).await.pubkey();
+
+    let voter = register_voter(&mut context, &election.address).await;
+    let tokens = create_token_account(&mut context, &mint, &voter.pubkey(), 100).await;
+    let snapshot = TokenSnapshot::Merkle { root: [1; 32], total_balance: 100 };
+
+    // Before any deposit the authority can still change its mind
+    let setup = [
+        set_token_weighting_ix(&election, &mint, snapshot, 0),
+        set_token_weighting_ix(&election, &mint, TokenSnapshot::Escrow, 0),
+    ];
+    send(&mut context, &setup, &[&election.authority]).await.unwrap();
+
+    let deposit = deposit_ix(&election.address, &mint, &voter.pubkey(), &tokens, 100);
+    send(&mut context, &[deposit], &[&voter]).await.unwrap();
+
+    // Switching away from escrow would strand the deposit in the vault
+    let reconfigure = set_token_weighting_ix(&election, &mint, snapshot, 0);
+    let result = send(&mut context, std::slice::from_ref(&reconfigure), &[&election.authority]).await;
+    assert_gov_error(result, GovError::TokenWeightingLocked);
+
+    let withdraw = withdraw_ix(&election.address, &voter.pubkey(), &tokens);
+    send(&mut context, &[withdraw], &[&voter]).await.unwrap();
+    assert_eq!(token_balance(&mut context, tokens).await, 100);
+
+    // The vault stays behind, and so does the configuration
+    let result = send(&mut context, &[reconfigure], &[&election.authority]).await;
+    assert_gov_error(result, GovError::TokenWeightingLocked);
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!(stored.token_weighting.unwrap().snapshot, TokenSnapshot::Escrow);
+}
+
+#[tokio::test]
+async fn test_switch_to_escrow_replaces_proven_balances() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+    let mint = create_mint(&mut context).await.pubkey();
+
+    let alice = register_voter(&mut context, &election.address).await;
+    let bob = register_voter(&mut context, &election.address).await;
+    let alice_tokens = create_token_account(&mut context, &mint, &alice.pubkey(), 40).await;
+
+    let alice_leaf = create_token_balance_leaf(&alice.pubkey(), 100);
+    let bob_leaf = create_token_balance_leaf(&bob.pubkey(), 50);
+    let root = hashv(&[&alice_leaf, &bob_leaf]).to_bytes();
+
+    let snapshot = TokenSnapshot::Merkle { root, total_balance: 150 };
+    send(&mut context, &[set_token_weighting_ix(&election, &mint, snapshot, 0)], &[&election.authority]).await.unwrap();
+
+    let prove = |voter: &Pubkey, balance: u64, sibling: [u8; 32], leaf_index: u32| Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::ProveTokenBalance {
+            election: election.address,
+            token_voter_weight: token_weight_pda(&election.address, voter),
+            voter: *voter,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::ProveTokenBalance { balance, merkle_proof: vec![sibling], leaf_index }.data(),
+    };
+    send(&mut context, &[prove(&alice.pubkey(), 100, bob_leaf, 0)], &[&alice]).await.unwrap();
+    send(&mut context, &[prove(&bob.pubkey(), 50, alice_leaf, 1)], &[&bob]).await.unwrap();
+
+    let switch = set_token_weighting_ix(&election, &mint, TokenSnapshot::Escrow, 0);
+    send(&mut context, &[switch], &[&election.authority]).await.unwrap();
+
+    // The first deposit replaces the proven balance instead of adding to it
+    let deposit = deposit_ix(&election.address, &mint, &alice.pubkey(), &alice_tokens, 40);
+    send(&mut context, &[deposit], &[&alice]).await.unwrap();
+    let weight: TokenVoterWeight = fetch(&mut context, token_weight_pda(&election.address, &alice.pubkey())).await;
+    assert_eq!((weight.balance, weight.weight, weight.token_account), (40, 40, alice_tokens));
+
+    let withdraw = withdraw_ix(&election.address, &alice.pubkey(), &alice_tokens);
+    send(&mut context, &[withdraw], &[&alice]).await.unwrap();
+    assert_eq!(token_balance(&mut context, alice_tokens).await, 40);
+    assert!(!account_exists(&mut context, token_weight_pda(&election.address, &alice.pubkey())).await);
+
+    // A proven balance that was never replaced can't vote, but can be closed
+    warp_to(&mut context, election.start).await;
+    let vote = token_vote_ix(&election.address, &bob.pubkey(), 0, false);
+    assert!(send(&mut context, &[vote], &[&bob]).await.is_err());
+
+    send(&mut context, &[close_proven_weight_ix(&election.address, &bob.pubkey())], &[&bob]).await.unwrap();
+    assert!(!account_exists(&mut context, token_weight_pda(&election.address, &bob.pubkey())).await);
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![0, 0]);
+}
+
+/// Proposal election weighted by a Merkle snapshot with one voter holding 100
+/// tokens, `total_balance` as declared by the authority
+async fn snapshot_proposal(context: &mut ProgramTestContext, total_balance: u64) -> (TestElection, Keypair) {
+    let election = create_election(context, 2).await;
+    let mint = create_mint(context).await.pubkey();
+
+    let voter = register_voter(context, &election.address).await;
+    let voter_leaf = create_token_balance_leaf(&voter.pubkey(), 100);
+    let other_leaf = create_token_balance_leaf(&Pubkey::new_unique(), total_balance - 100);
+    let root = hashv(&[&voter_leaf, &other_leaf]).to_bytes();
+
+    // 10% quorum
+    let proposal = VotingMethod::Proposal { quorum_bps: 1000, pass_threshold_bps: 5000, veto_threshold_bps: 0 };
+    let set_method = Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::SetVotingMethod {
+            election: election.address,
+            ranked_ballots: Pubkey::find_program_address(&[b"ranked_ballots", election.address.as_ref()], &mpl_gov_micro::ID).0,
+            pairwise_matrix: Pubkey::find_program_address(&[b"pairwise_matrix", election.address.as_ref()], &mpl_gov_micro::ID).0,
+            authority: election.authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::SetVotingMethod { voting_method: proposal, seats: 1 }.data(),
+    };
+    let snapshot = TokenSnapshot::Merkle { root, total_balance };
+    let setup = [set_method, set_token_weighting_ix(&election, &mint, snapshot, 0)];
+    send(context, &setup, &[&election.authority]).await.unwrap();
+
+    let prove = Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::ProveTokenBalance {
+            election: election.address,
+            token_voter_weight: token_weight_pda(&election.address, &voter.pubkey()),
+            voter: voter.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::ProveTokenBalance { balance: 100, merkle_proof: vec![other_leaf], leaf_index: 0 }.data(),
+    };
+    send(context, &[prove], &[&voter]).await.unwrap();
+
+    (election, voter)
+}
+
+/// Vote Yes with the voter's 100 tokens and tabulate once the election ends
+async fn vote_yes_and_tabulate(context: &mut ProgramTestContext, election: &TestElection, voter: &Keypair) -> ElectionResult {
+    warp_to(context, election.start).await;
+    let mut vote = token_vote_ix(&election.address, &voter.pubkey(), 0, false);
+    vote.data = instruction::CastVote {
+        ballot: Ballot::Proposal { option: ProposalOption::Yes },
+        merkle_proof: vec![],
+        leaf_index: None,
+        registered_at: None,
+        voter_weight: None,
+        rationale: None,
+    }
+    .data();
+    send(context, &[vote], &[voter]).await.unwrap();
+
+    warp_to(context, election.end + 1).await;
+    let payer = context.payer.pubkey();
+    send(context, &[tabulate_ix(&payer, &election.address)], &[]).await.unwrap();
+    fetch(context, result_pda(&election.address)).await
+}
+
+#[tokio::test]
+async fn test_snapshot_total_balance_sets_the_quorum() {
+    // 100 of 1000 tokens reach the 10% quorum exactly
+    let mut context = start().await;
+    let (election, voter) = snapshot_proposal(&mut context, 1000).await;
+    let result = vote_yes_and_tabulate(&mut context, &election, &voter).await;
+    assert_eq!(result.winners, vec![ProposalOption::Yes.index() as u8]);
+
+    // One more declared token and the same vote misses it
+    let mut context = start().await;
+    let (election, voter) = snapshot_proposal(&mut context, 1001).await;
+    let result = vote_yes_and_tabulate(&mut context, &election, &voter).await;
+    assert!(result.complete);
+    assert!(result.winners.is_empty());
+}
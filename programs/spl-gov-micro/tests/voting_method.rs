@@ -0,0 +1,123 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use common::*;
+use mpl_gov_micro::{accounts, instruction, Ballot, GovError, ProposalOption, VotingMethod};
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+
+const PROPOSAL: VotingMethod = VotingMethod::Proposal {
+    quorum_bps: 0,
+    pass_threshold_bps: 5000,
+    veto_threshold_bps: 0,
+};
+
+fn ranked_ballots_pda(election: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"ranked_ballots", election.as_ref()], &mpl_gov_micro::ID).0
+}
+
+fn pairwise_matrix_pda(election: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"pairwise_matrix", election.as_ref()], &mpl_gov_micro::ID).0
+}
+
+fn set_voting_method_ix(election: &TestElection, voting_method: VotingMethod) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::SetVotingMethod {
+            election: election.address,
+            ranked_ballots: ranked_ballots_pda(&election.address),
+            pairwise_matrix: pairwise_matrix_pda(&election.address),
+            authority: election.authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::SetVotingMethod { voting_method, seats: 1 }.data(),
+    }
+}
+
+fn init_pairwise_matrix_ix(payer: &Pubkey, election: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::InitPairwiseMatrix {
+            election: *election,
+            pairwise_matrix: pairwise_matrix_pda(election),
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::InitPairwiseMatrix {}.data(),
+    }
+}
+
+fn configure_tally_shards_ix(election: &TestElection, shard_count: u8) -> Instruction {
+    Instruction {
+        program_id: mpl_gov_micro::ID,
+        accounts: accounts::ConfigureTallyShards {
+            election: election.address,
+            authority: election.authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: instruction::ConfigureTallyShards { shard_count }.data(),
+    }
+}
+
+#[tokio::test]
+async fn test_proposal_replaces_the_candidates_once() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+
+    send(&mut context, &[set_voting_method_ix(&election, PROPOSAL)], &[&election.authority]).await.unwrap();
+    let stored = fetch_election(&mut context, election.address).await;
+    assert_eq!(stored.candidates, vec!["Yes", "No", "Abstain", "NoWithVeto"]);
+    assert_eq!(stored.vote_counts, vec![0; 4]);
+    assert_eq!(stored.score_ballots, vec![0; 4]);
+
+    // Adjusting the thresholds keeps the options
+    let stricter = VotingMethod::Proposal { quorum_bps: 1000, pass_threshold_bps: 6000, veto_threshold_bps: 3334 };
+    send(&mut context, &[set_voting_method_ix(&election, stricter)], &[&election.authority]).await.unwrap();
+
+    // The original candidates can't be restored
+    let result = send(&mut context, &[set_voting_method_ix(&election, VotingMethod::Plurality)], &[&election.authority]).await;
+    assert_gov_error(result, GovError::ProposalOptionsFixed);
+
+    let voter = register_voter(&mut context, &election.address).await;
+    warp_to(&mut context, election.start).await;
+    let vote = cast_vote_ix(
+        &election.address,
+        &election.address,
+        &voter.pubkey(),
+        Ballot::Proposal { option: ProposalOption::NoWithVeto },
+    );
+    send(&mut context, &[vote], &[&voter]).await.unwrap();
+    assert_eq!(fetch_election(&mut context, election.address).await.vote_counts, vec![0, 0, 0, 1]);
+}
+
+#[tokio::test]
+async fn test_proposal_switch_rejected_once_a_matrix_exists() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 3).await;
+
+    send(&mut context, &[set_voting_method_ix(&election, VotingMethod::Condorcet)], &[&election.authority]).await.unwrap();
+    let payer = context.payer.pubkey();
+    send(&mut context, &[init_pairwise_matrix_ix(&payer, &election.address)], &[]).await.unwrap();
+
+    let result = send(&mut context, &[set_voting_method_ix(&election, PROPOSAL)], &[&election.authority]).await;
+    assert_gov_error(result, GovError::CandidatesInUse);
+    assert_eq!(fetch_election(&mut context, election.address).await.candidates.len(), 3);
+}
+
+#[tokio::test]
+async fn test_proposal_switch_rejected_once_sharded() {
+    let mut context = start().await;
+    let election = create_election(&mut context, 2).await;
+
+    send(&mut context, &[configure_tally_shards_ix(&election, 2)], &[&election.authority]).await.unwrap();
+
+    let result = send(&mut context, &[set_voting_method_ix(&election, PROPOSAL)], &[&election.authority]).await;
+    assert_gov_error(result, GovError::CandidatesInUse);
+
+    // Without shards the switch goes through
+    send(&mut context, &[configure_tally_shards_ix(&election, 0)], &[&election.authority]).await.unwrap();
+    send(&mut context, &[set_voting_method_ix(&election, PROPOSAL)], &[&election.authority]).await.unwrap();
+}
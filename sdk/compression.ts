@@ -18,6 +18,7 @@ export interface CompressedVoterData {
   election: PublicKey;
   attestation: PublicKey;
   registeredAt: number;
+  weight: number;
 }
 
 /**
@@ -37,13 +38,15 @@ export interface MerkleProof {
  * @param election - Election public key
  * @param attestation - Attestation public key
  * @param registeredAt - Unix timestamp of registration
+ * @param weight - Voting weight committed in the leaf (defaults to 1)
  * @returns 32-byte leaf hash
  */
 export function createCompressedVoterLeaf(
   voter: PublicKey,
   election: PublicKey,
   attestation: PublicKey,
-  registeredAt: number
+  registeredAt: number,
+  weight: number = 1
 ): Buffer {
   // Serialize data in same format as Rust CompressedVoterData
   const voterBytes = voter.toBytes();
@@ -54,12 +57,17 @@ export function createCompressedVoterLeaf(
   const registeredAtBytes = Buffer.alloc(8);
   registeredAtBytes.writeBigInt64LE(BigInt(registeredAt));
 
+  // Convert weight to 8-byte little-endian
+  const weightBytes = Buffer.alloc(8);
+  weightBytes.writeBigUInt64LE(BigInt(weight));
+
   // Concatenate all bytes
   const data = Buffer.concat([
     voterBytes,
     electionBytes,
     attestationBytes,
     registeredAtBytes,
+    weightBytes,
   ]);
 
   // Hash with keccak256
@@ -244,7 +252,7 @@ export async function createCompressedElection(
   );
 
   return await program.methods
-    .createElection(candidates, startTime, endTime, true, maxVoters)
+    .createElection(candidates, startTime, endTime, true, maxVoters, { unixTimestamp: {} })
     .accounts({
       election: electionPda,
       authority: authority.publicKey,
@@ -272,7 +280,7 @@ export async function registerCompressedVoter(
   attestation: PublicKey
 ): Promise<string> {
   return await program.methods
-    .registerVoter()
+    .registerVoter(new anchor.BN(1))
     .accounts({
       election: election,
       voterRegistration: null, // Not needed in compression mode
@@ -297,6 +305,7 @@ export async function registerCompressedVoter(
  * @param leafIndex - Index of voter in merkle tree
  * @param registeredAt - Timestamp when voter registered
  * @param merkleProof - Merkle proof (empty array for MVP)
+ * @param weight - Voting weight committed in the voter's leaf (defaults to 1)
  * @returns Transaction signature
  */
 export async function castCompressedVote(
@@ -307,7 +316,8 @@ export async function castCompressedVote(
   choice: number,
   leafIndex: number,
   registeredAt: number,
-  merkleProof: Buffer[] = []
+  merkleProof: Buffer[] = [],
+  weight: number = 1
 ): Promise<string> {
   const [nullifierSetPda] = await deriveNullifierSetPda(
     election,
@@ -315,7 +325,14 @@ export async function castCompressedVote(
   );
 
   return await program.methods
-    .castVote(choice, merkleProof, leafIndex, new anchor.BN(registeredAt))
+    .castVote(
+      { single: { choice } },
+      merkleProof,
+      leafIndex,
+      new anchor.BN(registeredAt),
+      new anchor.BN(weight),
+      null
+    )
     .accounts({
       election: election,
       voterRegistration: null, // Not needed in compression mode
@@ -36,7 +36,8 @@ describe("zkCompression Tests", () => {
     voter: PublicKey,
     election: PublicKey,
     attestation: PublicKey,
-    registeredAt: number
+    registeredAt: number,
+    weight: number = 1
   ): Buffer => {
     // Serialize data in same format as Rust CompressedVoterData
     const voterBytes = voter.toBytes();
@@ -44,6 +45,8 @@ describe("zkCompression Tests", () => {
     const attestationBytes = attestation.toBytes();
     const registeredAtBytes = Buffer.alloc(8);
     registeredAtBytes.writeBigInt64LE(BigInt(registeredAt));
+    const weightBytes = Buffer.alloc(8);
+    weightBytes.writeBigUInt64LE(BigInt(weight));
 
     // Concatenate all bytes
     const data = Buffer.concat([
@@ -51,6 +54,7 @@ describe("zkCompression Tests", () => {
       electionBytes,
       attestationBytes,
       registeredAtBytes,
+      weightBytes,
     ]);
 
     // Hash with keccak256
@@ -109,7 +113,7 @@ describe("zkCompression Tests", () => {
 
       // Create with compression enabled
       await program.methods
-        .createElection(candidates, startTime, endTime, true, 10000) // use_compression=true, max_voters=10000
+        .createElection(candidates, startTime, endTime, true, 10000, { unixTimestamp: {} }) // use_compression=true, max_voters=10000
         .accounts({
           election: electionPda,
           authority: compressionAuthority.publicKey,
@@ -147,7 +151,7 @@ describe("zkCompression Tests", () => {
       const endTime = new anchor.BN(getCurrentTimestamp() + 86400);
 
       await program.methods
-        .createElection(candidates, startTime, endTime, false, 1000) // use_compression=false
+        .createElection(candidates, startTime, endTime, false, 1000, { unixTimestamp: {} }) // use_compression=false
         .accounts({
           election: electionPda,
           authority: legacyAuthority.publicKey,
@@ -200,7 +204,7 @@ describe("zkCompression Tests", () => {
       const endTime = new anchor.BN(getCurrentTimestamp() + 86400);
 
       await program.methods
-        .createElection(candidates, startTime, endTime, true, 10000)
+        .createElection(candidates, startTime, endTime, true, 10000, { unixTimestamp: {} })
         .accounts({
           election: compressionElection,
           authority: compressionAuthority.publicKey,
@@ -220,7 +224,7 @@ describe("zkCompression Tests", () => {
 
       // Register voter (no voter registration account needed in compression mode)
       await program.methods
-        .registerVoter()
+        .registerVoter(new anchor.BN(1))
         .accounts({
           election: compressionElection,
           voterRegistration: null, // Not needed in compression mode
@@ -269,7 +273,7 @@ describe("zkCompression Tests", () => {
 
       // Register voter 2
       await program.methods
-        .registerVoter()
+        .registerVoter(new anchor.BN(1))
         .accounts({
           election: compressionElection,
           voterRegistration: null,
@@ -284,7 +288,7 @@ describe("zkCompression Tests", () => {
 
       // Register voter 3
       await program.methods
-        .registerVoter()
+        .registerVoter(new anchor.BN(1))
         .accounts({
           election: compressionElection,
           voterRegistration: null,
@@ -338,7 +342,7 @@ describe("zkCompression Tests", () => {
       const endTime = new anchor.BN(getCurrentTimestamp() + 86400);
 
       await program.methods
-        .createElection(candidates, startTime, endTime, true, 10000)
+        .createElection(candidates, startTime, endTime, true, 10000, { unixTimestamp: {} })
         .accounts({
           election: compressionElection,
           authority: compressionAuthority.publicKey,
@@ -352,7 +356,7 @@ describe("zkCompression Tests", () => {
       // Register voter
       registeredAt = Math.floor(Date.now() / 1000);
       await program.methods
-        .registerVoter()
+        .registerVoter(new anchor.BN(1))
         .accounts({
           election: compressionElection,
           voterRegistration: null,
@@ -375,7 +379,7 @@ describe("zkCompression Tests", () => {
 
       // Cast vote with merkle proof
       await program.methods
-        .castVote(choice, merkleProof, leafIndex, new anchor.BN(registeredAt))
+        .castVote({ single: { choice } }, merkleProof, leafIndex, new anchor.BN(registeredAt), new anchor.BN(1), null)
         .accounts({
           election: compressionElection,
           voterRegistration: null, // Not needed in compression mode
@@ -403,7 +407,7 @@ describe("zkCompression Tests", () => {
 
       try {
         await program.methods
-          .castVote(1, [], 0, new anchor.BN(registeredAt))
+          .castVote({ single: { choice: 1 } }, [], 0, new anchor.BN(registeredAt), new anchor.BN(1), null)
           .accounts({
             election: compressionElection,
             voterRegistration: null,
@@ -89,7 +89,7 @@ describe("mpl-gov-micro", () => {
       const endTime = new anchor.BN(getCurrentTimestamp() + 86400); // +24 hours
 
       await program.methods
-        .createElection(candidates, startTime, endTime, false, 1000) // use_compression=false, max_voters=1000
+        .createElection(candidates, startTime, endTime, false, 1000, { unixTimestamp: {} }) // use_compression=false, max_voters=1000
         .accounts({
           election: electionPda,
           authority: authority.publicKey,
@@ -133,7 +133,7 @@ describe("mpl-gov-micro", () => {
 
       try {
         await program.methods
-          .createElection(tooManyCandidates, startTime, endTime, false, 1000)
+          .createElection(tooManyCandidates, startTime, endTime, false, 1000, { unixTimestamp: {} })
           .accounts({
             election: testElectionPda,
             authority: testAuthority.publicKey,
@@ -170,7 +170,7 @@ describe("mpl-gov-micro", () => {
 
       try {
         await program.methods
-          .createElection(candidates, startTime, endTime, false, 1000)
+          .createElection(candidates, startTime, endTime, false, 1000, { unixTimestamp: {} })
           .accounts({
             election: testElectionPda,
             authority: testAuthority.publicKey,
@@ -199,7 +199,7 @@ describe("mpl-gov-micro", () => {
       );
 
       await program.methods
-        .registerVoter()
+        .registerVoter(new anchor.BN(1))
         .accounts({
           election: electionPda,
           voterRegistration: voterRegPda,
@@ -231,7 +231,7 @@ describe("mpl-gov-micro", () => {
       );
 
       await program.methods
-        .registerVoter()
+        .registerVoter(new anchor.BN(1))
         .accounts({
           election: electionPda,
           voterRegistration: voterReg2Pda,
@@ -250,7 +250,7 @@ describe("mpl-gov-micro", () => {
       );
 
       await program.methods
-        .registerVoter()
+        .registerVoter(new anchor.BN(1))
         .accounts({
           election: electionPda,
           voterRegistration: voterReg3Pda,
@@ -292,7 +292,7 @@ describe("mpl-gov-micro", () => {
       const merkleProof = []; // Empty for MVP
 
       await program.methods
-        .castVote(choice, merkleProof, null, null) // leaf_index and registered_at are null for legacy mode
+        .castVote({ single: { choice } }, merkleProof, null, null, null, null) // proof fields are null for legacy mode
         .accounts({
           election: electionPda,
           voterRegistration: voterRegPda,
@@ -320,7 +320,7 @@ describe("mpl-gov-micro", () => {
 
       // Voter 2 votes for Bob (choice 1)
       await program.methods
-        .castVote(1, [], null, null)
+        .castVote({ single: { choice: 1 } }, [], null, null, null, null)
         .accounts({
           election: electionPda,
           voterRegistration: voterReg2Pda,
@@ -339,7 +339,7 @@ describe("mpl-gov-micro", () => {
 
       // Voter 3 votes for Charlie (choice 2)
       await program.methods
-        .castVote(2, [], null, null)
+        .castVote({ single: { choice: 2 } }, [], null, null, null, null)
         .accounts({
           election: electionPda,
           voterRegistration: voterReg3Pda,
@@ -370,7 +370,7 @@ describe("mpl-gov-micro", () => {
       try {
         // Try to vote again
         await program.methods
-          .castVote(1, [], null, null)
+          .castVote({ single: { choice: 1 } }, [], null, null, null, null)
           .accounts({
             election: electionPda,
             voterRegistration: voterRegPda,
@@ -407,7 +407,7 @@ describe("mpl-gov-micro", () => {
 
       // Register voter4
       await program.methods
-        .registerVoter()
+        .registerVoter(new anchor.BN(1))
         .accounts({
           election: electionPda,
           voterRegistration: voterReg4Pda,
@@ -425,7 +425,7 @@ describe("mpl-gov-micro", () => {
       try {
         // Try to vote for invalid candidate (index 3, but only 0-2 exist)
         await program.methods
-          .castVote(3, [], null, null)
+          .castVote({ single: { choice: 3 } }, [], null, null, null, null)
           .accounts({
             election: electionPda,
             voterRegistration: voterReg4Pda,
@@ -480,7 +480,7 @@ describe("mpl-gov-micro", () => {
 
       // Register voter5
       await program.methods
-        .registerVoter()
+        .registerVoter(new anchor.BN(1))
         .accounts({
           election: electionPda,
           voterRegistration: voterReg5Pda,
@@ -497,7 +497,7 @@ describe("mpl-gov-micro", () => {
 
       try {
         await program.methods
-          .castVote(0, [], null, null)
+          .castVote({ single: { choice: 0 } }, [], null, null, null, null)
           .accounts({
             election: electionPda,
             voterRegistration: voterReg5Pda,
@@ -537,7 +537,7 @@ describe("mpl-gov-micro", () => {
       const endTime = new anchor.BN(getCurrentTimestamp() + 3600);
 
       await program.methods
-        .createElection(candidates, startTime, endTime, false, 1000)
+        .createElection(candidates, startTime, endTime, false, 1000, { unixTimestamp: {} })
         .accounts({
           election: election2Pda,
           authority: authority2.publicKey,
@@ -566,7 +566,7 @@ describe("mpl-gov-micro", () => {
         );
 
         await program.methods
-          .registerVoter()
+          .registerVoter(new anchor.BN(1))
           .accounts({
             election: election2Pda,
             voterRegistration: voterRegPda,
@@ -592,7 +592,7 @@ describe("mpl-gov-micro", () => {
         const choice = i < 2 ? 0 : 1; // First 2 vote for Option A, last votes for Option B
 
         await program.methods
-          .castVote(choice, [], null, null)
+          .castVote({ single: { choice } }, [], null, null, null, null)
           .accounts({
             election: election2Pda,
             voterRegistration: voterRegPda,